@@ -22,6 +22,10 @@ impl AgentDetector for ClaudeDetector {
                 cpu_usage: p.cpu_usage,
                 cwd: p.cwd,
                 start_time: p.start_time,
+                config_dir: p.config_dir,
+                host: p.host,
+                memory: p.memory,
+                tree: p.tree,
             })
             .collect()
     }
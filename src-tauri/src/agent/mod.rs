@@ -11,6 +11,14 @@ pub struct AgentProcess {
     pub cwd: Option<std::path::PathBuf>,
     /// Process start time in seconds since UNIX epoch
     pub start_time: u64,
+    /// `CLAUDE_CONFIG_DIR` from the process environment, if set
+    pub config_dir: Option<std::path::PathBuf>,
+    /// Editor hosting the agent, for agents not running in a terminal
+    pub host: Option<crate::session::TerminalApp>,
+    /// Resident memory of the agent process itself, in bytes
+    pub memory: u64,
+    /// Aggregate usage and running commands of the process and its descendants
+    pub tree: crate::process::ProcessTree,
 }
 
 /// Trait for detecting and parsing agent sessions
@@ -41,8 +49,10 @@ pub fn get_all_sessions() -> SessionsResponse {
     let mut all_sessions = Vec::new();
 
     for detector in &detectors {
+        let scan_started = std::time::Instant::now();
         let processes = detector.find_processes();
         let sessions = detector.find_sessions(&processes);
+        crate::metrics::record_scan(detector.name(), scan_started.elapsed());
         log::info!("{}: found {} processes, {} sessions",
             detector.name(), processes.len(), sessions.len());
         all_sessions.extend(sessions);
@@ -56,6 +66,7 @@ pub fn get_all_sessions() -> SessionsResponse {
     let active_paths: HashSet<String> = all_sessions.iter().map(|s| s.project_path.clone()).collect();
     crate::session::git::cleanup_git_caches(&active_paths);
     crate::session::config::cleanup_links_cache(&active_paths);
+    crate::session::cwd_index::cleanup_cwd_index();
 
     // Sort by status priority first, then by most recent activity
     all_sessions.sort_by(|a, b| {
@@ -74,9 +85,12 @@ pub fn get_all_sessions() -> SessionsResponse {
         .count();
     let total_count = all_sessions.len();
 
-    SessionsResponse {
+    let response = SessionsResponse {
         sessions: all_sessions,
         total_count,
         waiting_count,
-    }
+    };
+    crate::metrics::record_poll(&response);
+    crate::api::publish_poll(&response);
+    response
 }
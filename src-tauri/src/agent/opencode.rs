@@ -95,8 +95,10 @@ fn find_opencode_processes() -> Vec<AgentProcess> {
         System::new_with_specifics(
             RefreshKind::new().with_processes(
                 ProcessRefreshKind::new()
+                    .with_cmd(UpdateKind::OnlyIfNotSet)
                     .with_cwd(UpdateKind::Always)
                     .with_cpu()
+                    .with_memory()
             )
         )
     });
@@ -105,10 +107,18 @@ fn find_opencode_processes() -> Vec<AgentProcess> {
     system.refresh_processes_specifics(
         ProcessesToUpdate::All,
         ProcessRefreshKind::new()
+            .with_cmd(UpdateKind::OnlyIfNotSet)
             .with_cwd(UpdateKind::Always)
-            .with_cpu(),
+            .with_cpu()
+            .with_memory(),
     );
 
+    let children = crate::process::children_map(system);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
     let mut processes = Vec::new();
 
     for (pid, process) in system.processes() {
@@ -128,6 +138,10 @@ fn find_opencode_processes() -> Vec<AgentProcess> {
                 cpu_usage: cpu,
                 cwd,
                 start_time: process.start_time(),
+                config_dir: None,
+                host: None,
+                memory: process.memory(),
+                tree: crate::process::process_tree(system, &children, *pid, now),
             });
         }
     }
@@ -275,7 +289,10 @@ fn get_latest_session_for_project(
     let (last_role, last_message_text, _last_message_time) = get_last_message(storage_path, &session.id);
 
     // Determine status
-    let status = if process.cpu_usage > 5.0 {
+    let cpu_threshold = config::get_status_thresholds(&session.directory).cpu_active_percent;
+    let status = if crate::process::is_paused(process.pid) {
+        SessionStatus::Paused
+    } else if process.cpu_usage > cpu_threshold {
         SessionStatus::Processing
     } else if last_role.as_deref() == Some("assistant") {
         SessionStatus::Waiting
@@ -325,7 +342,8 @@ fn get_latest_session_for_project(
     };
 
     let project_links = config::get_project_links(&actual_path);
-    let session_links = config::get_session_links(&actual_path, &session.id);
+    let mut session_links = config::get_session_links(&actual_path, &session.id);
+    crate::process::merge_dev_server_links(&mut session_links, &process.tree);
 
     Some(Session {
         id: session.id,
@@ -350,6 +368,10 @@ fn get_latest_session_for_project(
         context_window_percent: None,
         project_links,
         session_links,
+        profile: None,
+        tree_cpu_usage: process.tree.cpu_usage,
+        memory_bytes: process.tree.memory,
+        child_processes: process.tree.children.clone(),
     })
 }
 
@@ -490,7 +512,10 @@ fn get_global_session_for_directory(
     let (last_role, last_message_text, _last_message_time) = get_last_message(storage_path, &session.id);
 
     // Determine status
-    let status = if process.cpu_usage > 5.0 {
+    let cpu_threshold = config::get_status_thresholds(&session.directory).cpu_active_percent;
+    let status = if crate::process::is_paused(process.pid) {
+        SessionStatus::Paused
+    } else if process.cpu_usage > cpu_threshold {
         SessionStatus::Processing
     } else if last_role.as_deref() == Some("assistant") {
         SessionStatus::Waiting
@@ -534,7 +559,8 @@ fn get_global_session_for_directory(
     };
 
     let project_links = config::get_project_links(&session.directory);
-    let session_links = config::get_session_links(&session.directory, &session.id);
+    let mut session_links = config::get_session_links(&session.directory, &session.id);
+    crate::process::merge_dev_server_links(&mut session_links, &process.tree);
 
     Some(Session {
         id: session.id,
@@ -559,5 +585,9 @@ fn get_global_session_for_directory(
         context_window_percent: None,
         project_links,
         session_links,
+        profile: None,
+        tree_cpu_usage: process.tree.cpu_usage,
+        memory_bytes: process.tree.memory,
+        child_processes: process.tree.children.clone(),
     })
 }
@@ -0,0 +1,73 @@
+//! Reading the running app's sessions over its own API, for CLI commands that
+//! run every few seconds (tmux status lines) and shouldn't scan on each call.
+
+use log::debug;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use super::{token_path, HttpApiSettings};
+use crate::session::SessionsResponse;
+
+/// Time allowed to connect to the app, and for each read or write after
+const CLIENT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The running app's latest poll, or `None` if the HTTP API is disabled or
+/// the app isn't answering
+pub fn running_app_sessions() -> Option<SessionsResponse> {
+    let settings = crate::settings::get().http_api;
+    if !settings.enabled {
+        return None;
+    }
+    let token = settings
+        .token
+        .clone()
+        .filter(|t| !t.is_empty())
+        .or_else(|| std::fs::read_to_string(token_path()?).ok().map(|t| t.trim().to_string()))?;
+
+    match fetch_sessions(&local_address(&settings), &token) {
+        Ok(response) => Some(response),
+        Err(e) => {
+            debug!("App not reachable over the HTTP API: {}", e);
+            None
+        }
+    }
+}
+
+/// Where to reach an API bound to `settings.bind`: loopback when it listens
+/// on every interface
+fn local_address(settings: &HttpApiSettings) -> String {
+    match settings.bind.as_str() {
+        "0.0.0.0" => format!("127.0.0.1:{}", settings.port),
+        "::" | "[::]" => format!("[::1]:{}", settings.port),
+        bind => format!("{}:{}", bind, settings.port),
+    }
+}
+
+/// `GET /sessions` from the API at `address`
+pub fn fetch_sessions(address: &str, token: &str) -> Result<SessionsResponse, String> {
+    let addr: SocketAddr = address
+        .to_socket_addrs()
+        .map_err(|e| format!("Bad address {}: {}", address, e))?
+        .next()
+        .ok_or_else(|| format!("Bad address {}", address))?;
+    let mut stream = TcpStream::connect_timeout(&addr, CLIENT_TIMEOUT).map_err(|e| e.to_string())?;
+    let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(CLIENT_TIMEOUT));
+
+    let request = format!(
+        "GET /sessions HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\nConnection: close\r\n\r\n",
+        address, token
+    );
+    stream.write_all(request.as_bytes()).map_err(|e| e.to_string())?;
+
+    // Every response is `Connection: close`, so the body runs to the end
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw).map_err(|e| e.to_string())?;
+    let (head, body) = raw.split_once("\r\n\r\n").ok_or("Truncated response")?;
+    let status = head.lines().next().unwrap_or_default();
+    if !status.starts_with("HTTP/1.1 200") {
+        return Err(format!("{}: {}", status, body.trim()));
+    }
+    serde_json::from_str(body).map_err(|e| format!("Bad sessions JSON: {}", e))
+}
@@ -0,0 +1,85 @@
+//! The latest poll result and the fan-out of session events to `/events`
+//! subscribers and desktop notifications. Fed by `agent::get_all_sessions`,
+//! so the API never polls on its own while the app is polling: an extra
+//! poller would feed the status state machines twice as often and halve
+//! their debouncing.
+
+use once_cell::sync::Lazy;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::session::{diff_sessions, SessionEvent, SessionsResponse};
+
+/// The hub the app publishes its polls to
+static HUB: Lazy<Arc<EventHub>> = Lazy::new(|| Arc::new(EventHub::new()));
+
+/// The latest poll and everyone listening for what changes between polls
+#[derive(Default)]
+pub struct EventHub {
+    latest: Mutex<Option<(SessionsResponse, Instant)>>,
+    subscribers: Mutex<Vec<Sender<SessionEvent>>>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        EventHub::default()
+    }
+
+    /// Record a finished poll and send what changed since the previous one to
+    /// every subscriber
+    pub fn publish_poll(&self, response: &SessionsResponse) {
+        let previous = self
+            .latest
+            .lock()
+            .unwrap()
+            .replace((response.clone(), Instant::now()))
+            .map(|(r, _)| r.sessions);
+
+        let mut subscribers = self.subscribers.lock().unwrap();
+        if subscribers.is_empty() {
+            return;
+        }
+
+        // The first poll has nothing to compare against; every session would look new
+        let events = match previous {
+            Some(previous) => diff_sessions(&previous, &response.sessions),
+            None => return,
+        };
+        for event in events {
+            subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+        }
+    }
+
+    /// The last published poll and when it was taken
+    pub fn latest(&self) -> Option<(SessionsResponse, Instant)> {
+        self.latest.lock().unwrap().clone()
+    }
+
+    /// Receive every event published from now on. Dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> Receiver<SessionEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+}
+
+/// The app's hub
+pub fn hub() -> Arc<EventHub> {
+    HUB.clone()
+}
+
+/// `EventHub::publish_poll` on the app's hub
+pub fn publish_poll(response: &SessionsResponse) {
+    HUB.publish_poll(response)
+}
+
+/// `EventHub::latest` on the app's hub
+pub fn latest() -> Option<(SessionsResponse, Instant)> {
+    HUB.latest()
+}
+
+/// `EventHub::subscribe` on the app's hub
+pub fn subscribe() -> Receiver<SessionEvent> {
+    HUB.subscribe()
+}
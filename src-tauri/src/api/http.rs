@@ -0,0 +1,199 @@
+//! Just enough HTTP/1.1 for the local API: one request per connection,
+//! `Content-Length` bodies only, `Connection: close` on every response.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+
+/// Largest request head accepted (request line plus headers)
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    pub query: HashMap<String, String>,
+    /// Header names are lowercased
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+/// Read and parse a request from `stream`, including a `Content-Length` body
+pub fn read_request(stream: impl Read) -> Result<Request, String> {
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+
+    loop {
+        let mut line = String::new();
+        let read = (&mut reader)
+            .take((MAX_HEAD_BYTES - head.len()) as u64)
+            .read_line(&mut line)
+            .map_err(|e| format!("read failed: {}", e))?;
+        if read == 0 {
+            return Err("connection closed before end of headers".to_string());
+        }
+        if line == "\r\n" || line == "\n" {
+            break;
+        }
+        head.push_str(&line);
+        if head.len() >= MAX_HEAD_BYTES {
+            return Err("request head too large".to_string());
+        }
+    }
+
+    let mut request = parse_request(&head)?;
+    let length = match request.header("content-length") {
+        Some(value) => value.parse::<usize>().map_err(|_| format!("invalid Content-Length: {}", value))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(format!("request body too large ({} bytes)", length));
+    }
+
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).map_err(|e| format!("read failed: {}", e))?;
+    request.body = String::from_utf8(body).map_err(|_| "request body is not UTF-8".to_string())?;
+    Ok(request)
+}
+
+/// Parse a request line and headers
+pub fn parse_request(head: &str) -> Result<Request, String> {
+    let mut lines = head.lines();
+    let request_line = lines.next().ok_or("empty request")?;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or("missing method")?.to_string();
+    let target = parts.next().ok_or("missing request target")?;
+    if !parts.next().unwrap_or("").starts_with("HTTP/1.") {
+        return Err(format!("unsupported request line: {}", request_line));
+    }
+
+    let (path, query_string) = target.split_once('?').unwrap_or((target, ""));
+    let query = query_string
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Ok(Request {
+        method,
+        path: percent_decode(path),
+        query,
+        headers,
+        body: String::new(),
+    })
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match decoded {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(if bytes[i] == b'+' { b' ' } else { bytes[i] });
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(status: u16, body: String) -> Self {
+        Response {
+            status,
+            content_type: "application/json",
+            body,
+        }
+    }
+
+    /// `{"error": message}` with the given status
+    pub fn error(status: u16, message: &str) -> Self {
+        Response::json(status, serde_json::json!({ "error": message }).to_string())
+    }
+
+    /// 202 with no body, for JSON-RPC notifications
+    pub fn accepted() -> Self {
+        Response {
+            status: 202,
+            content_type: "text/plain",
+            body: String::new(),
+        }
+    }
+
+    pub fn no_content() -> Self {
+        Response {
+            status: 204,
+            content_type: "text/plain",
+            body: String::new(),
+        }
+    }
+
+    pub fn write_to(&self, mut stream: impl Write) -> std::io::Result<()> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len()
+        );
+        if self.status == 401 {
+            head.push_str("WWW-Authenticate: Bearer\r\n");
+        }
+        head.push_str("\r\n");
+
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(self.body.as_bytes())?;
+        stream.flush()
+    }
+}
+
+/// Response head opening a Server-Sent Events stream
+pub const SSE_HEAD: &str = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        _ => "",
+    }
+}
@@ -0,0 +1,297 @@
+//! Opt-in local HTTP API for other tools (scripts, Stream Deck, wall displays).
+//!
+//! Enabled with `"http_api": {"enabled": true}` in the global config and read
+//! once at startup. Binds to loopback unless `bind` says otherwise, and every
+//! request needs `Authorization: Bearer <token>`; the token is taken from the
+//! config or generated into `~/.agent-sessions/api-token`. `EventSource` can't
+//! send headers, so `?token=` is accepted as well.
+//!
+//! - `GET /sessions` - the `SessionsResponse` JSON
+//! - `GET /sessions/{id}` - one session
+//! - `POST /sessions/{id}/focus` - focus its terminal
+//! - `POST /sessions/{id}/kill` - kill it as configured in `kill`, returning the `KillReport`
+//! - `GET /events` - Server-Sent Events: `added`, `changed` (status transitions) and `removed`
+//! - `POST /mcp` - the MCP server over streamable HTTP (see `mcp`)
+//! - `GET /metrics` - Prometheus metrics (see `metrics`)
+
+mod client;
+mod events;
+mod http;
+
+pub use client::{fetch_sessions, running_app_sessions};
+pub use events::{hub, latest, publish_poll, subscribe, EventHub};
+pub use http::{parse_request, read_request, Request, Response};
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::mcp::McpServer;
+use crate::process::kill_process;
+use crate::session::Session;
+
+/// A poll older than this is refreshed before answering `/sessions`
+const MAX_POLL_AGE: Duration = Duration::from_secs(5);
+
+/// Comment sent on idle event streams so proxies and clients keep them open
+const SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// Time allowed to send a request head
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The `http_api` section of the global config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpApiSettings {
+    pub enabled: bool,
+    /// Address to listen on. Anything but loopback exposes sessions to the network.
+    pub bind: String,
+    pub port: u16,
+    /// Bearer token; generated and stored in `~/.agent-sessions/api-token` when unset
+    pub token: Option<String>,
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> Self {
+        HttpApiSettings {
+            enabled: false,
+            bind: "127.0.0.1".to_string(),
+            port: 7766,
+            token: None,
+        }
+    }
+}
+
+/// Start the API server in the background if enabled in the global config
+pub fn start() {
+    let settings = crate::settings::get().http_api;
+    if !settings.enabled {
+        return;
+    }
+
+    let token = match settings.token.clone().filter(|t| !t.is_empty()) {
+        Some(t) => t,
+        None => match load_or_create_token() {
+            Ok(t) => t,
+            Err(e) => {
+                warn!("Not starting HTTP API: {}", e);
+                return;
+            }
+        },
+    };
+
+    let address = format!("{}:{}", settings.bind, settings.port);
+    let listener = match TcpListener::bind(&address) {
+        Ok(l) => l,
+        Err(e) => {
+            warn!("Not starting HTTP API: failed to bind {}: {}", address, e);
+            return;
+        }
+    };
+    if !listener.local_addr().map(|a| a.ip().is_loopback()).unwrap_or(false) {
+        warn!("HTTP API is listening on non-loopback address {}", address);
+    }
+    info!("HTTP API listening on http://{}", address);
+
+    thread::spawn(move || serve(listener, token, hub()));
+}
+
+/// Accept connections forever, one thread each, answering from `hub`
+pub fn serve(listener: TcpListener, token: String, hub: Arc<EventHub>) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let token = token.clone();
+                let hub = hub.clone();
+                thread::spawn(move || handle_connection(stream, &token, &hub));
+            }
+            Err(e) => debug!("HTTP API accept failed: {}", e),
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, token: &str, hub: &EventHub) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+    let request = match read_request(&stream) {
+        Ok(r) => r,
+        Err(e) => {
+            debug!("Bad HTTP API request: {}", e);
+            let _ = Response::error(400, &e).write_to(&stream);
+            return;
+        }
+    };
+    debug!("HTTP API {} {}", request.method, request.path);
+
+    if !is_authorized(&request, token) {
+        let _ = Response::error(401, "missing or invalid bearer token").write_to(&stream);
+        return;
+    }
+
+    if request.method == "GET" && request.path == "/events" {
+        stream_events(&mut stream, hub);
+        return;
+    }
+
+    let _ = route(&request, hub).write_to(&stream);
+}
+
+/// Whether the request carries `token`, as a bearer header or `?token=`
+pub fn is_authorized(request: &Request, token: &str) -> bool {
+    let presented = request
+        .header("authorization")
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .or_else(|| request.query.get("token").map(String::as_str));
+
+    presented.map(|p| constant_time_eq(p.trim(), token)).unwrap_or(false)
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Answer every endpoint except `/events`
+pub fn route(request: &Request, hub: &EventHub) -> Response {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["sessions"]) => json_response(&sessions_from(hub)),
+        ("GET", ["sessions", id]) => match find(hub, id) {
+            Some(session) => json_response(&session),
+            None => Response::error(404, "session not found"),
+        },
+        ("POST", ["sessions", id, "focus"]) => match find(hub, id) {
+            Some(session) => {
+                let hint = crate::session::enum_name(&session.terminal_app);
+                match crate::terminal::focus_terminal_for_pid(session.pid, &hint, &session.project_path)
+                    .or_else(|_| crate::terminal::focus_terminal_by_path(&session.project_path))
+                {
+                    Ok(()) => Response::no_content(),
+                    Err(e) => Response::error(500, &e),
+                }
+            }
+            None => Response::error(404, "session not found"),
+        },
+        ("POST", ["sessions", id, "kill"]) => match find(hub, id) {
+            Some(session) => match kill_process(session.pid, &crate::settings::get().kill) {
+                Ok(report) => json_response(&report),
+                Err(e) => Response::error(500, &e),
+            },
+            None => Response::error(404, "session not found"),
+        },
+        ("GET", ["metrics"]) => Response {
+            status: 200,
+            content_type: "text/plain; version=0.0.4",
+            body: crate::metrics::render(&sessions_from(hub)),
+        },
+        ("POST", ["mcp"]) => {
+            // No server-initiated messages, so every reply fits a plain JSON response
+            match McpServer::new(Vec::new()).handle_text(&request.body) {
+                Some(reply) => Response::json(200, reply.to_string()),
+                None => Response::accepted(),
+            }
+        }
+        (_, ["sessions"]) | (_, ["sessions", _]) | (_, ["sessions", _, "focus" | "kill"]) | (_, ["events" | "metrics"]) | (_, ["mcp"]) => {
+            Response::error(405, "method not allowed")
+        }
+        _ => Response::error(404, "not found"),
+    }
+}
+
+/// The app's latest poll, or a fresh one if the app hasn't polled recently
+pub fn current_sessions() -> crate::session::SessionsResponse {
+    sessions_from(&hub())
+}
+
+/// `hub`'s latest poll, or a fresh one if it is missing or stale
+fn sessions_from(hub: &EventHub) -> crate::session::SessionsResponse {
+    match hub.latest() {
+        Some((response, at)) if at.elapsed() <= MAX_POLL_AGE => response,
+        _ => crate::agent::get_all_sessions(),
+    }
+}
+
+fn find(hub: &EventHub, id: &str) -> Option<Session> {
+    sessions_from(hub).sessions.into_iter().find(|s| s.id == id)
+}
+
+fn json_response<T: Serialize>(value: &T) -> Response {
+    match serde_json::to_string(value) {
+        Ok(body) => Response::json(200, body),
+        Err(e) => Response::error(500, &e.to_string()),
+    }
+}
+
+fn stream_events(stream: &mut TcpStream, hub: &EventHub) {
+    let events = hub.subscribe();
+    if stream.write_all(http::SSE_HEAD.as_bytes()).is_err() {
+        return;
+    }
+
+    loop {
+        let chunk = match events.recv_timeout(SSE_KEEPALIVE) {
+            Ok(event) => match serde_json::to_string(&event) {
+                Ok(data) => format!("event: {}\ndata: {}\n\n", event.name(), data),
+                Err(_) => continue,
+            },
+            Err(RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        if stream.write_all(chunk.as_bytes()).and_then(|_| stream.flush()).is_err() {
+            debug!("HTTP API event stream closed");
+            return;
+        }
+    }
+}
+
+fn token_path() -> Option<PathBuf> {
+    crate::settings::app_dir().map(|d| d.join("api-token"))
+}
+
+/// The stored API token, generating one on first use
+fn load_or_create_token() -> Result<String, String> {
+    let path = token_path().ok_or("no home directory")?;
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        let existing = existing.trim();
+        if !existing.is_empty() {
+            return Ok(existing.to_string());
+        }
+    }
+
+    let token = generate_token()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&path)
+        .and_then(|mut f| f.write_all(token.as_bytes()))
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+
+    info!("Generated HTTP API token in {:?}", path);
+    Ok(token)
+}
+
+/// 32 random bytes as hex
+pub fn generate_token() -> Result<String, String> {
+    use std::io::Read;
+
+    let mut bytes = [0u8; 32];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut bytes))
+        .map_err(|e| format!("Failed to read /dev/urandom: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
@@ -0,0 +1,9 @@
+//! Headless CLI over the session detection library; see `cli` for commands.
+
+fn main() {
+    // Detection logs a lot at info/warn level; keep the output clean unless asked
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("error")).init();
+
+    let args = std::env::args().skip(1).collect();
+    std::process::exit(tauri_temp_lib::cli::run(args))
+}
@@ -0,0 +1,473 @@
+//! The `agent-sessions` command-line interface.
+//!
+//! Uses the same detection as the app (`agent::get_all_sessions`) but never
+//! starts Tauri, so it works from scripts and over SSH. Sessions are named by
+//! any unique prefix of their id, or by PID.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::process::{kill_process, KillSettings};
+use crate::rules::RuleLogEntry;
+use crate::session::{self, diff_sessions, enum_name, Session, SessionEvent, StatusExplanation};
+use crate::tray_status::{render_title, TraySummary};
+use crate::webhooks::render_template;
+
+const USAGE: &str = "\
+Usage: agent-sessions <command> [options]
+
+Commands:
+  list [--json]                     Show running sessions
+  watch [--json] [--interval <s>]   Print sessions as they start, change status and end
+  focus <id>                        Bring the session's terminal to the front
+  kill <id> [--grace-ms <ms>] [--no-tree]
+                                    Terminate the session (SIGTERM, then SIGKILL)
+  explain <id> [--json]             Show how the session's status was decided
+  mcp                               Serve the MCP server on stdin/stdout
+  mcp register [<project>]          Add the MCP server to <project>/.mcp.json (default: .)
+  rules [--json] [--limit <n>]      Show recent automation rule firings, dry runs included
+  tmux status [--format <f>]        One-line summary for tmux status-right, e.g. ⏳2 ⚙3 ✓1
+  tmux pane <tty> [--format <f>]    Status of the session running on <tty>, e.g. #{pane_tty}
+
+<id> is a unique prefix of the session id, or the agent's PID.
+
+`tmux status` formats take the tray title placeholders ({waiting}, {working},
+{idle}, {total}, {longest_wait}, {lowest_context}, ...); a part in [brackets]
+is hidden while a placeholder in it is 0 or empty. `tmux pane` formats take
+{status}, {project}, {branch}, {title} and {message}.
+";
+
+/// Default `watch` poll interval, matching the app
+const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Rule firings shown by `rules` unless `--limit` says otherwise
+const DEFAULT_RULES_LIMIT: usize = 20;
+
+/// `tmux status` output unless `--format` says otherwise
+pub const DEFAULT_TMUX_STATUS_FORMAT: &str = "[⏳{waiting} ][⚙{working} ][✓{idle}]";
+
+/// `tmux pane` output unless `--format` says otherwise
+pub const DEFAULT_TMUX_PANE_FORMAT: &str = "{status}";
+
+/// Longest project name shown in the `list` table
+const MAX_PROJECT_WIDTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    List { json: bool },
+    Watch { json: bool, interval: Duration },
+    Focus { id: String },
+    Kill { id: String, grace_ms: Option<u64>, no_tree: bool },
+    Explain { id: String, json: bool },
+    Mcp,
+    McpRegister { project: Option<String> },
+    Rules { json: bool, limit: usize },
+    TmuxStatus { format: String },
+    TmuxPane { tty: String, format: String },
+    Help,
+}
+
+/// Run the CLI with `args` (excluding the program name). Returns the exit code.
+pub fn run(args: Vec<String>) -> i32 {
+    let command = match parse_args(&args) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("agent-sessions: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    match execute(command) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("agent-sessions: {}", e);
+            1
+        }
+    }
+}
+
+/// Parse command-line arguments into a `Command`
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut positional = Vec::new();
+    let mut json = false;
+    let mut no_tree = false;
+    let mut interval = None;
+    let mut grace_ms = None;
+    let mut limit = None;
+    let mut format = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--json" => json = true,
+            "--no-tree" => no_tree = true,
+            "--interval" => {
+                let value = iter.next().ok_or("--interval needs a number of seconds")?;
+                let secs: f64 = value.parse().map_err(|_| format!("invalid --interval: {}", value))?;
+                if !secs.is_finite() || secs <= 0.0 {
+                    return Err(format!("invalid --interval: {}", value));
+                }
+                interval = Some(Duration::from_secs_f64(secs));
+            }
+            "--grace-ms" => {
+                let value = iter.next().ok_or("--grace-ms needs a number of milliseconds")?;
+                grace_ms = Some(value.parse().map_err(|_| format!("invalid --grace-ms: {}", value))?);
+            }
+            "--limit" => {
+                let value = iter.next().ok_or("--limit needs a number")?;
+                limit = Some(value.parse().map_err(|_| format!("invalid --limit: {}", value))?);
+            }
+            "--format" => {
+                format = Some(iter.next().ok_or("--format needs a format string")?.clone());
+            }
+            flag if flag.starts_with('-') => return Err(format!("unknown option: {}", flag)),
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    let id = |positional: &[&str]| -> Result<String, String> {
+        match positional {
+            [_, id] => Ok(id.to_string()),
+            [name] => Err(format!("{} needs a session id", name)),
+            _ => Err(format!("unexpected argument: {}", positional[2])),
+        }
+    };
+
+    match positional.first().copied() {
+        None | Some("help") => Ok(Command::Help),
+        Some("list") if positional.len() == 1 => Ok(Command::List { json }),
+        Some("watch") if positional.len() == 1 => Ok(Command::Watch {
+            json,
+            interval: interval.unwrap_or(DEFAULT_WATCH_INTERVAL),
+        }),
+        Some("rules") if positional.len() == 1 => Ok(Command::Rules {
+            json,
+            limit: limit.unwrap_or(DEFAULT_RULES_LIMIT),
+        }),
+        Some("list") | Some("watch") | Some("rules") => Err(format!("unexpected argument: {}", positional[1])),
+        Some("focus") => Ok(Command::Focus { id: id(&positional)? }),
+        Some("kill") => Ok(Command::Kill {
+            id: id(&positional)?,
+            grace_ms,
+            no_tree,
+        }),
+        Some("explain") => Ok(Command::Explain {
+            id: id(&positional)?,
+            json,
+        }),
+        Some("mcp") => match positional.as_slice() {
+            [_] => Ok(Command::Mcp),
+            [_, "register"] => Ok(Command::McpRegister { project: None }),
+            [_, "register", project] => Ok(Command::McpRegister {
+                project: Some(project.to_string()),
+            }),
+            [_, other, ..] if *other != "register" => Err(format!("unknown mcp command: {}", other)),
+            _ => Err(format!("unexpected argument: {}", positional[3])),
+        },
+        Some("tmux") => match positional.as_slice() {
+            [_, "status"] => Ok(Command::TmuxStatus {
+                format: format.unwrap_or_else(|| DEFAULT_TMUX_STATUS_FORMAT.to_string()),
+            }),
+            [_, "pane", tty] => Ok(Command::TmuxPane {
+                tty: tty.to_string(),
+                format: format.unwrap_or_else(|| DEFAULT_TMUX_PANE_FORMAT.to_string()),
+            }),
+            [_, "pane"] => Err("tmux pane needs a tty".to_string()),
+            [] | [_] => Err("tmux needs a command: status or pane".to_string()),
+            [_, "status" | "pane", ..] => Err(format!("unexpected argument: {}", positional.last().unwrap())),
+            [_, other, ..] => Err(format!("unknown tmux command: {}", other)),
+        },
+        Some(other) => Err(format!("unknown command: {}", other)),
+    }
+}
+
+fn execute(command: Command) -> Result<(), String> {
+    match command {
+        Command::Help => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Command::List { json } => {
+            let sessions = session::get_sessions().sessions;
+            if json {
+                println!("{}", to_json(&sessions)?);
+            } else if sessions.is_empty() {
+                println!("No running sessions");
+            } else {
+                print!("{}", format_table(&sessions));
+            }
+            Ok(())
+        }
+        Command::Watch { json, interval } => watch(json, interval),
+        Command::Focus { id } => {
+            let sessions = session::get_sessions().sessions;
+            let session = find_session(&sessions, &id)?;
+            crate::terminal::focus_terminal_for_pid(session.pid, &enum_name(&session.terminal_app), &session.project_path)
+                .or_else(|_| crate::terminal::focus_terminal_by_path(&session.project_path))
+        }
+        Command::Kill { id, grace_ms, no_tree } => {
+            let sessions = session::get_sessions().sessions;
+            let session = find_session(&sessions, &id)?;
+
+            let defaults = crate::settings::get().kill;
+            let settings = KillSettings {
+                grace_period_ms: grace_ms.unwrap_or(defaults.grace_period_ms),
+                kill_tree: defaults.kill_tree && !no_tree,
+                ..defaults
+            };
+            let report = kill_process(session.pid, &settings)?;
+
+            println!("Killed {} ({})", short_id(&session.id), session.project_name);
+            if !report.terminated.is_empty() {
+                println!("  terminated:   {}", join_pids(&report.terminated));
+            }
+            if !report.force_killed.is_empty() {
+                println!("  force-killed: {}", join_pids(&report.force_killed));
+            }
+            Ok(())
+        }
+        Command::Explain { id, json } => {
+            let sessions = session::get_sessions().sessions;
+            let session = find_session(&sessions, &id)?;
+            let explanation = session::explain_status(&session.id)
+                .ok_or_else(|| format!("No status explanation for session {}", session.id))?;
+
+            if json {
+                println!("{}", to_json(&explanation)?);
+            } else {
+                print!("{}", format_explanation(&explanation));
+            }
+            Ok(())
+        }
+        Command::Mcp => crate::mcp::run_stdio(),
+        Command::McpRegister { project } => {
+            let project = match project {
+                Some(p) => std::path::PathBuf::from(p),
+                None => std::env::current_dir().map_err(|e| format!("No current directory: {}", e))?,
+            };
+            let command = std::env::current_exe().map_err(|e| format!("Can't locate agent-sessions: {}", e))?;
+            let path = crate::mcp::register_in_project(&project, &command)?;
+            println!("Registered {} in {}", crate::mcp::SERVER_NAME, path.display());
+            Ok(())
+        }
+        Command::Rules { json, limit } => {
+            let entries = crate::rules::log_path()
+                .map(|p| crate::jsonl_log::read::<RuleLogEntry>(&p))
+                .unwrap_or_default();
+            let recent = &entries[entries.len().saturating_sub(limit)..];
+
+            if json {
+                println!("{}", to_json(&recent)?);
+            } else if recent.is_empty() {
+                println!("No rules have fired");
+            } else {
+                for entry in recent {
+                    println!("{}", format_rule_entry(entry));
+                }
+            }
+            Ok(())
+        }
+        Command::TmuxStatus { format } => {
+            let sessions = tmux_sessions();
+            println!("{}", format_tmux_status(&sessions, &format, Utc::now()));
+            Ok(())
+        }
+        Command::TmuxPane { tty, format } => {
+            let sessions = tmux_sessions();
+            let ttys = crate::terminal::ttys_for_pids(&sessions.iter().map(|s| s.pid).collect::<Vec<_>>());
+            // Panes without an agent print nothing, leaving the status line blank
+            if let Some(session) = crate::terminal::session_on_tty(&sessions, &tty, |pid| ttys.get(&pid).cloned()) {
+                println!("{}", format_tmux_pane(session, &format));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn watch(json: bool, interval: Duration) -> Result<(), String> {
+    let mut previous: Vec<Session> = Vec::new();
+
+    loop {
+        let sessions = session::get_sessions().sessions;
+        for event in diff_sessions(&previous, &sessions) {
+            if json {
+                println!("{}", serde_json::to_string(&event).map_err(|e| e.to_string())?);
+            } else {
+                println!("{}  {}", chrono::Local::now().format("%H:%M:%S"), format_event(&event));
+            }
+        }
+        previous = sessions;
+        std::thread::sleep(interval);
+    }
+}
+
+/// Sessions for the tmux commands, which run every few seconds per pane: the
+/// running app's latest poll when its HTTP API is enabled, else a scan
+/// without the PR and ahead/behind lookups
+fn tmux_sessions() -> Vec<Session> {
+    if let Some(response) = crate::api::running_app_sessions() {
+        return response.sessions;
+    }
+    session::git::skip_branch_lookups();
+    session::get_sessions().sessions
+}
+
+/// The `tmux status` line: `format` filled like the tray title
+pub fn format_tmux_status(sessions: &[Session], format: &str, now: DateTime<Utc>) -> String {
+    render_title(format, &TraySummary::new(sessions, now)).trim().to_string()
+}
+
+/// The `tmux pane` line for `session`, on one line even when `{message}` spans several
+pub fn format_tmux_pane(session: &Session, format: &str) -> String {
+    render_template(format, session, None, None)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Resolve `query` to a session: an exact id, a unique id prefix, or a PID
+pub fn find_session<'a>(sessions: &'a [Session], query: &str) -> Result<&'a Session, String> {
+    if let Some(session) = sessions.iter().find(|s| s.id == query) {
+        return Ok(session);
+    }
+    if let Ok(pid) = query.parse::<u32>() {
+        if let Some(session) = sessions.iter().find(|s| s.pid == pid) {
+            return Ok(session);
+        }
+    }
+
+    let matches: Vec<&Session> = sessions.iter().filter(|s| s.id.starts_with(query)).collect();
+    match matches.as_slice() {
+        [session] => Ok(session),
+        [] => Err(format!("No session matching {}", query)),
+        _ => Err(format!(
+            "{} is ambiguous: {}",
+            query,
+            matches.iter().map(|s| short_id(&s.id)).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+/// Aligned table of sessions for `list`
+pub fn format_table(sessions: &[Session]) -> String {
+    let header = ["ID", "STATUS", "PID", "CPU", "PROJECT", "BRANCH"];
+    let rows: Vec<[String; 6]> = sessions
+        .iter()
+        .map(|s| {
+            [
+                short_id(&s.id).to_string(),
+                enum_name(&s.status),
+                s.pid.to_string(),
+                format!("{:.0}%", s.tree_cpu_usage.max(s.cpu_usage)),
+                truncate(&s.project_name, MAX_PROJECT_WIDTH),
+                s.git_branch.clone().unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[&str]| -> String {
+        let line = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        format!("{}\n", line.trim_end())
+    };
+
+    let mut out = format_row(&header);
+    for row in &rows {
+        out.push_str(&format_row(&row.each_ref().map(String::as_str)));
+    }
+    out
+}
+
+/// One line describing a `watch` event
+pub fn format_event(event: &SessionEvent) -> String {
+    match event {
+        SessionEvent::Added { session } => format!(
+            "+ {}  {}  {}",
+            short_id(&session.id),
+            session.project_name,
+            enum_name(&session.status)
+        ),
+        SessionEvent::Changed { from, session } => format!(
+            "~ {}  {}  {} -> {}",
+            short_id(&session.id),
+            session.project_name,
+            enum_name(from),
+            enum_name(&session.status)
+        ),
+        SessionEvent::Removed { id, project } => format!("- {}  {}  ended", short_id(id), project),
+    }
+}
+
+/// One line describing a rule firing for `rules`
+pub fn format_rule_entry(entry: &RuleLogEntry) -> String {
+    let at = chrono::DateTime::parse_from_rfc3339(&entry.at)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|_| entry.at.clone());
+    let outcome = match (&entry.error, entry.dry_run) {
+        (_, true) => "would".to_string(),
+        (Some(e), false) => format!("failed ({}):", e),
+        (None, false) => "ran".to_string(),
+    };
+    format!(
+        "{}  {}  {} {} on {}  {}",
+        at,
+        entry.rule,
+        outcome,
+        entry.action,
+        short_id(&entry.session_id),
+        entry.project
+    )
+}
+
+fn format_explanation(explanation: &StatusExplanation) -> String {
+    let mut out = format!(
+        "Session {}\n  file:    {}\n  status:  {} (content {}, before debouncing {})\n",
+        explanation.session_id,
+        explanation.jsonl_path,
+        enum_name(&explanation.final_status),
+        enum_name(&explanation.content_status),
+        enum_name(&explanation.raw_status),
+    );
+    if let Some(age) = explanation.file_age_secs {
+        out.push_str(&format!("  quiet:   {:.1}s\n", age));
+    }
+    out.push_str(&format!("  cpu:     {:.1}%\n", explanation.cpu_usage));
+    out.push_str("  decision:\n");
+    for step in &explanation.decision_path {
+        out.push_str(&format!("    - {}\n", step));
+    }
+    out
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        text.chars().take(max - 1).collect::<String>() + "…"
+    } else {
+        text.to_string()
+    }
+}
+
+fn join_pids(pids: &[u32]) -> String {
+    pids.iter().map(u32::to_string).collect::<Vec<_>>().join(" ")
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize: {}", e))
+}
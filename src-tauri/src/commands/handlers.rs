@@ -1,14 +1,17 @@
 use tauri::Manager;
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 use std::collections::{HashMap, HashSet};
 use std::sync::{LazyLock, Mutex};
 
-use crate::session::{get_sessions, convert_path_to_dir_name, SessionsResponse, ProjectLink};
+use crate::session::{get_sessions, convert_path_to_dir_name, Session, SessionsResponse, ProjectLink, StatusExplanation};
 use crate::session::config;
+use crate::session::profiles;
+use crate::process::{kill_process, pause_process, resume_process, KillReport};
+use crate::notifications::Notification;
+use crate::rules::RuleHost;
 use crate::terminal;
 
-// Store current shortcut for unregistration
-static CURRENT_SHORTCUT: Mutex<Option<Shortcut>> = Mutex::new(None);
+// Session last focused from the app, for the kill-focused hotkey
+static LAST_FOCUSED_PID: Mutex<Option<u32>> = Mutex::new(None);
 
 // Track last opened URL per project path to avoid duplicate tabs
 static CHROME_URLS: LazyLock<Mutex<HashMap<String, String>>> =
@@ -83,11 +86,7 @@ fn cursor_window_exists_for_project(project_path: &str) -> bool {
 /// Read the configured Chrome profile from ~/.agent-sessions/config.json.
 /// Returns Some("Profile 1") etc. if configured, None to use isolated mode.
 fn read_chrome_profile() -> Option<String> {
-    let home = dirs::home_dir()?;
-    let config_path = home.join(".agent-sessions").join("config.json");
-    let content = std::fs::read_to_string(config_path).ok()?;
-    let config: serde_json::Value = serde_json::from_str(&content).ok()?;
-    config.get("chrome_profile")?.as_str().map(|s| s.to_string())
+    crate::settings::get().chrome_profile
 }
 
 /// Find the main Chrome browser PID (not an agent-sessions isolated instance).
@@ -198,11 +197,21 @@ pub fn get_all_sessions() -> SessionsResponse {
     get_sessions()
 }
 
+/// Explain how a session's current status was derived (diagnostics panel).
+/// Reads what the last poll recorded: scanning again would feed the status
+/// state machines an extra observation and could change the status itself.
+#[tauri::command]
+pub fn explain_session_status(session_id: String) -> Result<StatusExplanation, String> {
+    crate::session::explain_status(&session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))
+}
+
 /// Focus the terminal containing a specific session and auto-layout windows
 #[tauri::command]
 pub fn focus_session(pid: u32, project_path: String, terminal_app: String) -> Result<(), String> {
     terminal::focus_terminal_for_pid(pid, &terminal_app, &project_path)
         .or_else(|_| terminal::focus_terminal_by_path(&project_path))?;
+    *LAST_FOCUSED_PID.lock().unwrap() = Some(pid);
 
     // Focus companion Cursor if tracked and still open (best-effort)
     if load_cursor_projects().contains(&project_path) {
@@ -222,75 +231,100 @@ pub fn focus_session(pid: u32, project_path: String, terminal_app: String) -> Re
     Ok(())
 }
 
-/// Update the tray icon title with session counts
-#[tauri::command]
-pub fn update_tray_title(app: tauri::AppHandle, total: usize, waiting: usize) -> Result<(), String> {
-    let title = if waiting > 0 {
-        format!("{} ({} waiting)", total, waiting)
-    } else if total > 0 {
-        format!("{}", total)
-    } else {
-        String::new()
-    };
-
-    if let Some(tray) = app.tray_by_id("main-tray") {
-        tray.set_title(Some(&title))
-            .map_err(|e| format!("Failed to set tray title: {}", e))?;
+/// Notifications whose thread may block waiting for a click at once. Past
+/// this, notifications are shown without click-to-focus, so threads for
+/// notifications nobody dismisses can't pile up.
+const MAX_CLICK_WAITS: usize = 4;
+
+/// Threads currently blocked waiting for a notification click
+static CLICK_WAITS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Show a session notification and focus the session when it is clicked.
+/// Uses the notification center on macOS and D-Bus on Linux; elsewhere
+/// notifications are only logged.
+pub fn show_notification(app: &tauri::AppHandle, notification: &Notification) {
+    use std::sync::atomic::Ordering;
+
+    let identifier = app.config().identifier.clone();
+    let notification = notification.clone();
+    let wait_for_click = CLICK_WAITS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < MAX_CLICK_WAITS).then_some(n + 1))
+        .is_ok();
+    if !wait_for_click {
+        log::debug!("{} notifications already wait for a click; not waiting for this one", MAX_CLICK_WAITS);
     }
-    Ok(())
+
+    // Waiting blocks until the notification is dismissed or clicked
+    std::thread::spawn(move || {
+        let shown = show_native_notification(&identifier, &notification, wait_for_click);
+        if wait_for_click {
+            CLICK_WAITS.fetch_sub(1, Ordering::SeqCst);
+        }
+        match shown {
+            Ok(true) => {
+                if let Err(e) = focus_session(notification.pid, notification.project_path, notification.terminal_app) {
+                    log::warn!("Failed to focus session {} from notification: {}", notification.session_id, e);
+                }
+            }
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to show notification: {}", e),
+        }
+    });
 }
 
-/// Register a global keyboard shortcut to toggle the window
-#[tauri::command]
-pub fn register_shortcut(app: tauri::AppHandle, shortcut: String) -> Result<(), String> {
-    // Unregister any existing shortcut first
-    if let Some(old_shortcut) = CURRENT_SHORTCUT.lock().unwrap().take() {
-        let _ = app.global_shortcut().unregister(old_shortcut);
-    }
+#[cfg(target_os = "macos")]
+fn show_native_notification(identifier: &str, notification: &Notification, wait_for_click: bool) -> Result<bool, String> {
+    use mac_notification_sys::{set_application, NotificationResponse};
 
-    // Parse the shortcut string
-    let parsed_shortcut: Shortcut = shortcut.parse()
-        .map_err(|e| format!("Invalid shortcut format: {}", e))?;
+    static SET_APPLICATION: std::sync::Once = std::sync::Once::new();
+    SET_APPLICATION.call_once(|| {
+        if let Err(e) = set_application(identifier) {
+            log::warn!("Failed to set notification application: {}", e);
+        }
+    });
+
+    let response = mac_notification_sys::Notification::new()
+        .title(&notification.title)
+        .message(&notification.body)
+        .wait_for_click(wait_for_click)
+        .send()
+        .map_err(|e| e.to_string())?;
+    Ok(matches!(response, NotificationResponse::Click))
+}
 
-    // Register the new shortcut - toggle window visibility
-    app.global_shortcut()
-        .on_shortcut(parsed_shortcut.clone(), move |app, _shortcut, event| {
-            // Only handle key press, not release
-            if event.state != tauri_plugin_global_shortcut::ShortcutState::Pressed {
-                return;
-            }
+#[cfg(target_os = "linux")]
+fn show_native_notification(_identifier: &str, notification: &Notification, wait_for_click: bool) -> Result<bool, String> {
+    crate::notifications::show_with_notify_send(notification, wait_for_click)
+}
 
-            if let Some(window) = app.get_webview_window("main") {
-                let is_visible = window.is_visible().unwrap_or(false);
-                let is_focused = window.is_focused().unwrap_or(false);
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn show_native_notification(_identifier: &str, notification: &Notification, _wait_for_click: bool) -> Result<bool, String> {
+    log::info!("Notification: {} - {}", notification.title, notification.body);
+    Ok(false)
+}
 
-                // If window is visible AND focused, hide it
-                // Otherwise, show and focus it
-                if is_visible && is_focused {
-                    let _ = window.hide();
-                } else {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
-        })
-        .map_err(|e| format!("Failed to register shortcut: {}", e))?;
+/// Runs automation rule actions that need the app
+pub struct AppRuleHost(pub tauri::AppHandle);
 
-    // Store the shortcut for later unregistration
-    *CURRENT_SHORTCUT.lock().unwrap() = Some(parsed_shortcut);
+impl RuleHost for AppRuleHost {
+    fn notify(&self, notification: &Notification) {
+        show_notification(&self.0, notification);
+    }
 
-    Ok(())
+    fn focus(&self, session: &Session) -> Result<(), String> {
+        focus_session_of(session)
+    }
 }
 
-/// Unregister the current global keyboard shortcut
-#[tauri::command]
-pub fn unregister_shortcut(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(shortcut) = CURRENT_SHORTCUT.lock().unwrap().take() {
-        app.global_shortcut()
-            .unregister(shortcut)
-            .map_err(|e| format!("Failed to unregister shortcut: {}", e))?;
-    }
-    Ok(())
+/// PID of the session last focused through `focus_session`
+pub fn last_focused_pid() -> Option<u32> {
+    *LAST_FOCUSED_PID.lock().unwrap()
+}
+
+/// `focus_session` for a session from a scan
+pub fn focus_session_of(session: &Session) -> Result<(), String> {
+    let terminal_app = crate::session::enum_name(&session.terminal_app);
+    focus_session(session.pid, session.project_path.clone(), terminal_app)
 }
 
 /// Find the main Chrome browser process PID for a given --user-data-dir.
@@ -775,6 +809,7 @@ fn terminal_process_name(terminal_app: &str) -> Option<&'static str> {
         "warp" => Some("Warp"),
         "iterm2" => Some("iTerm2"),
         "terminal" => Some("Terminal"),
+        "zed" => Some("Zed"),
         _ => None, // tmux, unknown — skip positioning
     }
 }
@@ -1086,25 +1121,27 @@ pub fn open_in_cursor(project_path: String) -> Result<(), String> {
     Ok(())
 }
 
-/// Kill an agent process by PID
-#[tauri::command]
-pub fn kill_session(pid: u32) -> Result<(), String> {
-    use std::process::Command;
+/// Kill an agent process by PID: SIGINT/SIGTERM first, SIGKILL after the
+/// grace period, and its descendants too unless disabled in the `kill` config.
+/// Runs off the main thread since it waits out the grace period.
+#[tauri::command(async)]
+pub fn kill_session(pid: u32) -> Result<KillReport, String> {
+    kill_process(pid, &crate::settings::get().kill)
+}
 
-    // Use SIGKILL (-9) to forcefully terminate the process
-    // SIGTERM often doesn't work for agent processes with child processes
-    let output = Command::new("kill")
-        .arg("-9")
-        .arg(pid.to_string())
-        .output()
-        .map_err(|e| format!("Failed to execute kill command: {}", e))?;
+/// Freeze an agent and everything it spawned with SIGSTOP. The session shows
+/// as Paused, including after an app restart, until resumed.
+/// Returns the PIDs stopped.
+#[tauri::command]
+pub fn pause_session(pid: u32) -> Result<Vec<u32>, String> {
+    pause_process(pid)
+}
 
-    if output.status.success() {
-        Ok(())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to kill process {}: {}", pid, stderr))
-    }
+/// Continue a paused agent and its descendants with SIGCONT.
+/// Returns the PIDs continued.
+#[tauri::command]
+pub fn resume_session_process(pid: u32) -> Result<Vec<u32>, String> {
+    resume_process(pid)
 }
 
 /// Save project links to `.agent-sessions.json`
@@ -1120,12 +1157,15 @@ pub fn save_session_links(
     session_id: String,
     links: Vec<ProjectLink>,
 ) -> Result<(), String> {
+    // Links generated from detected dev servers are recomputed every poll
+    let links: Vec<ProjectLink> = links.into_iter().filter(|l| !l.detected).collect();
     config::set_session_links(&project_path, &session_id, links.clone())?;
     let _ = update_claude_md_session_links(&project_path, &session_id, &links);
     Ok(())
 }
 
-/// Manage a section in `~/.claude/projects/<dir>/CLAUDE.md` with session links.
+/// Manage a section in `<config dir>/projects/<dir>/CLAUDE.md` with session links,
+/// for every Claude profile that has transcripts for the project.
 /// The section is delimited by HTML comment markers so it can be updated without
 /// disturbing user content.
 fn update_claude_md_session_links(
@@ -1133,17 +1173,22 @@ fn update_claude_md_session_links(
     _session_id: &str,
     links: &[ProjectLink],
 ) -> Result<(), String> {
-    let home = dirs::home_dir().ok_or_else(|| "No home dir".to_string())?;
     let dir_name = convert_path_to_dir_name(project_path);
-    let projects_dir = home.join(".claude").join("projects").join(&dir_name);
 
-    if !projects_dir.exists() {
-        // Claude projects dir doesn't exist for this project — skip silently
-        return Ok(());
+    for profile in profiles::known_profiles() {
+        let projects_dir = profile.projects_dir().join(&dir_name);
+        if !projects_dir.exists() {
+            // Claude projects dir doesn't exist for this project — skip silently
+            continue;
+        }
+        write_claude_md_session_links(&projects_dir.join("CLAUDE.md"), links)?;
     }
 
-    let claude_md = projects_dir.join("CLAUDE.md");
-    let existing = std::fs::read_to_string(&claude_md).unwrap_or_default();
+    Ok(())
+}
+
+fn write_claude_md_session_links(claude_md: &std::path::Path, links: &[ProjectLink]) -> Result<(), String> {
+    let existing = std::fs::read_to_string(claude_md).unwrap_or_default();
 
     let start_marker = "<!-- agent-sessions:links-start -->";
     let end_marker = "<!-- agent-sessions:links-end -->";
@@ -1203,7 +1248,7 @@ fn update_claude_md_session_links(
         }
     };
 
-    std::fs::write(&claude_md, new_content.trim_end().to_owned() + "\n")
+    std::fs::write(claude_md, new_content.trim_end().to_owned() + "\n")
         .map_err(|e| format!("Failed to write CLAUDE.md: {}", e))?;
 
     log::info!("Updated CLAUDE.md session links in {:?}", claude_md);
@@ -1211,8 +1256,8 @@ fn update_claude_md_session_links(
 }
 
 /// Kill a session and close its attached companion windows (Chrome, Cursor).
-#[tauri::command]
-pub fn kill_session_and_companions(pid: u32, project_path: String) -> Result<(), String> {
+#[tauri::command(async)]
+pub fn kill_session_and_companions(pid: u32, project_path: String) -> Result<KillReport, String> {
     // Close Chrome companion
     let is_real_profile = read_chrome_profile().is_some();
     if is_real_profile {
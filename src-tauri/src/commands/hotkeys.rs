@@ -0,0 +1,153 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::hotkeys::{self, HotkeyAction, HotkeyBindings, HotkeyConflict, KillConfirm, KILL_CONFIRM_WINDOW};
+use crate::notifications::Notification;
+use crate::process::kill_process;
+
+use super::{display_order, focus_next_waiting, focus_session_of, last_focused_pid, show_notification};
+
+// Registered shortcuts, for unregistration
+static REGISTERED: Mutex<Vec<Shortcut>> = Mutex::new(Vec::new());
+
+// Bindings left out by the last registration, including the one at startup
+// before the settings window has been opened
+static CONFLICTS: Mutex<Vec<HotkeyConflict>> = Mutex::new(Vec::new());
+
+static KILL_CONFIRM: Mutex<KillConfirm> = Mutex::new(KillConfirm::new());
+
+// Session ids in the order the window lists them
+static DISPLAY_ORDER: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Record the order the window lists sessions in, which positions in the
+/// focus-by-position hotkeys and the tray menu follow
+#[tauri::command]
+pub fn set_session_order(ids: Vec<String>) {
+    *DISPLAY_ORDER.lock().unwrap() = ids;
+}
+
+pub fn display_order() -> Vec<String> {
+    DISPLAY_ORDER.lock().unwrap().clone()
+}
+
+/// Replace all global shortcuts with `bindings`. Bindings that are invalid,
+/// bound twice or taken by another application are left out and returned.
+#[tauri::command]
+pub fn register_shortcuts(app: AppHandle, bindings: HotkeyBindings) -> Result<Vec<HotkeyConflict>, String> {
+    unregister_shortcut(app.clone())?;
+
+    let (planned, mut conflicts) = hotkeys::plan(&bindings);
+    let mut registered = Vec::new();
+
+    for (shortcut, action) in planned {
+        let parsed: Shortcut = match shortcut.parse() {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                conflicts.push(HotkeyConflict::new(action, &shortcut, format!("Invalid shortcut format: {}", e)));
+                continue;
+            }
+        };
+
+        let result = app.global_shortcut().on_shortcut(parsed, move |app, _shortcut, event| {
+            // Only handle key press, not release
+            if event.state == ShortcutState::Pressed {
+                run_hotkey(app, action);
+            }
+        });
+        match result {
+            Ok(()) => registered.push(parsed),
+            Err(e) => conflicts.push(HotkeyConflict::new(
+                action,
+                &shortcut,
+                format!("In use by another application ({})", e),
+            )),
+        }
+    }
+
+    *REGISTERED.lock().unwrap() = registered;
+    *CONFLICTS.lock().unwrap() = conflicts.clone();
+    Ok(conflicts)
+}
+
+/// Bindings the last `register_shortcuts` call left out
+#[tauri::command]
+pub fn get_hotkey_conflicts() -> Vec<HotkeyConflict> {
+    CONFLICTS.lock().unwrap().clone()
+}
+
+/// Unregister all global keyboard shortcuts
+#[tauri::command]
+pub fn unregister_shortcut(app: AppHandle) -> Result<(), String> {
+    CONFLICTS.lock().unwrap().clear();
+    let shortcuts: Vec<Shortcut> = REGISTERED.lock().unwrap().drain(..).collect();
+    if shortcuts.is_empty() {
+        return Ok(());
+    }
+    app.global_shortcut()
+        .unregister_multiple(shortcuts)
+        .map_err(|e| format!("Failed to unregister shortcut: {}", e))
+}
+
+fn run_hotkey(app: &AppHandle, action: HotkeyAction) {
+    if action == HotkeyAction::ToggleWindow {
+        toggle_main_window(app);
+        return;
+    }
+
+    // Focusing and killing can take seconds
+    let app = app.clone();
+    thread::spawn(move || {
+        let sessions = hotkeys::in_display_order(&crate::api::current_sessions().sessions, &display_order());
+        let result = match action {
+            HotkeyAction::ToggleWindow => Ok(()),
+            HotkeyAction::NextWaiting => focus_next_waiting(&sessions),
+            HotkeyAction::FocusSession(n) => match hotkeys::session_at(&sessions, n) {
+                Some(session) => focus_session_of(session),
+                None => Ok(()),
+            },
+            HotkeyAction::KillFocused => {
+                let session = last_focused_pid().and_then(|pid| sessions.iter().find(|s| s.pid == pid));
+                match session {
+                    Some(session) if KILL_CONFIRM.lock().unwrap().press(&session.id, Instant::now()) => {
+                        kill_process(session.pid, &crate::settings::get().kill).map(|_| ())
+                    }
+                    Some(session) => {
+                        let notification = Notification {
+                            title: format!("Kill {}?", session.project_name),
+                            body: format!(
+                                "Press the shortcut again within {} seconds to kill this session.",
+                                KILL_CONFIRM_WINDOW.as_secs()
+                            ),
+                            ..Notification::for_session(session)
+                        };
+                        show_notification(&app, &notification);
+                        Ok(())
+                    }
+                    None => Ok(()),
+                }
+            }
+        };
+
+        if let Err(e) = result {
+            log::warn!("Hotkey \"{}\" failed: {}", action.label(), e);
+        }
+    });
+}
+
+/// Hide the window when it is visible and focused, otherwise show and focus it
+fn toggle_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let is_visible = window.is_visible().unwrap_or(false);
+        let is_focused = window.is_focused().unwrap_or(false);
+
+        if is_visible && is_focused {
+            let _ = window.hide();
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+}
@@ -1,3 +1,7 @@
 mod handlers;
+mod hotkeys;
+mod tray;
 
 pub use handlers::*;
+pub use hotkeys::*;
+pub use tray::*;
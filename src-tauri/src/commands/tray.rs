@@ -0,0 +1,214 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use tauri::image::Image;
+use tauri::tray::TrayIcon;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::hotkeys::{in_display_order, KillConfirm, KILL_CONFIRM_WINDOW};
+use crate::notifications::Notification;
+use crate::process::kill_process;
+use crate::session::{Session, StatusDwell};
+use crate::tray_menu::{menu_model, next_waiting, TrayAction, TrayMenuModel, JUMP_TO_WAITING_ID};
+use crate::tray_status::{self, render_title, TraySummary};
+
+use super::{display_order, focus_session_of, show_notification};
+
+/// How often the tray is compared against the latest scan
+const TRAY_REFRESH: Duration = Duration::from_secs(2);
+
+// Session focused by the last jump to the next waiting session
+static LAST_JUMP: Mutex<Option<String>> = Mutex::new(None);
+
+// Kill picked once from the tray, waiting for a second pick to confirm it
+static KILL_CONFIRM: Mutex<KillConfirm> = Mutex::new(KillConfirm::new());
+
+/// Build the tray menu: the jump entry, one submenu per session, then
+/// "Show Window" and "Quit"
+pub fn build_tray_menu(app: &AppHandle, model: &TrayMenuModel) -> tauri::Result<Menu<Wry>> {
+    let jump_label = match model.waiting_count {
+        0 => "No waiting sessions".to_string(),
+        n => format!("Jump to next waiting session ({})", n),
+    };
+    let jump = MenuItemBuilder::with_id(JUMP_TO_WAITING_ID, jump_label)
+        .enabled(model.waiting_count > 0)
+        .build(app)?;
+
+    let mut menu = MenuBuilder::new(app).item(&jump).separator();
+
+    if model.sessions.is_empty() {
+        let none = MenuItemBuilder::with_id("no-sessions", "No running sessions")
+            .enabled(false)
+            .build(app)?;
+        menu = menu.item(&none);
+    }
+    for item in &model.sessions {
+        let id = &item.session_id;
+        let focus = MenuItemBuilder::with_id(TrayAction::Focus(id.clone()).menu_id(), "Focus").build(app)?;
+        let kill = MenuItemBuilder::with_id(TrayAction::Kill(id.clone()).menu_id(), "Kill").build(app)?;
+        let open_pr = MenuItemBuilder::with_id(TrayAction::OpenPr(id.clone()).menu_id(), "Open PR")
+            .enabled(item.pr_url.is_some())
+            .build(app)?;
+        let open_github = MenuItemBuilder::with_id(TrayAction::OpenGithub(id.clone()).menu_id(), "Open on GitHub")
+            .enabled(item.github_url.is_some())
+            .build(app)?;
+
+        let submenu = SubmenuBuilder::new(app, &item.label)
+            .item(&focus)
+            .item(&kill)
+            .separator()
+            .item(&open_pr)
+            .item(&open_github)
+            .build()?;
+        menu = menu.item(&submenu);
+    }
+
+    let show_item = MenuItemBuilder::with_id("show", "Show Window").build(app)?;
+    let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
+    menu.separator().item(&show_item).separator().item(&quit_item).build()
+}
+
+/// Keep the tray title, icon and menu in step with the latest scan, touching
+/// each only when what it shows changes
+pub fn start_tray_updates(app: AppHandle) {
+    thread::spawn(move || {
+        let mut dwell = StatusDwell::new();
+        let mut last_scan: Option<Instant> = None;
+        let mut shown_title: Option<String> = None;
+        let mut shown_icon: Option<tray_status::TrayIcon> = None;
+        let mut shown_menu = TrayMenuModel::default();
+
+        loop {
+            thread::sleep(TRAY_REFRESH);
+
+            let (response, taken_at) = match crate::api::latest() {
+                Some(latest) => latest,
+                None => continue,
+            };
+            let tray = match app.tray_by_id("main-tray") {
+                Some(tray) => tray,
+                None => continue,
+            };
+            if last_scan != Some(taken_at) {
+                dwell.update(&response.sessions, taken_at);
+                last_scan = Some(taken_at);
+            }
+            let now = Instant::now();
+            let settings = crate::settings::get().tray;
+            let summary = TraySummary::new(&response.sessions, chrono::Utc::now());
+
+            let title = render_title(&settings.title, &summary);
+            if shown_title.as_ref() != Some(&title) {
+                if let Err(e) = tray.set_title(Some(&title)) {
+                    log::warn!("Failed to set tray title: {}", e);
+                }
+                shown_title = Some(title);
+            }
+
+            let icon = if settings.status_icon {
+                tray_status::TrayIcon::for_summary(&summary)
+            } else {
+                tray_status::TrayIcon::Idle
+            };
+            if shown_icon != Some(icon) {
+                if let Err(e) = set_tray_icon(&tray, icon) {
+                    log::warn!("Failed to set tray icon: {}", e);
+                }
+                shown_icon = Some(icon);
+            }
+
+            let sessions = in_display_order(&response.sessions, &display_order());
+            let model = menu_model(&sessions, &dwell, now);
+            if model == shown_menu {
+                continue;
+            }
+            match build_tray_menu(&app, &model) {
+                Ok(menu) => {
+                    if let Err(e) = tray.set_menu(Some(menu)) {
+                        log::warn!("Failed to update tray menu: {}", e);
+                    }
+                }
+                Err(e) => log::warn!("Failed to build tray menu: {}", e),
+            }
+            shown_menu = model;
+        }
+    });
+}
+
+fn set_tray_icon(tray: &TrayIcon<Wry>, icon: tray_status::TrayIcon) -> tauri::Result<()> {
+    let bytes: &[u8] = match icon {
+        tray_status::TrayIcon::Idle => include_bytes!("../../icons/tray-icon.png"),
+        tray_status::TrayIcon::Working => include_bytes!("../../icons/tray-icon-working.png"),
+        tray_status::TrayIcon::Attention => include_bytes!("../../icons/tray-icon-attention.png"),
+        tray_status::TrayIcon::Error => include_bytes!("../../icons/tray-icon-error.png"),
+    };
+    tray.set_icon(Some(Image::from_bytes(bytes)?))?;
+    // Only the plain icon follows the menu bar's color; the variants keep theirs
+    tray.set_icon_as_template(icon == tray_status::TrayIcon::Idle)
+}
+
+/// Focus the waiting session after the one this last jumped to, so repeated
+/// jumps (from the tray or the hotkey) cycle through them
+pub fn focus_next_waiting(sessions: &[Session]) -> Result<(), String> {
+    let mut last = LAST_JUMP.lock().unwrap();
+    match next_waiting(sessions, last.as_deref()) {
+        Some(session) => {
+            *last = Some(session.id.clone());
+            focus_session_of(session)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Run a session action picked from the tray menu. Focusing and killing can
+/// take seconds, so this returns at once. Kill only asks the first time; picking
+/// it again for the same session within `KILL_CONFIRM_WINDOW` kills.
+pub fn handle_tray_action(app: &AppHandle, action: TrayAction) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let sessions = crate::api::current_sessions().sessions;
+        let find = |id: &str| sessions.iter().find(|s| s.id == id);
+
+        let result = match &action {
+            TrayAction::JumpToWaiting => focus_next_waiting(&sessions),
+            TrayAction::Focus(id) => match find(id) {
+                Some(session) => focus_session_of(session),
+                None => Err(format!("session {} is gone", id)),
+            },
+            TrayAction::Kill(id) => match find(id) {
+                Some(session) if KILL_CONFIRM.lock().unwrap().press(&session.id, Instant::now()) => {
+                    kill_process(session.pid, &crate::settings::get().kill).map(|_| ())
+                }
+                Some(session) => {
+                    let notification = Notification {
+                        title: format!("Kill {}?", session.project_name),
+                        body: format!(
+                            "Pick Kill again within {} seconds to kill this session.",
+                            KILL_CONFIRM_WINDOW.as_secs()
+                        ),
+                        ..Notification::for_session(session)
+                    };
+                    show_notification(&app, &notification);
+                    Ok(())
+                }
+                None => Err(format!("session {} is gone", id)),
+            },
+            TrayAction::OpenPr(id) | TrayAction::OpenGithub(id) => {
+                let url = find(id).and_then(|session| match action {
+                    TrayAction::OpenPr(_) => session.pr_info.as_ref().map(|pr| pr.url.clone()),
+                    _ => session.github_url.clone(),
+                });
+                match url {
+                    Some(url) => app.opener().open_url(url, None::<&str>).map_err(|e| e.to_string()),
+                    None => Err(format!("no URL for session {}", id)),
+                }
+            }
+        };
+
+        if let Err(e) = result {
+            log::warn!("Tray action {:?} failed: {}", action, e);
+        }
+    });
+}
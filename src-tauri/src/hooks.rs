@@ -0,0 +1,408 @@
+//! User shell hooks run on session lifecycle events: a lighter alternative to
+//! automation rules.
+//!
+//! Configured under `hooks` in the global config:
+//!
+//! ```json
+//! "hooks": {
+//!   "on_session_start": "echo started >> ~/agents.log",
+//!   "on_status_change": "...",
+//!   "on_waiting": "say \"$(basename $AGENT_PROJECT_PATH) needs you\"",
+//!   "on_session_end": "...",
+//!   "timeout_secs": 30
+//! }
+//! ```
+//!
+//! Driven by the same committed status transitions as the app's session
+//! events, so a change to Waiting runs both `on_status_change` and
+//! `on_waiting`. Commands run with `sh -c` and `AGENT_SESSION_ID`,
+//! `AGENT_PROJECT_PATH`, `AGENT_STATUS` ("ended" for `on_session_end`),
+//! `AGENT_PREVIOUS_STATUS`, `AGENT_BRANCH` and `AGENT_HOOK` set. A hook
+//! still running after `timeout_secs` is killed with its children. Each hook
+//! runs in its own process group, and background jobs still holding its output
+//! pipes at the timeout are killed with the group. Every run, with its output,
+//! is appended to `~/.agent-sessions/hooks.log`.
+
+use chrono::Utc;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::jsonl_log;
+use crate::process::{kill_process, KillSettings};
+use crate::session::{enum_name, Session, SessionEvent, SessionStatus};
+
+/// Output kept per stream and run, in bytes
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// How often a running hook is checked for having exited
+const WAIT_POLL: Duration = Duration::from_millis(50);
+
+/// Time the output pipes get to close once the hook's process group is killed
+const PIPE_GRACE: Duration = Duration::from_secs(1);
+
+/// Output read from one of a hook's pipes so far
+type Captured = Arc<Mutex<Vec<u8>>>;
+
+/// The `hooks` section of the global config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookSettings {
+    pub on_session_start: Option<String>,
+    pub on_status_change: Option<String>,
+    pub on_waiting: Option<String>,
+    pub on_session_end: Option<String>,
+    /// Seconds a hook may run before it is killed
+    pub timeout_secs: u64,
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        HookSettings {
+            on_session_start: None,
+            on_status_change: None,
+            on_waiting: None,
+            on_session_end: None,
+            timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hook {
+    OnSessionStart,
+    OnStatusChange,
+    OnWaiting,
+    OnSessionEnd,
+}
+
+impl Hook {
+    /// The config key, e.g. "on_waiting"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::OnSessionStart => "on_session_start",
+            Hook::OnStatusChange => "on_status_change",
+            Hook::OnWaiting => "on_waiting",
+            Hook::OnSessionEnd => "on_session_end",
+        }
+    }
+}
+
+impl HookSettings {
+    fn command(&self, hook: Hook) -> Option<&str> {
+        let command = match hook {
+            Hook::OnSessionStart => &self.on_session_start,
+            Hook::OnStatusChange => &self.on_status_change,
+            Hook::OnWaiting => &self.on_waiting,
+            Hook::OnSessionEnd => &self.on_session_end,
+        };
+        command.as_deref().map(str::trim).filter(|c| !c.is_empty())
+    }
+
+    /// The configured hooks an event triggers, with their commands
+    pub fn hooks_for(&self, event: &SessionEvent) -> Vec<(Hook, &str)> {
+        let hooks: &[Hook] = match event {
+            SessionEvent::Added { .. } => &[Hook::OnSessionStart],
+            SessionEvent::Changed { session, .. } if session.status == SessionStatus::Waiting => {
+                &[Hook::OnStatusChange, Hook::OnWaiting]
+            }
+            SessionEvent::Changed { .. } => &[Hook::OnStatusChange],
+            SessionEvent::Removed { .. } => &[Hook::OnSessionEnd],
+        };
+        hooks
+            .iter()
+            .filter_map(|hook| self.command(*hook).map(|command| (*hook, command)))
+            .collect()
+    }
+}
+
+/// What a hook is told about its session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HookContext {
+    pub session_id: String,
+    pub project_path: String,
+    pub status: String,
+    pub previous_status: String,
+    pub branch: String,
+}
+
+impl HookContext {
+    /// Context for an event. A session that ended is described by its last
+    /// known state from `known`, with status "ended".
+    pub fn for_event(event: &SessionEvent, known: &HashMap<String, Session>) -> Self {
+        match event {
+            SessionEvent::Added { session } => Self::from_session(session, None),
+            SessionEvent::Changed { from, session } => Self::from_session(session, Some(from)),
+            SessionEvent::Removed { id, .. } => match known.get(id) {
+                Some(session) => HookContext {
+                    status: "ended".to_string(),
+                    previous_status: enum_name(&session.status),
+                    ..Self::from_session(session, None)
+                },
+                None => HookContext {
+                    session_id: id.clone(),
+                    status: "ended".to_string(),
+                    ..HookContext::default()
+                },
+            },
+        }
+    }
+
+    fn from_session(session: &Session, previous: Option<&SessionStatus>) -> Self {
+        HookContext {
+            session_id: session.id.clone(),
+            project_path: session.project_path.clone(),
+            status: enum_name(&session.status),
+            previous_status: previous.map(enum_name).unwrap_or_default(),
+            branch: session.git_branch.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// One hook run, as written to the hooks log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRun {
+    /// RFC 3339 timestamp of the start
+    pub at: String,
+    pub hook: Hook,
+    pub session_id: String,
+    pub command: String,
+    /// `None` when killed or not started
+    pub exit_code: Option<i32>,
+    /// The hook, or a background job holding its output, outlived the timeout
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub stdout: String,
+    pub stderr: String,
+    /// Why the command couldn't be started
+    pub error: Option<String>,
+}
+
+/// Run `command` for `hook`, waiting for it at most `timeout`
+pub fn run_hook(hook: Hook, command: &str, context: &HookContext, timeout: Duration) -> HookRun {
+    let started = Instant::now();
+    let mut run = HookRun {
+        at: Utc::now().to_rfc3339(),
+        hook,
+        session_id: context.session_id.clone(),
+        command: command.to_string(),
+        exit_code: None,
+        timed_out: false,
+        duration_ms: 0,
+        stdout: String::new(),
+        stderr: String::new(),
+        error: None,
+    };
+
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(command)
+        .env("AGENT_SESSION_ID", &context.session_id)
+        .env("AGENT_PROJECT_PATH", &context.project_path)
+        .env("AGENT_STATUS", &context.status)
+        .env("AGENT_PREVIOUS_STATUS", &context.previous_status)
+        .env("AGENT_BRANCH", &context.branch)
+        .env("AGENT_HOOK", hook.name())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if Path::new(&context.project_path).is_dir() {
+        cmd.current_dir(&context.project_path);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let mut child = match cmd.spawn() {
+        Ok(c) => c,
+        Err(e) => {
+            run.error = Some(format!("Failed to run sh: {}", e));
+            return run;
+        }
+    };
+
+    // Drain both pipes while waiting so a chatty hook can't block on a full pipe
+    let (done_tx, done_rx) = mpsc::channel();
+    let mut open_pipes = 0;
+    let stdout = child.stdout.take().map(|s| {
+        open_pipes += 1;
+        spawn_reader(s, done_tx.clone())
+    });
+    let stderr = child.stderr.take().map(|s| {
+        open_pipes += 1;
+        spawn_reader(s, done_tx.clone())
+    });
+
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                run.exit_code = status.code();
+                break;
+            }
+            Ok(None) if started.elapsed() >= timeout => {
+                run.timed_out = true;
+                let settings = KillSettings {
+                    grace_period_ms: 1000,
+                    kill_tree: true,
+                    ..KillSettings::default()
+                };
+                if let Err(e) = kill_process(child.id(), &settings) {
+                    warn!("Failed to kill timed out hook: {}", e);
+                    let _ = child.kill();
+                }
+                let _ = child.wait();
+                break;
+            }
+            Ok(None) => thread::sleep(WAIT_POLL),
+            Err(e) => {
+                run.error = Some(e.to_string());
+                let _ = child.kill();
+                let _ = child.wait();
+                break;
+            }
+        }
+    }
+
+    // Background jobs can keep the pipes open after the shell exits. They get
+    // what is left of the timeout, then go down with the hook's process group;
+    // whatever was read by then is kept.
+    let remaining = timeout.saturating_sub(started.elapsed());
+    if !wait_for_pipes(&done_rx, &mut open_pipes, remaining) {
+        run.timed_out = true;
+        kill_group(child.id());
+        if !wait_for_pipes(&done_rx, &mut open_pipes, PIPE_GRACE) {
+            warn!("Hook {} left its output pipes open, keeping partial output", hook.name());
+        }
+    }
+
+    run.stdout = stdout.map(|c| captured(&c)).unwrap_or_default();
+    run.stderr = stderr.map(|c| captured(&c)).unwrap_or_default();
+    run.duration_ms = started.elapsed().as_millis() as u64;
+    run
+}
+
+/// Read a pipe to the end on its own thread, keeping the first
+/// `MAX_OUTPUT_BYTES`, and signal `done` once it is closed
+fn spawn_reader(mut pipe: impl Read + Send + 'static, done: Sender<()>) -> Captured {
+    let kept = Captured::default();
+    let sink = Arc::clone(&kept);
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let mut kept = sink.lock().unwrap();
+                    let room = MAX_OUTPUT_BYTES.saturating_sub(kept.len());
+                    kept.extend_from_slice(&buf[..n.min(room)]);
+                }
+            }
+        }
+        let _ = done.send(());
+    });
+    kept
+}
+
+/// Wait up to `timeout` for the `open` pipes to close, counting them down.
+/// False if some are still open.
+fn wait_for_pipes(done: &Receiver<()>, open: &mut usize, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while *open > 0 {
+        match done.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(()) => *open -= 1,
+            Err(_) => return false,
+        }
+    }
+    true
+}
+
+/// Output read so far, as text
+fn captured(output: &Captured) -> String {
+    String::from_utf8_lossy(&output.lock().unwrap()).into_owned()
+}
+
+/// SIGKILL whatever is left in the process group led by `pgid`
+fn kill_group(pgid: u32) {
+    match Command::new("kill").args(["-KILL", "--", &format!("-{}", pgid)]).output() {
+        Ok(output) if !output.status.success() => {
+            debug!("kill -{}: {}", pgid, String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to kill hook process group {}: {}", pgid, e),
+    }
+}
+
+/// Path of the hooks log (`~/.agent-sessions/hooks.log`)
+pub fn log_path() -> Option<PathBuf> {
+    crate::settings::app_dir().map(|d| d.join("hooks.log"))
+}
+
+/// Run hooks for session events in the background
+pub fn start() {
+    let events = crate::api::subscribe();
+    thread::spawn(move || {
+        // Last known state of every session, so `on_session_end` can say
+        // where the session ran. Only removed on `Removed`: a tick may see a
+        // scan before its events have been drained.
+        let mut known: HashMap<String, Session> = HashMap::new();
+        let mut last_scan: Option<Instant> = None;
+
+        loop {
+            match events.recv_timeout(Duration::from_secs(1)) {
+                Ok(event) => {
+                    let settings = crate::settings::get().hooks;
+                    let context = HookContext::for_event(&event, &known);
+                    for (hook, command) in settings.hooks_for(&event) {
+                        let (command, context) = (command.to_string(), context.clone());
+                        let timeout = Duration::from_secs(settings.timeout_secs);
+                        thread::spawn(move || {
+                            let run = run_hook(hook, &command, &context, timeout);
+                            info!(
+                                "Hook {} for {}: exit {:?}{} in {}ms",
+                                hook.name(),
+                                run.session_id,
+                                run.exit_code,
+                                if run.timed_out { " (timed out)" } else { "" },
+                                run.duration_ms
+                            );
+                            if let Some(path) = log_path() {
+                                jsonl_log::append(&path, &run);
+                            }
+                        });
+                    }
+
+                    match event {
+                        SessionEvent::Added { session } | SessionEvent::Changed { session, .. } => {
+                            known.insert(session.id.clone(), *session);
+                        }
+                        SessionEvent::Removed { id, .. } => {
+                            known.remove(&id);
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            if let Some((response, taken_at)) = crate::api::latest() {
+                if last_scan != Some(taken_at) {
+                    last_scan = Some(taken_at);
+                    for session in response.sessions {
+                        known.insert(session.id.clone(), session);
+                    }
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,226 @@
+//! Global hotkey bindings: which shortcut each action is bound to, checking
+//! them for clashes before they are registered, and picking the session an
+//! action applies to. `commands::hotkeys` registers them with the OS.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::session::Session;
+
+/// Sessions reachable with the focus-by-position shortcuts (1-9)
+pub const FOCUS_SLOTS: usize = 9;
+
+/// Time allowed for the second press that confirms a kill
+pub const KILL_CONFIRM_WINDOW: Duration = Duration::from_secs(5);
+
+/// Shortcut per action, as saved by the settings UI. Shortcuts use the
+/// global-shortcut plugin's syntax, e.g. "Control+Option+W".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotkeyBindings {
+    /// Show or hide the window
+    pub toggle_window: Option<String>,
+    /// Focus the next waiting session, longest-waiting first
+    pub next_waiting: Option<String>,
+    /// Modifiers that focus the session at a position together with 1-9,
+    /// e.g. "Control+Option"
+    pub focus_session: Option<String>,
+    /// Kill the last focused session; a second press confirms
+    pub kill_focused: Option<String>,
+}
+
+impl Default for HotkeyBindings {
+    fn default() -> Self {
+        HotkeyBindings {
+            toggle_window: Some("Control+Space".to_string()),
+            next_waiting: None,
+            focus_session: None,
+            kill_focused: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HotkeyAction {
+    ToggleWindow,
+    NextWaiting,
+    /// 1-based position in the session list
+    FocusSession(usize),
+    KillFocused,
+}
+
+impl HotkeyAction {
+    /// Name of the `HotkeyBindings` field the action is bound by
+    pub fn binding(&self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleWindow => "toggleWindow",
+            HotkeyAction::NextWaiting => "nextWaiting",
+            HotkeyAction::FocusSession(_) => "focusSession",
+            HotkeyAction::KillFocused => "killFocused",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            HotkeyAction::ToggleWindow => "Show/hide window".to_string(),
+            HotkeyAction::NextWaiting => "Next waiting session".to_string(),
+            HotkeyAction::FocusSession(n) => format!("Focus session {}", n),
+            HotkeyAction::KillFocused => "Kill focused session".to_string(),
+        }
+    }
+}
+
+/// A binding that could not be registered, reported back to the settings UI
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyConflict {
+    /// `HotkeyBindings` field name
+    pub binding: String,
+    pub shortcut: String,
+    pub reason: String,
+}
+
+impl HotkeyConflict {
+    pub fn new(action: HotkeyAction, shortcut: &str, reason: impl Into<String>) -> Self {
+        HotkeyConflict {
+            binding: action.binding().to_string(),
+            shortcut: shortcut.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Every shortcut the bindings ask for, with the action it triggers
+pub fn shortcuts(bindings: &HotkeyBindings) -> Vec<(String, HotkeyAction)> {
+    let mut shortcuts = Vec::new();
+    let mut add = |shortcut: &Option<String>, action: HotkeyAction| {
+        if let Some(s) = shortcut.as_ref().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            shortcuts.push((s.to_string(), action));
+        }
+    };
+
+    add(&bindings.toggle_window, HotkeyAction::ToggleWindow);
+    add(&bindings.next_waiting, HotkeyAction::NextWaiting);
+    if let Some(modifiers) = bindings.focus_session.as_ref().map(|m| m.trim()).filter(|m| !m.is_empty()) {
+        for n in 1..=FOCUS_SLOTS {
+            add(&Some(format!("{}+{}", modifiers, n)), HotkeyAction::FocusSession(n));
+        }
+    }
+    add(&bindings.kill_focused, HotkeyAction::KillFocused);
+    shortcuts
+}
+
+/// Split the bindings into shortcuts to register and ones that are invalid or
+/// bound twice. When two actions share a shortcut the first one keeps it.
+pub fn plan(bindings: &HotkeyBindings) -> (Vec<(String, HotkeyAction)>, Vec<HotkeyConflict>) {
+    let mut planned: Vec<(String, HotkeyAction, String)> = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for (shortcut, action) in shortcuts(bindings) {
+        let normalized = match normalize_shortcut(&shortcut) {
+            Ok(n) => n,
+            Err(e) => {
+                conflicts.push(HotkeyConflict::new(action, &shortcut, e));
+                continue;
+            }
+        };
+        match planned.iter().find(|(_, _, n)| n == &normalized) {
+            Some((_, other, _)) => conflicts.push(HotkeyConflict::new(
+                action,
+                &shortcut,
+                format!("Already used by \"{}\"", other.label()),
+            )),
+            None => planned.push((shortcut, action, normalized)),
+        }
+    }
+
+    (planned.into_iter().map(|(s, a, _)| (s, a)).collect(), conflicts)
+}
+
+/// Canonical form of a shortcut, so "Option+Ctrl+k" and "Control+Alt+K"
+/// compare equal. Global shortcuts need a modifier unless they are on a
+/// function key, or they would swallow ordinary typing.
+pub fn normalize_shortcut(shortcut: &str) -> Result<String, String> {
+    // Canonical order
+    const MODIFIERS: [&str; 4] = ["super", "ctrl", "alt", "shift"];
+
+    let parts: Vec<&str> = shortcut.split('+').map(str::trim).collect();
+    if parts.iter().any(|p| p.is_empty()) {
+        return Err(format!("Invalid shortcut \"{}\"", shortcut));
+    }
+    let (key, modifiers) = parts.split_last().ok_or_else(|| "Empty shortcut".to_string())?;
+
+    let mut found = Vec::new();
+    for modifier in modifiers {
+        let canonical = modifier_name(modifier).ok_or_else(|| format!("Unknown modifier \"{}\"", modifier))?;
+        if !found.contains(&canonical) {
+            found.push(canonical);
+        }
+    }
+    if modifier_name(key).is_some() {
+        return Err(format!("\"{}\" has no key besides modifiers", shortcut));
+    }
+
+    let key = key.to_uppercase();
+    let is_function_key = key.len() > 1 && key.starts_with('F') && key[1..].chars().all(|c| c.is_ascii_digit());
+    if found.is_empty() && !is_function_key {
+        return Err(format!("\"{}\" needs a modifier such as Control or Option", shortcut));
+    }
+
+    let mut normalized: Vec<&str> = MODIFIERS.iter().copied().filter(|m| found.contains(m)).collect();
+    normalized.push(&key);
+    Ok(normalized.join("+"))
+}
+
+fn modifier_name(name: &str) -> Option<&'static str> {
+    match name.to_lowercase().as_str() {
+        "command" | "cmd" | "super" | "meta" => Some("super"),
+        "control" | "ctrl" => Some("ctrl"),
+        "option" | "alt" => Some("alt"),
+        "shift" => Some("shift"),
+        "commandorcontrol" | "cmdorctrl" | "commandorctrl" | "cmdorcontrol" => {
+            Some(if cfg!(target_os = "macos") { "super" } else { "ctrl" })
+        }
+        _ => None,
+    }
+}
+
+/// The session at 1-based `position` in list order
+pub fn session_at(sessions: &[Session], position: usize) -> Option<&Session> {
+    position.checked_sub(1).and_then(|i| sessions.get(i))
+}
+
+/// `sessions` in the order the window lists them (`order`, session ids),
+/// followed by any it hasn't shown yet in scan order
+pub fn in_display_order(sessions: &[Session], order: &[String]) -> Vec<Session> {
+    let mut ordered: Vec<Session> = order
+        .iter()
+        .filter_map(|id| sessions.iter().find(|s| &s.id == id))
+        .cloned()
+        .collect();
+    ordered.extend(sessions.iter().filter(|s| !order.contains(&s.id)).cloned());
+    ordered
+}
+
+/// Two presses of the kill shortcut on the same session within
+/// `KILL_CONFIRM_WINDOW` kill it; the first only asks.
+#[derive(Debug, Default)]
+pub struct KillConfirm {
+    pending: Option<(String, Instant)>,
+}
+
+impl KillConfirm {
+    pub const fn new() -> Self {
+        KillConfirm { pending: None }
+    }
+
+    /// Record a press for `session_id`; true when it confirms an earlier one
+    pub fn press(&mut self, session_id: &str, now: Instant) -> bool {
+        let confirmed = matches!(
+            &self.pending,
+            Some((id, at)) if id == session_id && now.saturating_duration_since(*at) <= KILL_CONFIRM_WINDOW
+        );
+        self.pending = if confirmed { None } else { Some((session_id.to_string(), now)) };
+        confirmed
+    }
+}
@@ -0,0 +1,44 @@
+//! Append-only JSON-lines logs under `~/.agent-sessions` (webhook deliveries,
+//! rule firings, hook runs). A log past 1 MB is moved to `<name>.log.1` before
+//! the next write, so each keeps at most two files.
+
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+
+/// A log is moved to `<name>.log.1` past this size
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Append `entry` as one JSON line, rotating the log once it gets large
+pub fn append<T: Serialize>(path: &Path, entry: &T) {
+    if std::fs::metadata(path).map(|m| m.len() > MAX_LOG_BYTES).unwrap_or(false) {
+        let _ = std::fs::rename(path, path.with_extension("log.1"));
+    }
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    let line = match serde_json::to_string(entry) {
+        Ok(l) => l,
+        Err(_) => return,
+    };
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| writeln!(f, "{}", line));
+    if let Err(e) = result {
+        warn!("Failed to write log {:?}: {}", path, e);
+    }
+}
+
+/// Every entry of the log, oldest first. Lines that don't parse are skipped.
+pub fn read<T: DeserializeOwned>(path: &Path) -> Vec<T> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
@@ -1,11 +1,24 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 pub mod agent;
+pub mod api;
+pub mod cli;
 pub mod commands;
+pub mod hooks;
+pub mod hotkeys;
+pub mod jsonl_log;
 pub mod logging;
+pub mod mcp;
+pub mod metrics;
+pub mod notifications;
 pub mod process;
+pub mod rules;
 pub mod session;
+pub mod settings;
 pub mod terminal;
+pub mod tray_menu;
+pub mod tray_status;
+pub mod webhooks;
 
 #[cfg(test)]
 mod tests;
@@ -13,11 +26,10 @@ mod tests;
 use tauri::{
     Manager,
     tray::TrayIconBuilder,
-    menu::{MenuBuilder, MenuItemBuilder},
 };
 use std::sync::Mutex;
 
-use commands::{get_all_sessions, focus_session, update_tray_title, register_shortcut, unregister_shortcut, kill_session, kill_session_and_companions, launch_chrome, detach_chrome, launch_cursor, detach_cursor, open_in_cursor, save_project_links, save_session_links};
+use commands::{get_all_sessions, explain_session_status, focus_session, register_shortcuts, unregister_shortcut, get_hotkey_conflicts, set_session_order, kill_session, kill_session_and_companions, pause_session, resume_session_process, launch_chrome, detach_chrome, launch_cursor, detach_cursor, open_in_cursor, save_project_links, save_session_links};
 
 // Store tray icon ID for updates
 static TRAY_ID: Mutex<Option<String>> = Mutex::new(None);
@@ -30,19 +42,10 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
-        .invoke_handler(tauri::generate_handler![get_all_sessions, focus_session, update_tray_title, register_shortcut, unregister_shortcut, kill_session, kill_session_and_companions, launch_chrome, detach_chrome, launch_cursor, detach_cursor, open_in_cursor, save_project_links, save_session_links])
+        .invoke_handler(tauri::generate_handler![get_all_sessions, explain_session_status, focus_session, register_shortcuts, unregister_shortcut, get_hotkey_conflicts, set_session_order, kill_session, kill_session_and_companions, pause_session, resume_session_process, launch_chrome, detach_chrome, launch_cursor, detach_cursor, open_in_cursor, save_project_links, save_session_links])
         .setup(|app| {
-            // Create menu for tray
-            let show_item = MenuItemBuilder::with_id("show", "Show Window")
-                .build(app)?;
-            let quit_item = MenuItemBuilder::with_id("quit", "Quit")
-                .build(app)?;
-
-            let menu = MenuBuilder::new(app)
-                .item(&show_item)
-                .separator()
-                .item(&quit_item)
-                .build()?;
+            // Tray menu starts without sessions; start_tray_updates fills it in
+            let menu = commands::build_tray_menu(app.handle(), &tray_menu::TrayMenuModel::default())?;
 
             // Create tray icon with menu
             // Use include_bytes to embed tray icon at compile time
@@ -64,7 +67,11 @@ pub fn run() {
                         "quit" => {
                             app.exit(0);
                         }
-                        _ => {}
+                        id => {
+                            if let Some(action) = tray_menu::TrayAction::from_menu_id(id) {
+                                commands::handle_tray_action(app, action);
+                            }
+                        }
                     }
                 })
                 .on_tray_icon_event(|tray, event| {
@@ -80,6 +87,15 @@ pub fn run() {
 
             // Store tray ID
             *TRAY_ID.lock().unwrap() = Some("main-tray".to_string());
+            commands::start_tray_updates(app.handle().clone());
+
+            api::start();
+            webhooks::start();
+            hooks::start();
+
+            let handle = app.handle().clone();
+            notifications::start(move |notification| commands::show_notification(&handle, notification));
+            rules::start(commands::AppRuleHost(app.handle().clone()));
 
             Ok(())
         })
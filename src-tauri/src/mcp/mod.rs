@@ -0,0 +1,395 @@
+//! MCP server letting agents see their sibling sessions.
+//!
+//! Several sessions often work in the same repository; these tools let one
+//! check what the others are doing before touching shared files. Served over
+//! stdio by `agent-sessions mcp` (what `.mcp.json` registers) and over
+//! streamable HTTP at `POST /mcp` on the local API.
+//!
+//! Over stdio the server is a child of the agent that launched it, so the
+//! calling session is found by walking up from the parent PID. It is left
+//! out of `files_touched_by_other_sessions` and recorded as a note's author.
+
+use log::{debug, warn};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::session::{edits, notes, Session};
+
+/// Protocol revision answered when the client doesn't name one we know
+const PROTOCOL_VERSION: &str = "2025-03-26";
+
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// Key under `mcpServers` in `.mcp.json`
+pub const SERVER_NAME: &str = "agent-sessions";
+
+/// Ancestors checked when looking for the calling agent
+const MAX_CALLER_DEPTH: usize = 5;
+
+/// Longest `lastMessage` returned by `list_sessions`
+const MAX_MESSAGE_PREVIEW: usize = 200;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Answers MCP messages. `caller_pids` are the agent processes the client may
+/// be running in, nearest first; empty when unknown (HTTP).
+pub struct McpServer {
+    caller_pids: Vec<u32>,
+}
+
+impl McpServer {
+    pub fn new(caller_pids: Vec<u32>) -> Self {
+        McpServer { caller_pids }
+    }
+
+    /// Server for a stdio client: the caller is this process's parent chain
+    pub fn for_parent_process() -> Self {
+        McpServer::new(ancestor_pids(std::process::id(), MAX_CALLER_DEPTH))
+    }
+
+    /// Handle one JSON-RPC message or batch. `None` when nothing should be
+    /// sent back (notifications and responses).
+    pub fn handle(&self, message: Value) -> Option<Value> {
+        match message {
+            Value::Array(batch) => {
+                let responses: Vec<Value> = batch.into_iter().filter_map(|m| self.handle_one(m)).collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            message => self.handle_one(message),
+        }
+    }
+
+    /// Handle a raw message, answering parse errors too
+    pub fn handle_text(&self, text: &str) -> Option<Value> {
+        match serde_json::from_str::<Value>(text) {
+            Ok(message) => self.handle(message),
+            Err(e) => Some(error_response(Value::Null, PARSE_ERROR, &format!("Parse error: {}", e))),
+        }
+    }
+
+    fn handle_one(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let method = match message.get("method").and_then(|m| m.as_str()) {
+            Some(m) => m,
+            // A response to something we never send, or garbage
+            None => return id.map(|id| error_response(id, INVALID_REQUEST, "Invalid request")),
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        // Notifications get no reply
+        let id = id?;
+        debug!("MCP request {}", method);
+
+        let result = match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(|v| v.as_str()).unwrap_or("");
+        let version = if SUPPORTED_PROTOCOL_VERSIONS.contains(&requested) {
+            requested
+        } else {
+            PROTOCOL_VERSION
+        };
+
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": SERVER_NAME, "version": env!("CARGO_PKG_VERSION") },
+            "instructions": "Other coding agent sessions may be running in the same repository. \
+                Check files_touched_by_other_sessions before editing shared files, and use post_note \
+                to tell a sibling session about changes that affect it."
+        })
+    }
+
+    fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(|n| n.as_str())
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+        let sessions = crate::api::current_sessions().sessions;
+        let caller = self.caller(&sessions);
+
+        let outcome = match name {
+            "list_sessions" => Ok(list_sessions(&sessions, caller, optional_str(&args, "project"))),
+            "get_session" => required_str(&args, "id").and_then(|id| get_session(&sessions, id)),
+            "files_touched_by_other_sessions" => Ok(files_touched_by_others(
+                &sessions,
+                caller,
+                optional_str(&args, "project"),
+                optional_str(&args, "session_id"),
+            )),
+            "post_note" => required_str(&args, "session_id").and_then(|id| {
+                let text = required_str(&args, "text")?;
+                post_note(&sessions, caller, id, text)
+            }),
+            _ => return Err((INVALID_PARAMS, format!("Unknown tool: {}", name))),
+        };
+
+        // Tool failures are reported in the result so the model can see them
+        Ok(match outcome {
+            Ok(value) => json!({
+                "content": [{ "type": "text", "text": serde_json::to_string_pretty(&value).unwrap_or_default() }],
+                "structuredContent": { "result": value },
+                "isError": false
+            }),
+            Err(message) => json!({
+                "content": [{ "type": "text", "text": message }],
+                "isError": true
+            }),
+        })
+    }
+
+    /// The session the client runs in, if it could be identified
+    fn caller<'a>(&self, sessions: &'a [Session]) -> Option<&'a Session> {
+        self.caller_pids
+            .iter()
+            .find_map(|pid| sessions.iter().find(|s| s.pid == *pid))
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn optional_str<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty())
+}
+
+fn required_str<'a>(args: &'a Value, key: &str) -> Result<&'a str, String> {
+    optional_str(args, key).ok_or_else(|| format!("Missing required argument `{}`", key))
+}
+
+/// Whether `session` belongs to `project`, given as a path or a project name
+fn in_project(session: &Session, project: &str) -> bool {
+    let project = project.trim_end_matches('/');
+    if project.contains('/') {
+        let path = session.project_path.trim_end_matches('/');
+        path == project || path.starts_with(&format!("{}/", project))
+    } else {
+        session.project_name == project
+    }
+}
+
+fn summary(session: &Session, caller: Option<&Session>) -> Value {
+    let preview = session
+        .last_message
+        .as_deref()
+        .map(|m| m.chars().take(MAX_MESSAGE_PREVIEW).collect::<String>());
+
+    json!({
+        "id": session.id,
+        "projectName": session.project_name,
+        "projectPath": session.project_path,
+        "status": session.status,
+        "gitBranch": session.git_branch,
+        "lastMessage": preview,
+        "lastActivityAt": session.last_activity_at,
+        "pid": session.pid,
+        "isYou": caller.map(|c| c.id == session.id).unwrap_or(false),
+        "notes": notes::notes_for(&session.id).len(),
+    })
+}
+
+fn list_sessions(sessions: &[Session], caller: Option<&Session>, project: Option<&str>) -> Value {
+    let listed: Vec<Value> = sessions
+        .iter()
+        .filter(|s| project.map(|p| in_project(s, p)).unwrap_or(true))
+        .map(|s| summary(s, caller))
+        .collect();
+    Value::Array(listed)
+}
+
+fn get_session(sessions: &[Session], id: &str) -> Result<Value, String> {
+    let session = sessions
+        .iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("No running session {}", id))?;
+
+    let mut value = serde_json::to_value(session).map_err(|e| e.to_string())?;
+    value["notes"] = serde_json::to_value(notes::notes_for(id)).unwrap_or_default();
+    Ok(value)
+}
+
+fn files_touched_by_others(
+    sessions: &[Session],
+    caller: Option<&Session>,
+    project: Option<&str>,
+    exclude_session_id: Option<&str>,
+) -> Value {
+    let project = project.or(caller.map(|c| c.project_path.as_str()));
+    let exclude = exclude_session_id.or(caller.map(|c| c.id.as_str()));
+
+    let touched: Vec<Value> = sessions
+        .iter()
+        .filter(|s| Some(s.id.as_str()) != exclude)
+        .filter(|s| project.map(|p| in_project(s, p)).unwrap_or(true))
+        .filter_map(|s| {
+            let files = edits::edited_files(&edits::transcript_path(&s.id)?);
+            (!files.is_empty()).then(|| {
+                json!({
+                    "sessionId": s.id,
+                    "projectName": s.project_name,
+                    "status": s.status,
+                    "gitBranch": s.git_branch,
+                    "files": files,
+                })
+            })
+        })
+        .collect();
+    Value::Array(touched)
+}
+
+fn post_note(sessions: &[Session], caller: Option<&Session>, session_id: &str, text: &str) -> Result<Value, String> {
+    if !sessions.iter().any(|s| s.id == session_id) {
+        return Err(format!("No running session {}", session_id));
+    }
+    let note = notes::add_note(session_id, text, caller.map(|c| c.id.as_str()))?;
+    serde_json::to_value(note).map_err(|e| e.to_string())
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "list_sessions",
+            "description": "List running coding agent sessions with their status, branch and last message. \
+                The session you are running in has isYou=true.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Only sessions in this project path or project name" }
+                }
+            }
+        },
+        {
+            "name": "get_session",
+            "description": "Full details of one running session, including notes other sessions left for it.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string", "description": "Session id" } },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "files_touched_by_other_sessions",
+            "description": "Files that other running sessions have edited, most recent first. \
+                Check this before editing files another session may be working on.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Project path or name; defaults to your own project" },
+                    "session_id": { "type": "string", "description": "Session to leave out; defaults to your own session" }
+                }
+            }
+        },
+        {
+            "name": "post_note",
+            "description": "Leave a short note for another running session, e.g. about a shared file you changed. \
+                It sees the note through get_session.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "session_id": { "type": "string", "description": "Session the note is for" },
+                    "text": { "type": "string", "description": "The note" }
+                },
+                "required": ["session_id", "text"]
+            }
+        }
+    ])
+}
+
+/// Serve newline-delimited JSON-RPC on stdin/stdout until stdin closes
+pub fn run_stdio() -> Result<(), String> {
+    let server = McpServer::for_parent_process();
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|e| format!("Failed to read stdin: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_text(&line) {
+            writeln!(stdout, "{}", response)
+                .and_then(|_| stdout.flush())
+                .map_err(|e| format!("Failed to write stdout: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// `pid`'s parent, grandparent, ... up to `depth` levels
+fn ancestor_pids(pid: u32, depth: usize) -> Vec<u32> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, ProcessRefreshKind::new());
+
+    let mut ancestors = Vec::new();
+    let mut current = Pid::from_u32(pid);
+    for _ in 0..depth {
+        match system.process(current).and_then(|p| p.parent()) {
+            Some(parent) if parent.as_u32() > 1 => {
+                ancestors.push(parent.as_u32());
+                current = parent;
+            }
+            _ => break,
+        }
+    }
+    ancestors
+}
+
+/// Add (or update) this server in `<project>/.mcp.json`, run as `command mcp`.
+/// Other servers and keys in the file are kept. Returns the file's path.
+pub fn register_in_project(project_path: &Path, command: &Path) -> Result<PathBuf, String> {
+    let path = project_path.join(".mcp.json");
+
+    let mut config: Value = match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Failed to parse {:?}: {}", path, e))?,
+        Err(_) => json!({}),
+    };
+    if !config.is_object() {
+        return Err(format!("{:?} is not a JSON object", path));
+    }
+
+    let servers = config
+        .as_object_mut()
+        .unwrap()
+        .entry("mcpServers")
+        .or_insert_with(|| json!({}));
+    let servers = servers
+        .as_object_mut()
+        .ok_or_else(|| format!("`mcpServers` in {:?} is not an object", path))?;
+
+    if servers.contains_key(SERVER_NAME) {
+        warn!("Replacing existing {} entry in {:?}", SERVER_NAME, path);
+    }
+    servers.insert(
+        SERVER_NAME.to_string(),
+        json!({
+            "type": "stdio",
+            "command": command.to_string_lossy(),
+            "args": ["mcp"],
+        }),
+    );
+
+    let json = serde_json::to_string_pretty(&config).map_err(|e| format!("Failed to serialize: {}", e))?;
+    std::fs::write(&path, json + "\n").map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(path)
+}
@@ -0,0 +1,217 @@
+//! Prometheus text-format metrics, served at `/metrics` on the local API.
+//!
+//! Gauges are rendered from the latest `SessionsResponse`. Counters and the
+//! time each session has spent in its current status are accumulated from
+//! every poll through `record_poll`, and detector scan durations through
+//! `record_scan`, so they cover polls made for the app as well as the API.
+
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::session::{enum_name, AgentType, Session, SessionStatus, SessionsResponse, StatusDwell};
+
+/// Upper bounds of the scan duration histogram buckets, in seconds
+const SCAN_BUCKETS: &[f64] = &[0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+const STATUSES: &[SessionStatus] = &[
+    SessionStatus::Thinking,
+    SessionStatus::Processing,
+    SessionStatus::Compacting,
+    SessionStatus::Waiting,
+    SessionStatus::Idle,
+    SessionStatus::Paused,
+];
+
+const AGENT_TYPES: &[AgentType] = &[AgentType::Claude, AgentType::OpenCode];
+
+#[derive(Default)]
+struct ScanStats {
+    /// Cumulative count per entry of `SCAN_BUCKETS`
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Default)]
+struct MetricsState {
+    /// (from, to) -> count
+    transitions: BTreeMap<(String, String), u64>,
+    /// Detector name -> scan durations
+    scans: BTreeMap<String, ScanStats>,
+    /// Current status of each session and when it was entered
+    dwell: StatusDwell,
+}
+
+static STATE: Lazy<Mutex<MetricsState>> = Lazy::new(|| Mutex::new(MetricsState::default()));
+
+/// Count status transitions and track status durations from a finished poll
+pub fn record_poll(response: &SessionsResponse) {
+    record_poll_at(response, Instant::now());
+}
+
+/// `record_poll` with an explicit clock, for tests
+pub fn record_poll_at(response: &SessionsResponse, now: Instant) {
+    let mut state = STATE.lock().unwrap();
+
+    let state = &mut *state;
+
+    for session in &response.sessions {
+        match state.dwell.status(&session.id) {
+            Some(status) if status != &session.status => {
+                *state
+                    .transitions
+                    .entry((enum_name(status), enum_name(&session.status)))
+                    .or_default() += 1;
+            }
+            _ => {}
+        }
+    }
+    state.dwell.update(&response.sessions, now);
+}
+
+/// Record how long one detector's scan took
+pub fn record_scan(detector: &str, duration: Duration) {
+    let secs = duration.as_secs_f64();
+    let mut state = STATE.lock().unwrap();
+    let stats = state.scans.entry(detector.to_string()).or_default();
+
+    if stats.buckets.is_empty() {
+        stats.buckets = vec![0; SCAN_BUCKETS.len()];
+    }
+    for (bucket, bound) in stats.buckets.iter_mut().zip(SCAN_BUCKETS) {
+        if secs <= *bound {
+            *bucket += 1;
+        }
+    }
+    stats.sum += secs;
+    stats.count += 1;
+}
+
+/// Render every metric in the Prometheus text exposition format
+pub fn render(response: &SessionsResponse) -> String {
+    render_at(response, Instant::now())
+}
+
+/// `render` with an explicit clock, for tests
+pub fn render_at(response: &SessionsResponse, now: Instant) -> String {
+    let state = STATE.lock().unwrap();
+    let mut out = String::new();
+
+    header(&mut out, "agent_sessions_sessions", "gauge", "Running sessions by status");
+    for status in STATUSES {
+        let count = response.sessions.iter().filter(|s| &s.status == status).count();
+        sample(&mut out, "agent_sessions_sessions", &[("status", &enum_name(status))], count as f64);
+    }
+
+    header(&mut out, "agent_sessions_sessions_by_agent", "gauge", "Running sessions by agent type");
+    for agent_type in AGENT_TYPES {
+        let count = response.sessions.iter().filter(|s| &s.agent_type == agent_type).count();
+        sample(&mut out, "agent_sessions_sessions_by_agent", &[("agent_type", &enum_name(agent_type))], count as f64);
+    }
+
+    header(
+        &mut out,
+        "agent_sessions_session_status_seconds",
+        "gauge",
+        "Seconds the session has been in its current status",
+    );
+    for session in &response.sessions {
+        let since = if state.dwell.status(&session.id) == Some(&session.status) {
+            state.dwell.in_status(&session.id, now).as_secs_f64()
+        } else {
+            0.0
+        };
+        let status = enum_name(&session.status);
+        let mut labels = session_labels(session);
+        labels.push(("status", &status));
+        sample(&mut out, "agent_sessions_session_status_seconds", &labels, since);
+    }
+
+    header(
+        &mut out,
+        "agent_sessions_context_window_percent",
+        "gauge",
+        "Context window left before the session compacts, in percent",
+    );
+    for session in &response.sessions {
+        if let Some(percent) = session.context_window_percent {
+            sample(&mut out, "agent_sessions_context_window_percent", &session_labels(session), percent as f64);
+        }
+    }
+
+    header(
+        &mut out,
+        "agent_sessions_cpu_percent",
+        "gauge",
+        "CPU used by the agent and its child processes, in percent of one core",
+    );
+    for session in &response.sessions {
+        let cpu = session.tree_cpu_usage.max(session.cpu_usage);
+        sample(&mut out, "agent_sessions_cpu_percent", &session_labels(session), cpu as f64);
+    }
+
+    header(
+        &mut out,
+        "agent_sessions_status_transitions_total",
+        "counter",
+        "Committed session status transitions",
+    );
+    for ((from, to), count) in &state.transitions {
+        sample(
+            &mut out,
+            "agent_sessions_status_transitions_total",
+            &[("from", from), ("to", to)],
+            *count as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "agent_sessions_detector_scan_duration_seconds",
+        "histogram",
+        "Time taken by each agent detector to find processes and sessions",
+    );
+    for (detector, stats) in &state.scans {
+        let name = "agent_sessions_detector_scan_duration_seconds";
+        for (bound, count) in SCAN_BUCKETS.iter().zip(&stats.buckets) {
+            let le = bound.to_string();
+            sample(&mut out, &format!("{}_bucket", name), &[("detector", detector), ("le", &le)], *count as f64);
+        }
+        sample(&mut out, &format!("{}_bucket", name), &[("detector", detector), ("le", "+Inf")], stats.count as f64);
+        sample(&mut out, &format!("{}_sum", name), &[("detector", detector)], stats.sum);
+        sample(&mut out, &format!("{}_count", name), &[("detector", detector)], stats.count as f64);
+    }
+
+    out
+}
+
+fn session_labels(session: &Session) -> Vec<(&'static str, &str)> {
+    vec![
+        ("session_id", session.id.as_str()),
+        ("project", session.project_name.as_str()),
+        ("branch", session.git_branch.as_deref().unwrap_or("")),
+    ]
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    let labels = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect::<Vec<_>>()
+        .join(",");
+    let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+}
+
+/// Escape a label value: backslash, double quote and newline
+pub fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
@@ -0,0 +1,339 @@
+//! Desktop notifications when a session changes to a status worth looking at.
+//!
+//! Driven by the session events published from every poll (see
+//! `api::subscribe`). A transition matching a rule is held for its minimum
+//! dwell and only shown if the session is still in that status by then, so a
+//! session flickering through Waiting doesn't notify. Rules come from the
+//! `notifications` section of the global config, with per-project overrides
+//! keyed by project name or path:
+//!
+//! ```json
+//! "notifications": {
+//!   "on": [{"to": "waiting"}, {"from": ["thinking", "processing"], "to": "idle"}],
+//!   "min_dwell_secs": 5,
+//!   "quiet_hours": {"start": "22:00", "end": "08:00"},
+//!   "projects": {"scratch": {"enabled": false}}
+//! }
+//! ```
+
+use chrono::{Local, NaiveTime};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::session::{Session, SessionEvent, SessionStatus};
+
+/// How often pending notifications are checked against their dwell
+const TICK: Duration = Duration::from_secs(1);
+
+/// Longest last message shown in a notification body, in characters
+const MAX_BODY_CHARS: usize = 140;
+
+/// The `notifications` section of the global config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    /// Transitions that notify
+    pub on: Vec<NotificationTrigger>,
+    /// Seconds a session must stay in the new status before notifying
+    pub min_dwell_secs: u64,
+    /// Local time range in which nothing is shown
+    pub quiet_hours: Option<QuietHours>,
+    /// Overrides keyed by project name or project path
+    pub projects: HashMap<String, ProjectNotificationRules>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        NotificationSettings {
+            enabled: true,
+            on: vec![
+                NotificationTrigger {
+                    from: Vec::new(),
+                    to: SessionStatus::Waiting,
+                    min_dwell_secs: None,
+                },
+                NotificationTrigger {
+                    from: vec![SessionStatus::Thinking, SessionStatus::Processing, SessionStatus::Compacting],
+                    to: SessionStatus::Idle,
+                    min_dwell_secs: None,
+                },
+            ],
+            min_dwell_secs: 5,
+            quiet_hours: None,
+            projects: HashMap::new(),
+        }
+    }
+}
+
+/// A status transition that notifies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationTrigger {
+    /// Previous statuses this applies to; empty matches any
+    #[serde(default)]
+    pub from: Vec<SessionStatus>,
+    pub to: SessionStatus,
+    /// Overrides `min_dwell_secs` for this transition
+    #[serde(default)]
+    pub min_dwell_secs: Option<u64>,
+}
+
+impl NotificationTrigger {
+    pub fn matches(&self, from: &SessionStatus, to: &SessionStatus) -> bool {
+        &self.to == to && (self.from.is_empty() || self.from.contains(from))
+    }
+}
+
+/// Per-project overrides; unset fields fall back to the global rules
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectNotificationRules {
+    pub enabled: Option<bool>,
+    pub on: Option<Vec<NotificationTrigger>>,
+    pub min_dwell_secs: Option<u64>,
+}
+
+/// `"HH:MM"` local times; `start` after `end` spans midnight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    /// Whether `time` falls in the quiet range. Unparseable times never do.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let parse = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M");
+        let (start, end) = match (parse(&self.start), parse(&self.end)) {
+            (Ok(start), Ok(end)) => (start, end),
+            _ => {
+                warn!("Ignoring malformed quiet_hours {:?}-{:?}", self.start, self.end);
+                return false;
+            }
+        };
+
+        if start <= end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+impl NotificationSettings {
+    /// How long `session` must stay in its status before notifying about its
+    /// change from `from`, or `None` if no rule asks for a notification
+    pub fn dwell_for(&self, session: &Session, from: &SessionStatus) -> Option<Duration> {
+        let project = self
+            .projects
+            .get(&session.project_path)
+            .or_else(|| self.projects.get(&session.project_name));
+
+        if !project.and_then(|p| p.enabled).unwrap_or(self.enabled) {
+            return None;
+        }
+
+        let triggers = project.and_then(|p| p.on.as_ref()).unwrap_or(&self.on);
+        let trigger = triggers.iter().find(|t| t.matches(from, &session.status))?;
+        let secs = trigger
+            .min_dwell_secs
+            .or_else(|| project.and_then(|p| p.min_dwell_secs))
+            .unwrap_or(self.min_dwell_secs);
+        Some(Duration::from_secs(secs))
+    }
+
+    /// Whether notifications are suppressed at local time `time`
+    pub fn is_quiet(&self, time: NaiveTime) -> bool {
+        self.quiet_hours.as_ref().map(|q| q.contains(time)).unwrap_or(false)
+    }
+}
+
+/// A notification ready to show, with what's needed to focus its session
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub session_id: String,
+    pub pid: u32,
+    pub project_path: String,
+    /// Serde name of the session's `TerminalApp`, as `focus_session` takes it
+    pub terminal_app: String,
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    pub fn for_session(session: &Session) -> Self {
+        let project = &session.project_name;
+        let title = match session.status {
+            SessionStatus::Waiting => format!("{} needs your input", project),
+            SessionStatus::Idle => format!("{} finished", project),
+            SessionStatus::Compacting => format!("{} is compacting its context", project),
+            SessionStatus::Thinking => format!("{} is thinking", project),
+            SessionStatus::Processing => format!("{} is running a tool", project),
+            SessionStatus::Paused => format!("{} is paused", project),
+        };
+
+        let body = session
+            .last_message
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(|m| {
+                let mut body: String = m.chars().take(MAX_BODY_CHARS).collect();
+                if m.chars().count() > MAX_BODY_CHARS {
+                    body.push('…');
+                }
+                body
+            })
+            .or_else(|| session.git_branch.clone())
+            .unwrap_or_default();
+
+        let terminal_app = crate::session::enum_name(&session.terminal_app);
+
+        Notification {
+            session_id: session.id.clone(),
+            pid: session.pid,
+            project_path: session.project_path.clone(),
+            terminal_app,
+            title,
+            body,
+        }
+    }
+}
+
+struct Pending {
+    status: SessionStatus,
+    due_at: Instant,
+}
+
+/// Transitions waiting out their dwell, one per session
+#[derive(Default)]
+pub struct Notifier {
+    pending: HashMap<String, Pending>,
+}
+
+impl Notifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedule or cancel a notification for a session event
+    pub fn observe(&mut self, event: &SessionEvent, settings: &NotificationSettings, now: Instant) {
+        match event {
+            SessionEvent::Changed { from, session } => {
+                self.pending.remove(&session.id);
+                if let Some(dwell) = settings.dwell_for(session, from) {
+                    self.pending.insert(
+                        session.id.clone(),
+                        Pending {
+                            status: session.status.clone(),
+                            due_at: now + dwell,
+                        },
+                    );
+                }
+            }
+            SessionEvent::Removed { id, .. } => {
+                self.pending.remove(id);
+            }
+            SessionEvent::Added { .. } => {}
+        }
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Notifications whose dwell has passed with the session still in the
+    /// status that triggered them. Ones falling in quiet hours are dropped.
+    pub fn due(
+        &mut self,
+        sessions: &[Session],
+        settings: &NotificationSettings,
+        now: Instant,
+        local_time: NaiveTime,
+    ) -> Vec<Notification> {
+        let mut ready = Vec::new();
+        self.pending.retain(|id, pending| {
+            if now < pending.due_at {
+                return true;
+            }
+            let session = sessions.iter().find(|s| &s.id == id && s.status == pending.status);
+            match session {
+                Some(session) if settings.is_quiet(local_time) => {
+                    debug!("Suppressed notification for {} during quiet hours", session.id);
+                }
+                Some(session) => ready.push(Notification::for_session(session)),
+                None => {}
+            }
+            false
+        });
+        ready
+    }
+}
+
+/// Watch session events in the background and pass notifications to `show`
+pub fn start<F>(show: F)
+where
+    F: Fn(&Notification) + Send + 'static,
+{
+    let events = crate::api::subscribe();
+    thread::spawn(move || {
+        let mut notifier = Notifier::new();
+        loop {
+            match events.recv_timeout(TICK) {
+                Ok(event) => notifier.observe(&event, &crate::settings::get().notifications, Instant::now()),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            if !notifier.has_pending() {
+                continue;
+            }
+            let sessions = crate::api::latest().map(|(r, _)| r.sessions).unwrap_or_default();
+            let settings = crate::settings::get().notifications;
+            for notification in notifier.due(&sessions, &settings, Instant::now(), Local::now().time()) {
+                show(&notification);
+            }
+        }
+    });
+}
+
+/// Show `notification` over D-Bus with `notify-send`. With `wait_for_click`
+/// this blocks until it is closed and returns whether it was clicked.
+pub fn show_with_notify_send(notification: &Notification, wait_for_click: bool) -> Result<bool, String> {
+    if !wait_for_click {
+        return show_plain_with_notify_send(notification);
+    }
+
+    let output = std::process::Command::new("notify-send")
+        .args(["--app-name=Agent Sessions", "--action=default=Focus", "--wait"])
+        .arg(&notification.title)
+        .arg(&notification.body)
+        .output()
+        .map_err(|e| format!("Failed to run notify-send: {}", e))?;
+
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).trim() == "default");
+    }
+
+    // libnotify before 0.7.9 has no actions; show it without click-to-focus
+    debug!("notify-send with actions failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    show_plain_with_notify_send(notification)
+}
+
+fn show_plain_with_notify_send(notification: &Notification) -> Result<bool, String> {
+    let status = std::process::Command::new("notify-send")
+        .arg("--app-name=Agent Sessions")
+        .arg(&notification.title)
+        .arg(&notification.body)
+        .status()
+        .map_err(|e| format!("Failed to run notify-send: {}", e))?;
+    if status.success() {
+        Ok(false)
+    } else {
+        Err(format!("notify-send exited with {}", status))
+    }
+}
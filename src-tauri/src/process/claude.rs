@@ -4,6 +4,11 @@ use sysinfo::{ProcessRefreshKind, RefreshKind, System};
 use std::path::PathBuf;
 use std::sync::Mutex;
 
+use super::tree::{children_map, process_tree, ProcessTree};
+use super::launch::{classify_claude_command, is_vscode_extension_command, ClaudeLaunch};
+use crate::session::TerminalApp;
+use crate::session::profiles::config_dir_from_environ;
+
 /// Represents a running Claude Code process
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ClaudeProcess {
@@ -13,6 +18,12 @@ pub struct ClaudeProcess {
     pub memory: u64,
     /// Process start time in seconds since UNIX epoch
     pub start_time: u64,
+    /// `CLAUDE_CONFIG_DIR` from the process environment, if set
+    pub config_dir: Option<PathBuf>,
+    /// Editor hosting the agent, for agents not running in a terminal
+    pub host: Option<TerminalApp>,
+    /// Aggregate CPU/memory and running commands of the process and its descendants
+    pub tree: ProcessTree,
 }
 
 // Reuse System instance to avoid expensive re-initialization
@@ -51,11 +62,38 @@ pub fn is_orphaned_process(system: &System, process: &sysinfo::Process) -> bool
     false
 }
 
+/// Shells and the like a launcher may start the CLI through, e.g. npm 7+
+/// running the bin with `sh -c`
+const MAX_LAUNCHER_DEPTH: usize = 4;
+
+/// Launchers whose session runs in a descendant: the nearest classified
+/// ancestor, within `MAX_LAUNCHER_DEPTH` generations, of another classified
+/// process. They are hidden so one launch doesn't show up as two sessions.
+pub fn hidden_launchers(
+    launches: &std::collections::HashMap<sysinfo::Pid, ClaudeLaunch>,
+    parent_of: impl Fn(sysinfo::Pid) -> Option<sysinfo::Pid>,
+) -> std::collections::HashSet<sysinfo::Pid> {
+    launches
+        .keys()
+        .filter_map(|pid| {
+            let mut current = *pid;
+            for _ in 0..MAX_LAUNCHER_DEPTH {
+                current = parent_of(current)?;
+                if let Some(launch) = launches.get(&current) {
+                    return (*launch == ClaudeLaunch::Launcher).then_some(current);
+                }
+            }
+            None
+        })
+        .collect()
+}
+
 /// Find all running Claude Code processes on the system
+/// Matches the `claude` binary as well as node/bun/npx and wrapper launches (see `launch`).
 /// Filters out sub-agent processes (whose parent is also a Claude process)
 /// and orphaned processes (whose terminal has been closed)
 pub fn find_claude_processes() -> Vec<ClaudeProcess> {
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
     use sysinfo::Pid;
 
     debug!("=== Starting process discovery ===");
@@ -70,6 +108,7 @@ pub fn find_claude_processes() -> Vec<ClaudeProcess> {
                 ProcessRefreshKind::new()
                     .with_cmd(sysinfo::UpdateKind::Always)
                     .with_cwd(sysinfo::UpdateKind::Always)
+                    .with_environ(sysinfo::UpdateKind::OnlyIfNotSet)
                     .with_cpu()
                     .with_memory()
             )
@@ -82,6 +121,7 @@ pub fn find_claude_processes() -> Vec<ClaudeProcess> {
         ProcessRefreshKind::new()
             .with_cmd(sysinfo::UpdateKind::Always)
             .with_cwd(sysinfo::UpdateKind::Always)
+            .with_environ(sysinfo::UpdateKind::OnlyIfNotSet)
             .with_cpu()
             .with_memory()
     );
@@ -89,33 +129,46 @@ pub fn find_claude_processes() -> Vec<ClaudeProcess> {
     let total_processes = system.processes().len();
     trace!("Total system processes: {}", total_processes);
 
-    // First pass: collect all Claude process PIDs
-    let mut claude_pids: HashSet<Pid> = HashSet::new();
+    let settings = crate::settings::get();
+
+    // First pass: classify every process that looks like Claude Code
+    let mut launches: HashMap<Pid, ClaudeLaunch> = HashMap::new();
     for (pid, process) in system.processes() {
-        let cmd = process.cmd();
-        let is_claude = if let Some(first_arg) = cmd.first() {
-            let first_arg_str = first_arg.to_string_lossy().to_lowercase();
-            first_arg_str == "claude" || first_arg_str.ends_with("/claude")
-        } else {
-            false
-        };
-        if is_claude {
-            claude_pids.insert(*pid);
+        let cmd: Vec<String> = process.cmd().iter().map(|a| a.to_string_lossy().to_string()).collect();
+        if let Some(launch) = classify_claude_command(&cmd, &settings.claude_process_patterns) {
+            launches.insert(*pid, launch);
         }
     }
 
+    // Processes running the CLI itself - children of these are sub-agents
+    let claude_pids: HashSet<Pid> = launches
+        .iter()
+        .filter(|(_, launch)| **launch == ClaudeLaunch::Direct)
+        .map(|(pid, _)| *pid)
+        .collect();
+
+    // Launchers (npx, wrappers) whose session runs in a descendant process
+    let launchers_with_child = hidden_launchers(&launches, |pid| system.process(pid)?.parent());
+
+    let children = children_map(system);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
     let mut processes = Vec::new();
 
     // Second pass: collect Claude processes, excluding sub-agents and orphans
     for (pid, process) in system.processes() {
-        let cmd = process.cmd();
         let process_name = process.name().to_string_lossy();
 
-        let is_claude = if let Some(first_arg) = cmd.first() {
-            let first_arg_str = first_arg.to_string_lossy().to_lowercase();
-            first_arg_str == "claude" || first_arg_str.ends_with("/claude")
-        } else {
-            false
+        let is_claude = match launches.get(pid) {
+            Some(ClaudeLaunch::Launcher) if launchers_with_child.contains(pid) => {
+                trace!("Skipping launcher pid={}, its child runs the session", pid.as_u32());
+                false
+            }
+            Some(_) => true,
+            None => false,
         };
 
         // Exclude our own app
@@ -131,6 +184,17 @@ pub fn find_claude_processes() -> Vec<ClaudeProcess> {
                 continue;
             }
 
+            // Agents embedded in an editor rather than running in a terminal
+            let cmd: Vec<String> = process.cmd().iter().map(|a| a.to_string_lossy().to_string()).collect();
+            let mut host = None;
+            if is_vscode_extension_command(&cmd) {
+                if !settings.include_editor_agents {
+                    debug!("Skipping VS Code extension agent: pid={}, cwd={:?}", pid.as_u32(), cwd);
+                    continue;
+                }
+                host = Some(TerminalApp::VscodeExtension);
+            }
+
             // Check if parent is also a Claude process (indicates sub-agent)
             if let Some(parent_pid) = process.parent() {
                 if claude_pids.contains(&parent_pid) {
@@ -152,13 +216,16 @@ pub fn find_claude_processes() -> Vec<ClaudeProcess> {
                         .collect::<Vec<_>>()
                         .join(" ");
                     if parent_cmd.contains("claude-code-acp") {
-                        debug!(
-                            "Skipping Zed external agent: pid={}, parent_pid={}, cwd={:?}",
-                            pid.as_u32(),
-                            parent_pid.as_u32(),
-                            cwd
-                        );
-                        continue;
+                        if !settings.include_editor_agents {
+                            debug!(
+                                "Skipping Zed external agent: pid={}, parent_pid={}, cwd={:?}",
+                                pid.as_u32(),
+                                parent_pid.as_u32(),
+                                cwd
+                            );
+                            continue;
+                        }
+                        host = Some(TerminalApp::Zed);
                     }
                 }
             }
@@ -188,6 +255,9 @@ pub fn find_claude_processes() -> Vec<ClaudeProcess> {
                 cpu_usage: process.cpu_usage(),
                 memory: process.memory(),
                 start_time: process.start_time(),
+                config_dir: config_dir_from_environ(process.environ()),
+                host,
+                tree: process_tree(system, &children, *pid, now),
             });
         }
     }
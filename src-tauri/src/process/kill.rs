@@ -0,0 +1,221 @@
+//! Graceful termination of an agent and, optionally, everything it spawned.
+//!
+//! The agent first gets SIGINT or SIGTERM so Claude Code can flush its
+//! transcript; whatever is still running after the grace period gets SIGKILL.
+//! Paused (stopped) processes are continued right after the signal so they
+//! can act on it.
+//! The descendant tree is snapshotted before any signal is sent, because once
+//! the agent exits its children are reparented and can no longer be found.
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System};
+
+use super::tree::children_map;
+
+/// How often liveness is re-checked during the grace period
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Time the agent gets to stop its own children before they are signalled
+/// directly; counts toward the grace period
+const AGENT_HEAD_START: Duration = Duration::from_millis(500);
+
+/// Signal sent before escalating to SIGKILL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KillSignal {
+    Sigint,
+    #[default]
+    Sigterm,
+}
+
+impl KillSignal {
+    fn as_signal(self) -> Signal {
+        match self {
+            KillSignal::Sigint => Signal::Interrupt,
+            KillSignal::Sigterm => Signal::Term,
+        }
+    }
+}
+
+/// How sessions are killed, from the `kill` section of the global config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KillSettings {
+    /// First signal sent to every target
+    pub signal: KillSignal,
+    /// Time targets get to exit before SIGKILL
+    pub grace_period_ms: u64,
+    /// Also terminate every descendant of the agent (dev servers, test runners)
+    pub kill_tree: bool,
+}
+
+impl Default for KillSettings {
+    fn default() -> Self {
+        KillSettings {
+            signal: KillSignal::Sigterm,
+            grace_period_ms: 3000,
+            kill_tree: true,
+        }
+    }
+}
+
+/// Which PIDs went down, and how
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KillReport {
+    /// Exited within the grace period
+    pub terminated: Vec<u32>,
+    /// Still running after the grace period and sent SIGKILL
+    pub force_killed: Vec<u32>,
+}
+
+impl KillReport {
+    /// Every PID that was taken down
+    pub fn all(&self) -> Vec<u32> {
+        self.terminated.iter().chain(&self.force_killed).copied().collect()
+    }
+}
+
+/// A process identified by PID and start time, so a PID reused during the
+/// grace period is never signalled
+#[derive(Debug, Clone, Copy)]
+struct Target {
+    pid: Pid,
+    start_time: u64,
+}
+
+/// Terminate `pid` (and its descendants if `settings.kill_tree`), escalating
+/// to SIGKILL after the grace period. Blocks for up to the grace period.
+pub fn kill_process(pid: u32, settings: &KillSettings) -> Result<KillReport, String> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, ProcessRefreshKind::new());
+
+    let root = Pid::from_u32(pid);
+    let root_process = system.process(root).ok_or_else(|| format!("Process {} not found", pid))?;
+
+    let mut targets = vec![Target {
+        pid: root,
+        start_time: root_process.start_time(),
+    }];
+    if settings.kill_tree {
+        targets.extend(descendants(&system, root));
+    }
+    debug!("Killing {:?} with {:?}", targets.iter().map(|t| t.pid).collect::<Vec<_>>(), settings.signal);
+
+    let grace = Duration::from_millis(settings.grace_period_ms);
+    let deadline = Instant::now() + grace;
+
+    // The agent is signalled first and given a moment to stop its own
+    // children cleanly; whatever it leaves running is signalled after
+    if !send(root_process, settings.signal) {
+        return Err(format!("Failed to send {:?} to process {}", settings.signal, pid));
+    }
+    if targets.len() > 1 {
+        let head_start = Instant::now() + AGENT_HEAD_START.min(grace);
+        while Instant::now() < head_start && !still_running(&targets[..1]).is_empty() {
+            thread::sleep(POLL_INTERVAL);
+        }
+        for target in still_running(&targets[1..]) {
+            let sent = system.process(target.pid).is_some_and(|p| send(p, settings.signal));
+            if !sent {
+                debug!("Failed to send {:?} to descendant {}", settings.signal, target.pid);
+            }
+        }
+    }
+
+    let mut alive = still_running(&targets);
+    while !alive.is_empty() && Instant::now() < deadline {
+        thread::sleep(POLL_INTERVAL);
+        alive = still_running(&alive);
+    }
+
+    let mut report = KillReport::default();
+    for target in &targets {
+        if !alive.iter().any(|a| a.pid == target.pid) {
+            report.terminated.push(target.pid.as_u32());
+        }
+    }
+
+    if !alive.is_empty() {
+        let mut system = System::new();
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&pids(&alive)), ProcessRefreshKind::new());
+        for target in &alive {
+            match system.process(target.pid) {
+                Some(p) if p.start_time() == target.start_time => {
+                    if p.kill_with(Signal::Kill).unwrap_or(false) {
+                        report.force_killed.push(target.pid.as_u32());
+                    } else {
+                        warn!("Failed to SIGKILL process {}", target.pid);
+                    }
+                }
+                // Exited between the last poll and now
+                _ => report.terminated.push(target.pid.as_u32()),
+            }
+        }
+    }
+
+    info!(
+        "Killed process {}: {} terminated, {} force-killed",
+        pid,
+        report.terminated.len(),
+        report.force_killed.len()
+    );
+    Ok(report)
+}
+
+/// Send `signal`, continuing a stopped (paused) process afterwards: a stopped
+/// process only acts on the signal once it runs again, so it would otherwise
+/// sit out the whole grace period
+fn send(process: &Process, signal: KillSignal) -> bool {
+    let sent = process.kill_with(signal.as_signal()).unwrap_or(false);
+    if sent && process.status() == ProcessStatus::Stop {
+        debug!("Continuing stopped process {} so it receives {:?}", process.pid(), signal);
+        process.kill_with(Signal::Continue);
+    }
+    sent
+}
+
+fn descendants(system: &System, root: Pid) -> Vec<Target> {
+    let children = children_map(system);
+    let mut found = Vec::new();
+    let mut stack = children.get(&root).cloned().unwrap_or_default();
+
+    while let Some(pid) = stack.pop() {
+        if found.iter().any(|t: &Target| t.pid == pid) {
+            continue;
+        }
+        if let Some(process) = system.process(pid) {
+            found.push(Target {
+                pid,
+                start_time: process.start_time(),
+            });
+        }
+        stack.extend(children.get(&pid).into_iter().flatten());
+    }
+    found
+}
+
+fn pids(targets: &[Target]) -> Vec<Pid> {
+    targets.iter().map(|t| t.pid).collect()
+}
+
+/// Targets that still exist with the same start time. Zombies count as gone:
+/// they have exited and only wait for their parent to reap them.
+fn still_running(targets: &[Target]) -> Vec<Target> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&pids(targets)), ProcessRefreshKind::new());
+
+    targets
+        .iter()
+        .filter(|t| {
+            system
+                .process(t.pid)
+                .map(|p| p.start_time() == t.start_time && p.status() != ProcessStatus::Zombie)
+                .unwrap_or(false)
+        })
+        .copied()
+        .collect()
+}
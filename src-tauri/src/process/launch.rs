@@ -0,0 +1,136 @@
+//! Recognising the different ways Claude Code gets launched.
+//!
+//! Besides the `claude` binary, the CLI runs as `node .../@anthropic-ai/claude-code/cli.js`,
+//! via `npx`/`bunx` or a package runner, or behind a company wrapper script.
+//! Extra command-line patterns can be added under `claude_process_patterns` in
+//! `~/.agent-sessions/config.json`; `*` matches any run of characters.
+
+/// npm package the CLI ships in
+const CLAUDE_PACKAGE: &str = "@anthropic-ai/claude-code";
+
+/// Path fragment of the Claude Code VS Code extension's install directory
+const VSCODE_EXTENSION_DIR: &str = "/extensions/anthropic.claude-code-";
+
+/// Interpreters that can run the CLI's JS entry point directly
+const JS_RUNTIMES: &[&str] = &["node", "nodejs", "bun", "deno"];
+
+/// Package runners that download and spawn the CLI
+const PACKAGE_RUNNERS: &[&str] = &["npx", "bunx", "pnpx"];
+
+/// How a process relates to a Claude Code session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaudeLaunch {
+    /// The process runs the CLI itself (`claude`, `node .../cli.js`)
+    Direct,
+    /// A launcher (`npx`, wrapper script) that may spawn the CLI as a child.
+    /// It only counts as the session when no such child exists.
+    Launcher,
+}
+
+/// Classify a process by its argv. Returns `None` for anything that isn't
+/// Claude Code.
+pub fn classify_claude_command(cmd: &[String], extra_patterns: &[String]) -> Option<ClaudeLaunch> {
+    let first = cmd.first()?;
+    let program = basename(first);
+
+    if program == "claude" {
+        return Some(ClaudeLaunch::Direct);
+    }
+
+    if is_js_runtime(&program) {
+        // `node [flags] .../@anthropic-ai/claude-code/cli.js`, `bun x @anthropic-ai/claude-code`
+        if cmd[1..].iter().any(|arg| runs_claude_entry_point(arg)) {
+            return Some(ClaudeLaunch::Direct);
+        }
+        if cmd[1..].iter().any(|arg| is_claude_package_spec(arg)) {
+            return Some(ClaudeLaunch::Launcher);
+        }
+    }
+
+    let is_runner = PACKAGE_RUNNERS.contains(&program.as_str()) || is_dlx(cmd);
+    if is_runner && cmd[1..].iter().any(|arg| is_claude_package_spec(arg)) {
+        return Some(ClaudeLaunch::Launcher);
+    }
+
+    if !extra_patterns.is_empty() {
+        let command_line = cmd.join(" ");
+        if extra_patterns.iter().any(|p| wildcard_match(p, &command_line)) {
+            return Some(ClaudeLaunch::Launcher);
+        }
+    }
+
+    None
+}
+
+/// Whether argv belongs to the CLI bundled with the VS Code extension
+/// (`~/.vscode/extensions/anthropic.claude-code-<version>/...`, also under
+/// `~/.cursor` and other forks)
+pub fn is_vscode_extension_command(cmd: &[String]) -> bool {
+    cmd.iter().any(|arg| arg.contains(VSCODE_EXTENSION_DIR))
+}
+
+fn basename(arg: &str) -> String {
+    arg.rsplit('/').next().unwrap_or(arg).to_lowercase()
+}
+
+/// `node`, `node20`, `bun`, ... (versioned binaries are common with version managers)
+fn is_js_runtime(program: &str) -> bool {
+    let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '-');
+    JS_RUNTIMES.contains(&name)
+}
+
+/// `pnpm dlx` / `yarn dlx`
+fn is_dlx(cmd: &[String]) -> bool {
+    matches!(basename(&cmd[0]).as_str(), "pnpm" | "yarn") && cmd.get(1).map(|a| a == "dlx").unwrap_or(false)
+}
+
+/// A path to a JS file inside the CLI package or the VS Code extension, or to a `claude` bin shim
+fn runs_claude_entry_point(arg: &str) -> bool {
+    if arg.starts_with('-') {
+        return false;
+    }
+    arg.contains(&format!("{}/", CLAUDE_PACKAGE))
+        || arg.contains(VSCODE_EXTENSION_DIR)
+        || (arg.contains('/') && basename(arg) == "claude")
+}
+
+/// `@anthropic-ai/claude-code` or `@anthropic-ai/claude-code@1.2.3`
+fn is_claude_package_spec(arg: &str) -> bool {
+    arg == CLAUDE_PACKAGE
+        || arg
+            .strip_prefix(CLAUDE_PACKAGE)
+            .map(|rest| rest.starts_with('@'))
+            .unwrap_or(false)
+}
+
+/// Match `text` against `pattern`, where `*` matches any run of characters.
+/// Patterns without `*` match anywhere in the text.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    if !pattern.contains('*') {
+        return text.contains(pattern);
+    }
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut rest = text;
+
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
+        }
+    }
+
+    true
+}
@@ -1,3 +1,13 @@
 mod claude;
+mod kill;
+mod launch;
+mod pause;
+mod ports;
+mod tree;
 
-pub use claude::{ClaudeProcess, find_claude_processes, is_orphaned_process};
+pub use claude::{ClaudeProcess, find_claude_processes, hidden_launchers, is_orphaned_process};
+pub use kill::{KillReport, KillSettings, KillSignal, kill_process};
+pub use launch::{ClaudeLaunch, classify_claude_command, is_vscode_extension_command, wildcard_match};
+pub use pause::{is_paused, is_paused_in, pause_process, pause_process_in, resume_process, resume_process_in};
+pub use tree::{ProcessTree, children_map, describe_command, process_tree};
+pub use ports::{ListeningPort, dev_server_links, listening_ports, merge_dev_server_links, parse_lsof_listen, parse_proc_net_tcp};
@@ -0,0 +1,184 @@
+//! Freezing and thawing an agent with SIGSTOP/SIGCONT.
+//!
+//! Paused agents are recorded in `~/.agent-sessions/paused.json` by PID and
+//! start time, so they still show as Paused after the app restarts and a
+//! reused PID is never mistaken for them. An entry only counts while the
+//! process is actually stopped: if something else continues it (e.g. `fg` in
+//! its terminal) the entry is dropped on the next check.
+//!
+//! Stopping a terminal's foreground job hands the terminal back to its shell,
+//! the same as Ctrl+Z. After resuming, an agent that then tries to read the
+//! terminal may need `fg` there.
+
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System};
+
+use super::tree::children_map;
+
+/// Paused agent PID -> its start time
+type PausedMap = HashMap<u32, u64>;
+
+/// Paused agents per paused file, each loaded from disk on first use
+static PAUSED: Lazy<Mutex<HashMap<PathBuf, PausedMap>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn paused_path() -> Option<PathBuf> {
+    crate::settings::app_dir().map(|d| d.join("paused.json"))
+}
+
+/// Run `f` on the paused agents recorded in `path`, saving them if `f` says
+/// they changed
+fn with_paused<R>(path: &Path, f: impl FnOnce(&mut PausedMap) -> (R, bool)) -> R {
+    let mut all = PAUSED.lock().unwrap();
+    let paused = all.entry(path.to_path_buf()).or_insert_with(|| load_paused(path));
+    let (result, changed) = f(paused);
+    if changed {
+        save_paused(path, paused);
+    }
+    result
+}
+
+fn load_paused(path: &Path) -> PausedMap {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_paused(path: &Path, paused: &PausedMap) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(paused) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(path, json) {
+                warn!("Failed to save paused sessions to {:?}: {}", path, e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize paused sessions: {}", e),
+    }
+}
+
+/// Stop `pid` and all of its descendants. Returns every PID stopped.
+pub fn pause_process(pid: u32) -> Result<Vec<u32>, String> {
+    let path = paused_path().ok_or("no home directory")?;
+    pause_process_in(&path, pid)
+}
+
+/// `pause_process` recording the pause in an explicit paused file
+pub fn pause_process_in(path: &Path, pid: u32) -> Result<Vec<u32>, String> {
+    let system = all_processes();
+    let root = Pid::from_u32(pid);
+    let start_time = system
+        .process(root)
+        .map(|p| p.start_time())
+        .ok_or_else(|| format!("Process {} not found", pid))?;
+
+    // The agent first, so it can't spawn anything new while the tree is walked
+    let stopped = signal_tree(&system, root, Signal::Stop, false)?;
+
+    with_paused(path, |paused| {
+        paused.insert(pid, start_time);
+        ((), true)
+    });
+
+    info!("Paused process {} ({} processes stopped)", pid, stopped.len());
+    Ok(stopped)
+}
+
+/// Continue `pid` and all of its descendants. Returns every PID continued.
+pub fn resume_process(pid: u32) -> Result<Vec<u32>, String> {
+    let path = paused_path().ok_or("no home directory")?;
+    resume_process_in(&path, pid)
+}
+
+/// `resume_process` against an explicit paused file
+pub fn resume_process_in(path: &Path, pid: u32) -> Result<Vec<u32>, String> {
+    let system = all_processes();
+    let root = Pid::from_u32(pid);
+
+    let result = if system.process(root).is_some() {
+        // Descendants first, so the agent finds its tools running when it wakes
+        signal_tree(&system, root, Signal::Continue, true)
+    } else {
+        Err(format!("Process {} not found", pid))
+    };
+
+    with_paused(path, |paused| ((), paused.remove(&pid).is_some()));
+
+    if let Ok(continued) = &result {
+        info!("Resumed process {} ({} processes continued)", pid, continued.len());
+    }
+    result
+}
+
+/// Whether `pid` was paused by `pause_process` and is still stopped
+pub fn is_paused(pid: u32) -> bool {
+    paused_path().is_some_and(|path| is_paused_in(&path, pid))
+}
+
+/// `is_paused` against an explicit paused file
+pub fn is_paused_in(path: &Path, pid: u32) -> bool {
+    with_paused(path, |paused| {
+        let start_time = match paused.get(&pid) {
+            Some(t) => *t,
+            None => return (false, false),
+        };
+
+        let mut system = System::new();
+        let sys_pid = Pid::from_u32(pid);
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&[sys_pid]), ProcessRefreshKind::new());
+
+        let stopped = system
+            .process(sys_pid)
+            .map(|p| p.start_time() == start_time && p.status() == ProcessStatus::Stop)
+            .unwrap_or(false);
+
+        if !stopped {
+            debug!("Process {} is no longer paused, forgetting it", pid);
+            paused.remove(&pid);
+        }
+        (stopped, !stopped)
+    })
+}
+
+fn all_processes() -> System {
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, ProcessRefreshKind::new());
+    system
+}
+
+/// Send `signal` to `root` and every descendant, root first or last.
+/// Fails only if the root itself can't be signalled.
+fn signal_tree(system: &System, root: Pid, signal: Signal, root_last: bool) -> Result<Vec<u32>, String> {
+    let children = children_map(system);
+    let mut pids = vec![root];
+    let mut i = 0;
+    while i < pids.len() {
+        for child in children.get(&pids[i]).into_iter().flatten() {
+            if !pids.contains(child) {
+                pids.push(*child);
+            }
+        }
+        i += 1;
+    }
+    if root_last {
+        pids.reverse();
+    }
+
+    let mut signalled = Vec::new();
+    for pid in pids {
+        let sent = system.process(pid).and_then(|p| p.kill_with(signal)).unwrap_or(false);
+        if sent {
+            signalled.push(pid.as_u32());
+        } else if pid == root {
+            return Err(format!("Failed to send {:?} to process {}", signal, root));
+        } else {
+            debug!("Failed to send {:?} to descendant {}", signal, pid);
+        }
+    }
+    Ok(signalled)
+}
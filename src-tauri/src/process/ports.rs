@@ -0,0 +1,185 @@
+//! Listening TCP ports of an agent's descendant processes.
+//!
+//! Dev servers the agent starts (`npm run dev`, `cargo run`, ...) show up as
+//! `http://localhost:<port>` session links. On Linux sockets are matched via
+//! `/proc/net/tcp{,6}` and each process's fd inodes; on macOS via
+//! `lsof -iTCP -sTCP:LISTEN`. Results are cached briefly per PID set so
+//! frequent polls don't spawn lsof every time.
+
+use log::{debug, trace};
+use once_cell::sync::Lazy;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::session::ProjectLink;
+use super::ProcessTree;
+
+/// How long a port scan is reused; also how long a dead server's link can linger
+const PORTS_TTL: Duration = Duration::from_secs(4);
+
+/// TCP state `LISTEN` in `/proc/net/tcp`
+const TCP_LISTEN: &str = "0A";
+
+/// A TCP port some process is listening on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ListeningPort {
+    pub pid: u32,
+    pub port: u16,
+}
+
+/// Scan results keyed by the sorted PID set, with when they were taken
+type PortsCache = HashMap<Vec<u32>, (Vec<ListeningPort>, Instant)>;
+
+static PORTS_CACHE: Lazy<Mutex<PortsCache>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Session links for every port the given processes listen on
+pub fn dev_server_links(pids: &[u32]) -> Vec<ProjectLink> {
+    let ports: BTreeSet<u16> = listening_ports(pids).into_iter().map(|p| p.port).collect();
+
+    ports
+        .into_iter()
+        .map(|port| ProjectLink {
+            label: format!("localhost:{}", port),
+            url: format!("http://localhost:{}", port),
+            icon: None,
+            detected: true,
+        })
+        .collect()
+}
+
+/// Add a link for each dev server in `tree` to `links`, skipping URLs that
+/// are already there (e.g. a configured link to the same port)
+pub fn merge_dev_server_links(links: &mut Vec<ProjectLink>, tree: &ProcessTree) {
+    for link in dev_server_links(&tree.descendants) {
+        if !links.iter().any(|l| l.url == link.url) {
+            links.push(link);
+        }
+    }
+}
+
+/// Listening TCP ports owned by any of `pids`
+pub fn listening_ports(pids: &[u32]) -> Vec<ListeningPort> {
+    if pids.is_empty() {
+        return Vec::new();
+    }
+
+    let mut key = pids.to_vec();
+    key.sort_unstable();
+
+    let mut cache = PORTS_CACHE.lock().unwrap();
+    if let Some((ports, scanned_at)) = cache.get(&key) {
+        if scanned_at.elapsed() <= PORTS_TTL {
+            return ports.clone();
+        }
+    }
+
+    let ports = scan_listening_ports(&key);
+    if !ports.is_empty() {
+        debug!("Listening ports for pids {:?}: {:?}", key, ports);
+    }
+
+    cache.retain(|_, (_, scanned_at)| scanned_at.elapsed() <= PORTS_TTL);
+    cache.insert(key, (ports.clone(), Instant::now()));
+    ports
+}
+
+#[cfg(target_os = "linux")]
+fn scan_listening_ports(pids: &[u32]) -> Vec<ListeningPort> {
+    let mut inodes = HashMap::new();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        if let Ok(content) = std::fs::read_to_string(table) {
+            inodes.extend(parse_proc_net_tcp(&content));
+        }
+    }
+    if inodes.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ports = BTreeSet::new();
+    for &pid in pids {
+        let fds = match std::fs::read_dir(format!("/proc/{}/fd", pid)) {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        for fd in fds.flatten() {
+            let target = match std::fs::read_link(fd.path()) {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            if let Some(port) = socket_inode(&target.to_string_lossy()).and_then(|i| inodes.get(&i)) {
+                trace!("pid {} listens on port {}", pid, port);
+                ports.insert(ListeningPort { pid, port: *port });
+            }
+        }
+    }
+    ports.into_iter().collect()
+}
+
+#[cfg(target_os = "macos")]
+fn scan_listening_ports(pids: &[u32]) -> Vec<ListeningPort> {
+    let pid_list = pids.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(",");
+    let output = std::process::Command::new("lsof")
+        .args(["-nP", "-a", "-iTCP", "-sTCP:LISTEN", "-Fpn", "-p", &pid_list])
+        .output();
+
+    match output {
+        // lsof exits 1 when nothing matched
+        Ok(o) => parse_lsof_listen(&String::from_utf8_lossy(&o.stdout)),
+        Err(e) => {
+            debug!("Failed to run lsof for listening ports: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn scan_listening_ports(_pids: &[u32]) -> Vec<ListeningPort> {
+    Vec::new()
+}
+
+/// Map socket inode -> port for listening sockets in a `/proc/net/tcp` table
+pub fn parse_proc_net_tcp(content: &str) -> HashMap<u64, u16> {
+    content
+        .lines()
+        .skip(1) // header
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || fields[3] != TCP_LISTEN {
+                return None;
+            }
+            let port_hex = fields[1].rsplit(':').next()?;
+            let port = u16::from_str_radix(port_hex, 16).ok()?;
+            let inode = fields[9].parse::<u64>().ok()?;
+            (inode != 0).then_some((inode, port))
+        })
+        .collect()
+}
+
+/// `socket:[12345]` -> 12345
+fn socket_inode(link_target: &str) -> Option<u64> {
+    link_target
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Parse `lsof -Fpn` output: `p<pid>` lines followed by `n<addr>:<port>` lines
+pub fn parse_lsof_listen(output: &str) -> Vec<ListeningPort> {
+    let mut ports = BTreeSet::new();
+    let mut current_pid = None;
+
+    for line in output.lines() {
+        if let Some(pid) = line.strip_prefix('p') {
+            current_pid = pid.parse::<u32>().ok();
+        } else if let (Some(addr), Some(pid)) = (line.strip_prefix('n'), current_pid) {
+            if let Some(port) = addr.rsplit(':').next().and_then(|p| p.parse::<u16>().ok()) {
+                ports.insert(ListeningPort { pid, port });
+            }
+        }
+    }
+
+    ports.into_iter().collect()
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use sysinfo::{Pid, System};
+
+use crate::session::ChildProcess;
+
+/// Shells the agent wraps tool commands in; their children are what's interesting
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "fish", "dash"];
+
+/// Longest command line reported for a child process
+const MAX_COMMAND_LEN: usize = 80;
+
+/// Resource usage of an agent process and all of its descendants
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProcessTree {
+    /// CPU of the agent plus every descendant
+    pub cpu_usage: f32,
+    /// Resident memory of the agent plus every descendant, in bytes
+    pub memory: u64,
+    /// Commands the agent is running through a shell (its tool calls), most
+    /// recently started first. Shell wrappers are looked through, and a listed
+    /// command's own children are not listed. Processes the agent starts
+    /// directly, such as MCP servers and nested agents, live as long as the
+    /// session and are left out.
+    pub children: Vec<ChildProcess>,
+    /// Every descendant PID, excluding the agent itself
+    pub descendants: Vec<u32>,
+}
+
+/// Map of parent PID to child PIDs for every process in `system`
+pub fn children_map(system: &System) -> HashMap<Pid, Vec<Pid>> {
+    let mut map: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in system.processes() {
+        if let Some(parent) = process.parent() {
+            map.entry(parent).or_default().push(*pid);
+        }
+    }
+    map
+}
+
+/// Collect the resource tree rooted at `root`. `now` is seconds since the UNIX epoch.
+pub fn process_tree(system: &System, children: &HashMap<Pid, Vec<Pid>>, root: Pid, now: u64) -> ProcessTree {
+    let mut tree = ProcessTree::default();
+    let mut visited = HashSet::new();
+
+    // (pid, whether a listed ancestor already covers it)
+    let mut stack = vec![(root, false)];
+    while let Some((pid, covered)) = stack.pop() {
+        if !visited.insert(pid) {
+            continue;
+        }
+        let process = match system.process(pid) {
+            Some(p) => p,
+            None => continue,
+        };
+
+        tree.cpu_usage += process.cpu_usage();
+        tree.memory += process.memory();
+        if pid != root {
+            tree.descendants.push(pid.as_u32());
+        }
+
+        let mut child_covered = covered;
+        if pid != root && !covered {
+            let argv: Vec<String> = process.cmd().iter().map(|a| a.to_string_lossy().to_string()).collect();
+            if is_shell(&argv, &process.name().to_string_lossy()) {
+                // A tool call; what it runs is listed
+            } else if process.parent() == Some(root) {
+                // Started by the agent itself, not through a shell
+                child_covered = true;
+            } else {
+                tree.children.push(ChildProcess {
+                    pid: pid.as_u32(),
+                    command: describe_command(&argv, &process.name().to_string_lossy()),
+                    runtime_secs: now.saturating_sub(process.start_time()),
+                });
+                child_covered = true;
+            }
+        }
+
+        for child in children.get(&pid).into_iter().flatten() {
+            stack.push((*child, child_covered));
+        }
+    }
+
+    tree.children.sort_by_key(|c| c.runtime_secs);
+    tree
+}
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn is_shell(argv: &[String], name: &str) -> bool {
+    let program = argv.first().map(|a| basename(a)).unwrap_or(name);
+    SHELLS.contains(&program.trim_start_matches('-'))
+}
+
+/// Short, readable command line: program basename plus arguments, truncated
+pub fn describe_command(argv: &[String], name: &str) -> String {
+    let mut command = match argv.split_first() {
+        Some((program, args)) => std::iter::once(basename(program))
+            .chain(args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => name.to_string(),
+    };
+
+    if command.chars().count() > MAX_COMMAND_LEN {
+        command = command.chars().take(MAX_COMMAND_LEN - 1).collect::<String>() + "…";
+    }
+    command
+}
@@ -0,0 +1,394 @@
+//! User-defined automation rules, evaluated after every scan.
+//!
+//! Rules live under `automation` in the global config. A rule fires once when
+//! every condition in `when` starts holding for a session, runs its `then`
+//! actions, and can fire again for that session only after the conditions
+//! stopped holding in between:
+//!
+//! ```json
+//! "automation": {
+//!   "dry_run": false,
+//!   "rules": [
+//!     {"name": "Reap idle scratch sessions",
+//!      "when": {"project": "scratch", "status": ["idle"], "for_secs": 7200},
+//!      "then": [{"action": "kill"}]},
+//!     {"name": "Blocked for 10 minutes",
+//!      "when": {"status": ["waiting"], "for_secs": 600},
+//!      "then": [{"action": "webhook", "hook": "slack"}]},
+//!     {"name": "Context almost full", "when": {"context_below": 10}, "then": [{"action": "notify"}]}
+//!   ]
+//! }
+//! ```
+//!
+//! `for_secs` counts from when this app first saw the session in its current
+//! status, so it restarts with the app. With `dry_run`, globally or per rule,
+//! actions are only written to `~/.agent-sessions/rules.log`, where every
+//! firing is recorded either way.
+
+use chrono::Utc;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::jsonl_log;
+use crate::notifications::Notification;
+use crate::process::kill_process;
+use crate::session::{enum_name, AgentType, Session, SessionStatus, StatusDwell};
+use crate::webhooks::{deliver, render_template};
+
+/// How often the latest scan is checked for being new
+const TICK: Duration = Duration::from_secs(1);
+
+/// The `automation` section of the global config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutomationSettings {
+    /// Log what would fire instead of running any action
+    pub dry_run: bool,
+    #[serde(deserialize_with = "valid_rules")]
+    pub rules: Vec<Rule>,
+}
+
+/// Parse each rule on its own, so a malformed one is skipped with a warning
+/// instead of disabling every other rule with it
+fn valid_rules<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<Rule>, D::Error> {
+    let entries = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let name = entry
+                .get("name")
+                .and_then(|n| n.as_str())
+                .map(|n| format!("{:?}", n))
+                .unwrap_or_else(|| format!("#{}", index + 1));
+            serde_json::from_value(entry)
+                .map_err(|e| warn!("Ignoring automation rule {}: {}", name, e))
+                .ok()
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    #[serde(default)]
+    pub when: RuleCondition,
+    pub then: Vec<RuleAction>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Conditions on a session, all of which must hold. Unknown keys are
+/// rejected so a misspelled condition can't widen a rule to every session;
+/// the rule is skipped instead.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleCondition {
+    /// Project name or path
+    pub project: Option<String>,
+    pub branch: Option<String>,
+    pub agent_type: Option<AgentType>,
+    /// Any of these statuses; empty matches any
+    pub status: Vec<SessionStatus>,
+    /// Seconds spent in the current status, at least
+    pub for_secs: Option<u64>,
+    /// Context window left, in percent, below this
+    pub context_below: Option<f32>,
+    /// CPU of the agent's process tree, in percent of one core, above this
+    pub cpu_above: Option<f32>,
+    /// Memory of the agent's process tree above this many MiB
+    pub memory_above_mb: Option<u64>,
+}
+
+impl RuleCondition {
+    /// Whether `session`, `in_status` into its current status, satisfies every condition
+    pub fn matches(&self, session: &Session, in_status: Duration) -> bool {
+        let project_ok = self
+            .project
+            .as_ref()
+            .map(|p| p == &session.project_name || p == &session.project_path)
+            .unwrap_or(true);
+        let branch_ok = self
+            .branch
+            .as_ref()
+            .map(|b| session.git_branch.as_ref() == Some(b))
+            .unwrap_or(true);
+
+        project_ok
+            && branch_ok
+            && self.agent_type.as_ref().map(|a| a == &session.agent_type).unwrap_or(true)
+            && (self.status.is_empty() || self.status.contains(&session.status))
+            && self.for_secs.map(|s| in_status >= Duration::from_secs(s)).unwrap_or(true)
+            && self
+                .context_below
+                .map(|limit| session.context_window_percent.map(|p| p < limit).unwrap_or(false))
+                .unwrap_or(true)
+            && self
+                .cpu_above
+                .map(|limit| session.tree_cpu_usage.max(session.cpu_usage) > limit)
+                .unwrap_or(true)
+            && self
+                .memory_above_mb
+                .map(|limit| session.memory_bytes > limit * 1024 * 1024)
+                .unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RuleAction {
+    /// Desktop notification titled with the rule name; `message` is a template
+    Notify {
+        #[serde(default)]
+        message: Option<String>,
+    },
+    /// Post to the webhook with this name, or to every configured webhook
+    Webhook {
+        #[serde(default)]
+        hook: Option<String>,
+    },
+    /// Run with `sh -c` in the project directory, with the hook `AGENT_*`
+    /// variables plus `AGENT_PID`, `AGENT_PROJECT` and `AGENT_RULE` set
+    Command { command: String },
+    /// Kill as configured under `kill`
+    Kill,
+    /// Bring the session's terminal to the front
+    Focus,
+}
+
+impl RuleAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleAction::Notify { .. } => "notify",
+            RuleAction::Webhook { .. } => "webhook",
+            RuleAction::Command { .. } => "command",
+            RuleAction::Kill => "kill",
+            RuleAction::Focus => "focus",
+        }
+    }
+}
+
+/// A rule that started matching a session
+#[derive(Debug, Clone)]
+pub struct Firing {
+    pub rule: Rule,
+    pub session: Session,
+    pub dry_run: bool,
+}
+
+/// Status dwell times and which rules currently match which sessions
+#[derive(Default)]
+pub struct RuleEngine {
+    dwell: StatusDwell,
+    /// (rule, session id) pairs that matched on the last evaluation. Rules are
+    /// keyed by their JSON, so editing one resets only its own matches.
+    matched: HashSet<(String, String)>,
+}
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluate every rule against a scan taken at `now`, returning the ones
+    /// that started matching a session since the previous scan
+    pub fn evaluate(&mut self, sessions: &[Session], settings: &AutomationSettings, now: Instant) -> Vec<Firing> {
+        self.dwell.update(sessions, now);
+
+        let mut matched = HashSet::new();
+        let mut firings = Vec::new();
+        for rule in &settings.rules {
+            let rule_key = serde_json::to_string(rule).unwrap_or_default();
+            for session in sessions {
+                if !rule.when.matches(session, self.dwell.in_status(&session.id, now)) {
+                    continue;
+                }
+
+                let key = (rule_key.clone(), session.id.clone());
+                if !self.matched.contains(&key) {
+                    firings.push(Firing {
+                        rule: rule.clone(),
+                        session: session.clone(),
+                        dry_run: settings.dry_run || rule.dry_run,
+                    });
+                }
+                matched.insert(key);
+            }
+        }
+        self.matched = matched;
+        firings
+    }
+}
+
+/// What the app provides for actions that need its window
+pub trait RuleHost {
+    fn notify(&self, notification: &Notification);
+    fn focus(&self, session: &Session) -> Result<(), String>;
+}
+
+/// One action of a firing, as written to the rules log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleLogEntry {
+    /// RFC 3339 timestamp
+    pub at: String,
+    pub rule: String,
+    pub session_id: String,
+    pub project: String,
+    pub action: String,
+    pub dry_run: bool,
+    pub error: Option<String>,
+}
+
+/// Run (or, in dry-run mode, only log) every action of `firing`
+pub fn execute(firing: &Firing, host: &dyn RuleHost, log_path: Option<&Path>) -> Vec<RuleLogEntry> {
+    let session = &firing.session;
+    let mut entries = Vec::new();
+
+    for action in &firing.rule.then {
+        let result = if firing.dry_run {
+            info!("[dry run] Rule {:?} would {} {}", firing.rule.name, action.name(), session.id);
+            Ok(())
+        } else {
+            info!("Rule {:?}: {} {}", firing.rule.name, action.name(), session.id);
+            run_action(action, &firing.rule, session, host)
+        };
+        if let Err(e) = &result {
+            warn!("Rule {:?} failed to {} {}: {}", firing.rule.name, action.name(), session.id, e);
+        }
+
+        let entry = RuleLogEntry {
+            at: Utc::now().to_rfc3339(),
+            rule: firing.rule.name.clone(),
+            session_id: session.id.clone(),
+            project: session.project_name.clone(),
+            action: action.name().to_string(),
+            dry_run: firing.dry_run,
+            error: result.err(),
+        };
+        if let Some(path) = log_path {
+            jsonl_log::append(path, &entry);
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+fn run_action(action: &RuleAction, rule: &Rule, session: &Session, host: &dyn RuleHost) -> Result<(), String> {
+    match action {
+        RuleAction::Notify { message } => {
+            let mut notification = Notification::for_session(session);
+            if let Some(message) = message {
+                notification.body = render_template(message, session, None, Some(&rule.name));
+            } else {
+                notification.body = notification.title.clone();
+            }
+            notification.title = rule.name.clone();
+            host.notify(&notification);
+            Ok(())
+        }
+        RuleAction::Webhook { hook } => {
+            let settings = crate::settings::get().webhooks;
+            let hooks: Vec<_> = settings
+                .hooks
+                .iter()
+                .filter(|h| hook.as_ref().map(|name| &h.label() == name).unwrap_or(true))
+                .cloned()
+                .collect();
+            if hooks.is_empty() {
+                return Err(match hook {
+                    Some(name) => format!("no webhook named {:?}", name),
+                    None => "no webhooks configured".to_string(),
+                });
+            }
+
+            let transition = format!("rule: {}", rule.name);
+            for hook in hooks {
+                let body = hook.rule_payload(&rule.name, session);
+                let (settings, session_id, transition) = (settings.clone(), session.id.clone(), transition.clone());
+                // Retries sleep; keep them off the rules thread
+                thread::spawn(move || {
+                    let log = crate::webhooks::log_path();
+                    if let Err(e) = deliver(&hook, &body, &session_id, &transition, &settings, log.as_deref()) {
+                        warn!("Webhook {} failed for {}: {}", hook.label(), session_id, e);
+                    }
+                });
+            }
+            Ok(())
+        }
+        RuleAction::Command { command } => run_command(command, rule, session).map(|_| ()),
+        RuleAction::Kill => kill_process(session.pid, &crate::settings::get().kill).map(|_| ()),
+        RuleAction::Focus => host.focus(session),
+    }
+}
+
+/// Start `command` with `sh -c` and the session in its environment, reaping
+/// it in the background. Runs in the project directory when it still exists.
+pub fn run_command(command: &str, rule: &Rule, session: &Session) -> Result<u32, String> {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(command)
+        .env("AGENT_SESSION_ID", &session.id)
+        .env("AGENT_PID", session.pid.to_string())
+        .env("AGENT_PROJECT", &session.project_name)
+        .env("AGENT_PROJECT_PATH", &session.project_path)
+        .env("AGENT_BRANCH", session.git_branch.as_deref().unwrap_or(""))
+        .env("AGENT_STATUS", enum_name(&session.status))
+        .env("AGENT_RULE", &rule.name)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if Path::new(&session.project_path).is_dir() {
+        cmd.current_dir(&session.project_path);
+    }
+
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to run {:?}: {}", command, e))?;
+    let pid = child.id();
+    let rule_name = rule.name.clone();
+    thread::spawn(move || match child.wait() {
+        Ok(status) if !status.success() => warn!("Rule {:?} command exited with {}", rule_name, status),
+        Ok(_) => {}
+        Err(e) => warn!("Rule {:?} command: {}", rule_name, e),
+    });
+    Ok(pid)
+}
+
+/// Path of the rules log (`~/.agent-sessions/rules.log`)
+pub fn log_path() -> Option<PathBuf> {
+    crate::settings::app_dir().map(|d| d.join("rules.log"))
+}
+
+/// Evaluate the rules against every new scan in the background
+pub fn start<H: RuleHost + Send + 'static>(host: H) {
+    thread::spawn(move || {
+        let mut engine = RuleEngine::new();
+        let mut last_scan: Option<Instant> = None;
+        loop {
+            thread::sleep(TICK);
+
+            let (response, taken_at) = match crate::api::latest() {
+                Some(latest) => latest,
+                None => continue,
+            };
+            if last_scan == Some(taken_at) {
+                continue;
+            }
+            last_scan = Some(taken_at);
+
+            let settings = crate::settings::get().automation;
+            if settings.rules.is_empty() {
+                continue;
+            }
+            let firings = engine.evaluate(&response.sessions, &settings, taken_at);
+            debug!("Automation: {} rule(s) fired", firings.len());
+            for firing in &firings {
+                execute(firing, &host, log_path().as_deref());
+            }
+        }
+    });
+}
@@ -1,11 +1,12 @@
-use log::debug;
+use log::{debug, warn};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Mutex;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use super::model::ProjectLink;
+use super::status::StatusThresholds;
 
 // ---------------------------------------------------------------------------
 // TTL Cache (same pattern as git.rs)
@@ -56,9 +57,21 @@ impl<T: Clone> TtlCache<T> {
 // Static cache
 // ---------------------------------------------------------------------------
 
-static CONFIG_CACHE: Lazy<Mutex<TtlCache<ProjectConfig>>> =
+/// Config plus the file mtime it was read at, so edits are picked up
+/// on the next poll instead of waiting out the TTL
+type CachedConfig = (ProjectConfig, Option<SystemTime>);
+
+static CONFIG_CACHE: Lazy<Mutex<TtlCache<CachedConfig>>> =
     Lazy::new(|| Mutex::new(TtlCache::new(Duration::from_secs(60))));
 
+/// Config mtime and errors of an invalid `statusThresholds` already warned about
+type ThresholdWarning = (Option<SystemTime>, String);
+
+/// Last warning per project path, so each problem is logged once rather than
+/// on every poll
+static THRESHOLD_WARNINGS: Lazy<Mutex<HashMap<String, ThresholdWarning>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 // ---------------------------------------------------------------------------
 // Config file schema
 // ---------------------------------------------------------------------------
@@ -70,6 +83,8 @@ pub struct ProjectConfig {
     pub links: Vec<ProjectLink>,
     #[serde(default)]
     pub session_links: HashMap<String, Vec<ProjectLink>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_thresholds: Option<StatusThresholdOverrides>,
 }
 
 impl Default for ProjectConfig {
@@ -77,6 +92,39 @@ impl Default for ProjectConfig {
         ProjectConfig {
             links: Vec::new(),
             session_links: HashMap::new(),
+            status_thresholds: None,
+        }
+    }
+}
+
+/// Per-project overrides for `StatusThresholds`; unset fields keep the global value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusThresholdOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_quiet_secs: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_quiet_secs: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_active_percent: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subagent_active_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sibling_active_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_confirmations: Option<u32>,
+}
+
+impl StatusThresholdOverrides {
+    /// Apply these overrides on top of `base`
+    pub fn apply_to(&self, base: &StatusThresholds) -> StatusThresholds {
+        StatusThresholds {
+            text_quiet_secs: self.text_quiet_secs.unwrap_or(base.text_quiet_secs),
+            tool_quiet_secs: self.tool_quiet_secs.unwrap_or(base.tool_quiet_secs),
+            cpu_active_percent: self.cpu_active_percent.unwrap_or(base.cpu_active_percent),
+            subagent_active_secs: self.subagent_active_secs.unwrap_or(base.subagent_active_secs),
+            sibling_active_secs: self.sibling_active_secs.unwrap_or(base.sibling_active_secs),
+            quiet_confirmations: self.quiet_confirmations.unwrap_or(base.quiet_confirmations),
         }
     }
 }
@@ -86,22 +134,55 @@ impl Default for ProjectConfig {
 // ---------------------------------------------------------------------------
 
 /// Read full project config from `.agent-sessions.json` in the project root.
-/// Returns default config on missing file or parse errors. Cached for 60s.
+/// Returns default config on missing file or parse errors. Cached for 60s,
+/// or until the file's mtime changes.
 pub fn get_config(project_path: &str) -> ProjectConfig {
+    let modified = config_modified(project_path);
     {
         let cache = CONFIG_CACHE.lock().unwrap();
-        if let Some(cached) = cache.get(project_path) {
-            return cached;
+        if let Some((cached, cached_modified)) = cache.get(project_path) {
+            if cached_modified == modified {
+                return cached;
+            }
         }
     }
 
     let result = read_config(project_path);
 
     let mut cache = CONFIG_CACHE.lock().unwrap();
-    cache.insert(project_path.to_string(), result.clone());
+    cache.insert(project_path.to_string(), (result.clone(), modified));
     result
 }
 
+/// Status thresholds for a project: global settings with the project's
+/// `statusThresholds` overrides applied. Invalid overrides are ignored, with
+/// one warning per version of the config file.
+pub fn get_status_thresholds(project_path: &str) -> StatusThresholds {
+    let global = crate::settings::get().status_thresholds;
+
+    let overrides = match get_config(project_path).status_thresholds {
+        Some(o) => o,
+        None => return global,
+    };
+
+    let merged = overrides.apply_to(&global);
+    match merged.validate() {
+        Ok(()) => merged,
+        Err(errors) => {
+            let warning = (config_modified(project_path), errors.join("; "));
+            let mut warned = THRESHOLD_WARNINGS.lock().unwrap();
+            if warned.get(project_path) != Some(&warning) {
+                warn!(
+                    "Ignoring invalid statusThresholds in {}/.agent-sessions.json: {}",
+                    project_path, warning.1
+                );
+                warned.insert(project_path.to_string(), warning);
+            }
+            global
+        }
+    }
+}
+
 /// Read project links from `.agent-sessions.json` in the project root.
 /// Returns an empty vec on missing file or parse errors. Cached for 60s.
 pub fn get_project_links(project_path: &str) -> Vec<ProjectLink> {
@@ -156,6 +237,14 @@ pub fn cleanup_links_cache(active_project_paths: &std::collections::HashSet<Stri
 // Internal
 // ---------------------------------------------------------------------------
 
+fn config_modified(project_path: &str) -> Option<SystemTime> {
+    std::path::Path::new(project_path)
+        .join(".agent-sessions.json")
+        .metadata()
+        .and_then(|m| m.modified())
+        .ok()
+}
+
 fn read_config(project_path: &str) -> ProjectConfig {
     let config_path = std::path::Path::new(project_path).join(".agent-sessions.json");
 
@@ -0,0 +1,120 @@
+//! Project directory name -> real project path, from transcript contents.
+//!
+//! Claude encodes the launch directory into the project folder name lossily
+//! (`/`, `.` and other characters all become `-`), so decoding it by probing
+//! the filesystem guesses wrong for names containing dashes or dots and fails
+//! for deleted directories. Every JSONL line carries the session's `cwd`, so
+//! the index reads it from the transcripts instead. Entries are keyed by the
+//! project folder's mtime, which changes when transcripts are added or removed
+//! but not when they are appended to. Folders without a matching `cwd` yet are
+//! not cached, so a `cwd` line appended later is still found.
+
+use log::{debug, trace};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use super::parser::convert_path_to_dir_name;
+
+/// Lines read from the top of each transcript while looking for a `cwd`
+const CWD_SCAN_LINES: usize = 50;
+
+#[derive(Deserialize)]
+struct CwdLine {
+    cwd: Option<String>,
+}
+
+struct IndexEntry {
+    modified: Option<SystemTime>,
+    project_path: String,
+}
+
+static CWD_INDEX: Lazy<Mutex<HashMap<PathBuf, IndexEntry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Real project path for a `~/.claude/projects/<dir>` folder, if any of its
+/// transcripts recorded a `cwd` that encodes to the folder's name
+pub fn project_path_for_dir(project_dir: &Path) -> Option<String> {
+    let modified = fs::metadata(project_dir).and_then(|m| m.modified()).ok();
+
+    if let Some(entry) = CWD_INDEX.lock().unwrap().get(project_dir) {
+        if entry.modified == modified {
+            return Some(entry.project_path.clone());
+        }
+    }
+
+    let project_path = scan_project_dir(project_dir);
+    debug!("Indexed {:?} -> {:?}", project_dir, project_path);
+
+    if let Some(path) = &project_path {
+        CWD_INDEX.lock().unwrap().insert(
+            project_dir.to_path_buf(),
+            IndexEntry {
+                modified,
+                project_path: path.clone(),
+            },
+        );
+    }
+    project_path
+}
+
+/// Drop index entries for project folders that no longer exist
+pub fn cleanup_cwd_index() {
+    CWD_INDEX.lock().unwrap().retain(|dir, _| dir.exists());
+}
+
+fn scan_project_dir(project_dir: &Path) -> Option<String> {
+    let dir_name = project_dir.file_name()?.to_str()?;
+
+    // Newest transcripts first - they are the ones most likely to be open
+    let mut files: Vec<(PathBuf, SystemTime)> = fs::read_dir(project_dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|e| e == "jsonl").unwrap_or(false))
+        .filter_map(|p| {
+            let modified = p.metadata().and_then(|m| m.modified()).ok()?;
+            Some((p, modified))
+        })
+        .collect();
+    files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+
+    files
+        .iter()
+        .find_map(|(path, _)| cwd_from_transcript(path, dir_name))
+}
+
+/// First `cwd` in the transcript that encodes to `dir_name`. Later lines can
+/// carry a different cwd after the session `cd`s elsewhere, so each candidate
+/// is checked against the folder name.
+pub fn cwd_from_transcript(path: &Path, dir_name: &str) -> Option<String> {
+    let file = File::open(path).ok()?;
+
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .take(CWD_SCAN_LINES)
+        .filter_map(|line| serde_json::from_str::<CwdLine>(&line).ok()?.cwd)
+        .find(|cwd| {
+            let matches = dir_name_matches(cwd, dir_name);
+            if !matches {
+                trace!("cwd {} in {:?} doesn't encode to {}", cwd, path, dir_name);
+            }
+            matches
+        })
+}
+
+/// Whether Claude would store transcripts for `path` under `dir_name`.
+/// Claude replaces every character outside `[A-Za-z0-9]` with `-`; older
+/// versions kept dots, which `convert_path_to_dir_name` still models.
+pub fn dir_name_matches(path: &str, dir_name: &str) -> bool {
+    let encoded: String = path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    encoded == dir_name || convert_path_to_dir_name(path) == dir_name
+}
@@ -0,0 +1,47 @@
+//! How long each session has been in its current status, as seen across
+//! successive scans.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::model::{Session, SessionStatus};
+
+/// When each session entered the status it had in the latest scan. Counts
+/// from the first scan that saw it, so dwell restarts with the app.
+#[derive(Debug, Default)]
+pub struct StatusDwell {
+    since: HashMap<String, (SessionStatus, Instant)>,
+}
+
+impl StatusDwell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a scan taken at `now`, forgetting sessions no longer in it
+    pub fn update(&mut self, sessions: &[Session], now: Instant) {
+        for session in sessions {
+            let entry = self
+                .since
+                .entry(session.id.clone())
+                .or_insert_with(|| (session.status.clone(), now));
+            if entry.0 != session.status {
+                *entry = (session.status.clone(), now);
+            }
+        }
+        self.since.retain(|id, _| sessions.iter().any(|s| &s.id == id));
+    }
+
+    /// Status `session_id` had in the latest scan
+    pub fn status(&self, session_id: &str) -> Option<&SessionStatus> {
+        self.since.get(session_id).map(|(status, _)| status)
+    }
+
+    /// Time `session_id` has spent in its current status as of `now`
+    pub fn in_status(&self, session_id: &str, now: Instant) -> Duration {
+        self.since
+            .get(session_id)
+            .map(|(_, since)| now.saturating_duration_since(*since))
+            .unwrap_or_default()
+    }
+}
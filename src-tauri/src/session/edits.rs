@@ -0,0 +1,99 @@
+//! Files a session has edited, from the `tool_use` blocks in its transcript.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use super::profiles;
+
+/// Tools that modify files, and the input field naming the file
+const EDIT_TOOLS: &[(&str, &str)] = &[
+    ("Edit", "file_path"),
+    ("MultiEdit", "file_path"),
+    ("Write", "file_path"),
+    ("NotebookEdit", "notebook_path"),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditedFile {
+    pub path: String,
+    /// Timestamp of the most recent edit, as recorded in the transcript
+    pub last_edited_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TranscriptLine {
+    timestamp: Option<String>,
+    message: Option<TranscriptMessage>,
+}
+
+#[derive(Deserialize)]
+struct TranscriptMessage {
+    content: Option<serde_json::Value>,
+}
+
+/// Every file edited in the transcript at `jsonl_path`, most recently edited first
+pub fn edited_files(jsonl_path: &Path) -> Vec<EditedFile> {
+    let file = match File::open(jsonl_path) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(),
+    };
+
+    // path -> (line index of last edit, timestamp)
+    let mut edits: HashMap<String, (usize, Option<String>)> = HashMap::new();
+
+    for (index, line) in BufReader::new(file).lines().map_while(Result::ok).enumerate() {
+        // Cheap pre-filter; most lines are not tool calls
+        if !line.contains("\"tool_use\"") {
+            continue;
+        }
+        let parsed: TranscriptLine = match serde_json::from_str(&line) {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+        let blocks = match parsed.message.and_then(|m| m.content) {
+            Some(serde_json::Value::Array(blocks)) => blocks,
+            _ => continue,
+        };
+
+        for block in &blocks {
+            if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+                continue;
+            }
+            let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            let field = match EDIT_TOOLS.iter().find(|(tool, _)| *tool == name) {
+                Some((_, field)) => field,
+                None => continue,
+            };
+            if let Some(path) = block.get("input").and_then(|i| i.get(*field)).and_then(|p| p.as_str()) {
+                edits.insert(path.to_string(), (index, parsed.timestamp.clone()));
+            }
+        }
+    }
+
+    let mut files: Vec<(String, (usize, Option<String>))> = edits.into_iter().collect();
+    files.sort_by_key(|(_, (index, _))| std::cmp::Reverse(*index));
+    files
+        .into_iter()
+        .map(|(path, (_, last_edited_at))| EditedFile { path, last_edited_at })
+        .collect()
+}
+
+/// Transcript of `session_id`, searched for in every known Claude profile
+pub fn transcript_path(session_id: &str) -> Option<PathBuf> {
+    if let Some(explanation) = super::explain_status(session_id) {
+        return Some(PathBuf::from(explanation.jsonl_path));
+    }
+
+    let file_name = format!("{}.jsonl", session_id);
+    profiles::known_profiles().into_iter().find_map(|profile| {
+        std::fs::read_dir(profile.projects_dir())
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path().join(&file_name))
+            .find(|candidate| candidate.is_file())
+    })
+}
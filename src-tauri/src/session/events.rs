@@ -0,0 +1,55 @@
+//! Changes between two session polls, shared by `agent-sessions watch` and
+//! the HTTP API's event stream.
+
+use serde::Serialize;
+
+use super::model::{Session, SessionStatus};
+
+/// A session starting, changing status or ending between two polls
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum SessionEvent {
+    Added { session: Box<Session> },
+    Changed { from: SessionStatus, session: Box<Session> },
+    Removed { id: String, project: String },
+}
+
+impl SessionEvent {
+    /// The `event` tag, e.g. "changed"
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionEvent::Added { .. } => "added",
+            SessionEvent::Changed { .. } => "changed",
+            SessionEvent::Removed { .. } => "removed",
+        }
+    }
+}
+
+/// Sessions that started, changed status or ended between two polls
+pub fn diff_sessions(previous: &[Session], current: &[Session]) -> Vec<SessionEvent> {
+    let mut events = Vec::new();
+
+    for session in current {
+        match previous.iter().find(|p| p.id == session.id) {
+            None => events.push(SessionEvent::Added {
+                session: Box::new(session.clone()),
+            }),
+            Some(before) if before.status != session.status => events.push(SessionEvent::Changed {
+                from: before.status.clone(),
+                session: Box::new(session.clone()),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for session in previous {
+        if !current.iter().any(|c| c.id == session.id) {
+            events.push(SessionEvent::Removed {
+                id: session.id.clone(),
+                project: session.project_name.clone(),
+            });
+        }
+    }
+
+    events
+}
@@ -3,6 +3,7 @@ use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
@@ -152,9 +153,21 @@ pub fn is_worktree(project_path: &str) -> bool {
     result
 }
 
+/// Cleared by `skip_branch_lookups`
+static BRANCH_LOOKUPS: AtomicBool = AtomicBool::new(true);
+
+/// Stop looking up PRs and ahead/behind counts for the rest of the process,
+/// for one-off scans whose caller doesn't show them and can't wait for `gh`
+pub fn skip_branch_lookups() {
+    BRANCH_LOOKUPS.store(false, Ordering::Relaxed);
+}
+
 /// Get commits ahead/behind upstream (cached 30s).
 /// Returns (ahead, behind) or None if not a git repo or no upstream.
 pub fn get_ahead_behind(project_path: &str, branch: &str) -> Option<(u32, u32)> {
+    if !BRANCH_LOOKUPS.load(Ordering::Relaxed) {
+        return None;
+    }
     let cache_key = format!("{}:{}", project_path, branch);
 
     {
@@ -173,7 +186,7 @@ pub fn get_ahead_behind(project_path: &str, branch: &str) -> Option<(u32, u32)>
 
 /// Get PR info for a branch (cached 60s). Returns None if no PR or gh unavailable.
 pub fn get_pr_info(project_path: &str, branch: &str) -> Option<PrInfo> {
-    if !*GH_AVAILABLE {
+    if !BRANCH_LOOKUPS.load(Ordering::Relaxed) || !*GH_AVAILABLE {
         return None;
     }
 
@@ -3,8 +3,22 @@ pub mod parser;
 pub mod git;
 pub mod config;
 mod status;
+mod status_machine;
+pub mod slash_commands;
+pub mod profiles;
+pub mod cwd_index;
+mod dwell;
+pub mod edits;
+pub mod events;
+pub mod notes;
+pub mod replay;
 
-pub use model::{AgentType, Session, SessionStatus, SessionsResponse, TerminalApp, ProjectLink};
+pub use model::{enum_name, AgentType, ChildProcess, Session, SessionStatus, SessionsResponse, TerminalApp, ProjectLink, SiblingOverride, StatusExplanation};
+pub use events::{SessionEvent, diff_sessions};
+pub use dwell::StatusDwell;
 pub use git::{PrInfo, CiStatus};
-pub use parser::{parse_session_file, convert_dir_name_to_path, convert_path_to_dir_name, get_sessions, get_sessions_internal, cleanup_stale_status_entries};
-pub use status::{determine_status, status_sort_priority, has_tool_use, has_tool_result, is_local_slash_command, is_interrupted_request, is_thinking_only};
+pub use parser::{parse_session_file, convert_dir_name_to_path, convert_path_to_dir_name, get_sessions, get_sessions_internal, get_sessions_in, cleanup_stale_status_entries, explain_status};
+pub use status::{StatusThresholds, determine_status, determine_status_with_reason, status_sort_priority, has_tool_use, has_tool_result, is_local_slash_command, is_local_slash_command_with, is_interrupted_request, is_thinking_only};
+pub use profiles::ClaudeProfile;
+pub use slash_commands::SlashCommands;
+pub use status_machine::{StatusMachine, StatusMachineConfig, StatusTransition, TransitionRule};
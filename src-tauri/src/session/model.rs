@@ -8,6 +8,17 @@ pub struct ProjectLink {
     pub url: String,
     #[serde(default)]
     pub icon: Option<String>,
+    /// Generated from a detected listening port rather than configured; never saved
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub detected: bool,
+}
+
+/// The serde name of a unit enum variant (`SessionStatus::Idle` -> "idle")
+pub fn enum_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
 }
 
 /// Type of AI coding agent
@@ -28,6 +39,11 @@ pub enum TerminalApp {
     Vscode,
     Terminal,
     Tmux,
+    /// Zed's agent panel (via `claude-code-acp`)
+    Zed,
+    /// The Claude Code extension inside VS Code or a fork
+    #[serde(rename = "vscode-extension")]
+    VscodeExtension,
     Unknown,
 }
 
@@ -57,6 +73,27 @@ pub struct Session {
     pub context_window_percent: Option<f32>,
     pub project_links: Vec<ProjectLink>,
     pub session_links: Vec<ProjectLink>,
+    /// Claude config profile the session belongs to (see `profiles`)
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// CPU of the agent process plus all of its descendants
+    #[serde(default)]
+    pub tree_cpu_usage: f32,
+    /// Resident memory of the agent process plus all of its descendants, in bytes
+    #[serde(default)]
+    pub memory_bytes: u64,
+    /// Commands the agent is currently running (see `process::ProcessTree`)
+    #[serde(default)]
+    pub child_processes: Vec<ChildProcess>,
+}
+
+/// A command running under an agent process
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildProcess {
+    pub pid: u32,
+    pub command: String,
+    pub runtime_secs: u64,
 }
 
 /// Status of a Claude Code session
@@ -68,6 +105,8 @@ pub enum SessionStatus {
     Thinking,
     Compacting,
     Idle,
+    /// Stopped with `pause_session`; overrides the transcript heuristics
+    Paused,
 }
 
 /// Response containing all sessions and counts
@@ -79,6 +118,46 @@ pub struct SessionsResponse {
     pub waiting_count: usize,
 }
 
+/// A more active status borrowed from another recently modified JSONL file
+/// in the same project (see `find_session_for_process`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiblingOverride {
+    pub file: String,
+    pub status: SessionStatus,
+}
+
+/// Inputs and decision path behind a session's status, for diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusExplanation {
+    pub session_id: String,
+    pub jsonl_path: String,
+    /// `type` of the most recent message with content
+    pub last_msg_type: Option<String>,
+    pub last_role: Option<String>,
+    pub has_tool_use: bool,
+    pub has_tool_result: bool,
+    pub is_local_command: bool,
+    pub is_interrupted: bool,
+    /// A compact_boundary was seen before any content message
+    pub is_compacting: bool,
+    pub file_age_secs: Option<f32>,
+    pub cpu_usage: f32,
+    /// Status from the message content, file age and CPU alone
+    pub content_status: SessionStatus,
+    pub active_subagent_count: usize,
+    /// Set when active subagents turned Waiting/Idle into Processing
+    pub subagent_override: bool,
+    pub sibling_override: Option<SiblingOverride>,
+    /// Status after overrides, before debouncing
+    pub raw_status: SessionStatus,
+    /// Status shown on the card
+    pub final_status: SessionStatus,
+    /// Human-readable steps, in the order they were applied
+    pub decision_path: Vec<String>,
+}
+
 /// Internal struct for parsing JSONL messages
 #[derive(Debug, Deserialize)]
 pub(crate) struct JsonlMessage {
@@ -0,0 +1,131 @@
+//! Short notes sessions leave for each other through the MCP server
+//! (`post_note`), stored in `~/.agent-sessions/notes.json` keyed by the
+//! session they are about. Several MCP server processes may write at once, so
+//! every change holds an exclusive lock on `notes.json.lock` while it re-reads
+//! and rewrites the file, and the file is replaced atomically so readers never
+//! need the lock. Notes older than a week are pruned whenever one is added.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Notes kept per session; older ones are dropped
+const MAX_NOTES_PER_SESSION: usize = 50;
+
+/// Notes older than this are pruned
+const NOTE_TTL_DAYS: i64 = 7;
+
+/// Longest note accepted, in characters
+pub const MAX_NOTE_LEN: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub text: String,
+    /// Session that posted the note, when known
+    #[serde(default)]
+    pub from_session_id: Option<String>,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+}
+
+type NotesFile = HashMap<String, Vec<Note>>;
+
+fn notes_path() -> Option<PathBuf> {
+    crate::settings::app_dir().map(|d| d.join("notes.json"))
+}
+
+fn load(path: &Path) -> NotesFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(path: &Path, notes: &NotesFile) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let json = serde_json::to_string_pretty(notes).map_err(|e| format!("Failed to serialize notes: {}", e))?;
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    std::fs::write(&tmp, json)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Hold the notes file's lock until the returned file is dropped
+fn lock(path: &Path) -> Result<File, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let lock_path = path.with_extension("json.lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| format!("Failed to open {:?}: {}", lock_path, e))?;
+    file.lock().map_err(|e| format!("Failed to lock {:?}: {}", lock_path, e))?;
+    Ok(file)
+}
+
+/// Attach a note to `session_id`
+pub fn add_note(session_id: &str, text: &str, from_session_id: Option<&str>) -> Result<Note, String> {
+    let path = notes_path().ok_or("no home directory")?;
+    add_note_in(&path, session_id, text, from_session_id)
+}
+
+/// `add_note` against an explicit notes file
+pub fn add_note_in(path: &Path, session_id: &str, text: &str, from_session_id: Option<&str>) -> Result<Note, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("note text is empty".to_string());
+    }
+    if text.chars().count() > MAX_NOTE_LEN {
+        return Err(format!("note is longer than {} characters", MAX_NOTE_LEN));
+    }
+
+    let note = Note {
+        text: text.to_string(),
+        from_session_id: from_session_id.map(str::to_string),
+        created_at: Utc::now().to_rfc3339(),
+    };
+
+    let _lock = lock(path)?;
+    let mut notes = load(path);
+    prune_expired(&mut notes, Utc::now() - Duration::days(NOTE_TTL_DAYS));
+
+    let entry = notes.entry(session_id.to_string()).or_default();
+    entry.push(note.clone());
+    if entry.len() > MAX_NOTES_PER_SESSION {
+        let excess = entry.len() - MAX_NOTES_PER_SESSION;
+        entry.drain(..excess);
+    }
+    save(path, &notes)?;
+    Ok(note)
+}
+
+/// Notes left for `session_id`, oldest first
+pub fn notes_for(session_id: &str) -> Vec<Note> {
+    notes_path()
+        .map(|p| notes_for_in(&p, session_id))
+        .unwrap_or_default()
+}
+
+/// `notes_for` against an explicit notes file
+pub fn notes_for_in(path: &Path, session_id: &str) -> Vec<Note> {
+    load(path).remove(session_id).unwrap_or_default()
+}
+
+fn prune_expired(notes: &mut NotesFile, cutoff: DateTime<Utc>) {
+    for entries in notes.values_mut() {
+        entries.retain(|n| {
+            DateTime::parse_from_rfc3339(&n.created_at)
+                .map(|t| t >= cutoff)
+                .unwrap_or(false)
+        });
+    }
+    notes.retain(|_, entries| !entries.is_empty());
+}
@@ -2,32 +2,55 @@ use log::{debug, info, trace, warn};
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use once_cell::sync::Lazy;
 
 use crate::agent::AgentProcess;
 use crate::terminal::detect_terminal_for_pid;
-use super::model::{AgentType, Session, SessionStatus, SessionsResponse, JsonlMessage, TerminalApp};
+use super::model::{AgentType, Session, SessionStatus, SessionsResponse, JsonlMessage, TerminalApp, SiblingOverride, StatusExplanation};
 use super::git;
 use super::config;
-use super::status::{determine_status, has_tool_use, has_tool_result, is_local_slash_command, is_interrupted_request, is_thinking_only, status_sort_priority};
+use super::cwd_index;
+use super::status_machine::{StatusMachine, StatusMachineConfig};
+use super::replay;
+use super::profiles;
+use super::slash_commands::SlashCommands;
+use super::status::{StatusThresholds, determine_status_with_reason, has_tool_use, has_tool_result, is_local_slash_command_with, is_interrupted_request, is_thinking_only, status_sort_priority};
 
-/// Track previous status for each session to detect transitions
-static PREVIOUS_STATUS: Lazy<Mutex<HashMap<String, SessionStatus>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// Number of trailing JSONL lines inspected for status.
+/// Must be large enough to cover long stretches of progress entries during tool execution
+/// (observed up to 275 consecutive non-content lines in real sessions)
+pub(crate) const JSONL_LOOKBACK_LINES: usize = 500;
 
-/// Clean up PREVIOUS_STATUS entries for sessions that no longer exist.
+/// Per-session status state machines, used to debounce raw status and detect transitions
+static STATUS_MACHINES: Lazy<Mutex<HashMap<String, StatusMachine>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Status explanations from the most recent scan, for `explain_status`
+static LAST_EXPLANATIONS: Lazy<Mutex<HashMap<String, StatusExplanation>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Clean up STATUS_MACHINES entries for sessions that no longer exist.
 /// Call this after all agent detectors have run to prevent unbounded memory growth.
 pub fn cleanup_stale_status_entries(active_session_ids: &std::collections::HashSet<String>) {
-    let mut prev_status_map = PREVIOUS_STATUS.lock().unwrap();
-    let before_count = prev_status_map.len();
-    prev_status_map.retain(|id, _| active_session_ids.contains(id));
-    let removed = before_count - prev_status_map.len();
+    let mut machines = STATUS_MACHINES.lock().unwrap();
+    let before_count = machines.len();
+    machines.retain(|id, _| active_session_ids.contains(id));
+    let removed = before_count - machines.len();
     if removed > 0 {
-        debug!("Cleaned up {} stale entries from PREVIOUS_STATUS (kept {})", removed, prev_status_map.len());
+        debug!("Cleaned up {} stale entries from STATUS_MACHINES (kept {})", removed, machines.len());
+    }
+    drop(machines);
+
+    if let Ok(mut explanations) = LAST_EXPLANATIONS.lock() {
+        explanations.retain(|id, _| active_session_ids.contains(id));
     }
 }
 
+/// Inputs and decision path behind a session's status as of the most recent scan
+pub fn explain_status(session_id: &str) -> Option<StatusExplanation> {
+    LAST_EXPLANATIONS.lock().ok()?.get(session_id).cloned()
+}
+
 /// Extract a preview of content for debugging
 fn get_content_preview(content: &serde_json::Value) -> String {
     match content {
@@ -157,6 +180,71 @@ pub fn get_sessions() -> SessionsResponse {
 /// Internal function to get sessions for a specific agent type
 /// Called by agent detectors (ClaudeDetector, OpenCodeDetector, etc.)
 pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType) -> Vec<Session> {
+    get_sessions_in(processes, agent_type, &profiles::default_config_dir())
+}
+
+/// Get sessions for `processes`, scanning `<config dir>/projects` for each
+/// distinct `CLAUDE_CONFIG_DIR` among them. Processes without one use
+/// `default_config_dir`; tests pass a temp directory here instead of relying
+/// on the real home directory.
+pub fn get_sessions_in(processes: &[AgentProcess], agent_type: AgentType, default_config_dir: &Path) -> Vec<Session> {
+    // Group processes by profile, keeping first-seen order so scans are stable
+    let mut groups: Vec<(profiles::ClaudeProfile, Vec<AgentProcess>)> = Vec::new();
+    for process in processes {
+        let config_dir = process.config_dir.as_deref().unwrap_or(default_config_dir);
+        let profile = profiles::profile_for(config_dir, default_config_dir);
+        match groups.iter_mut().find(|(p, _)| *p == profile) {
+            Some((_, group)) => group.push(process.clone()),
+            None => groups.push((profile, vec![process.clone()])),
+        }
+    }
+
+    let mut recording = replay::is_recording().then(Vec::new);
+    let now = std::time::Instant::now();
+    let mut sessions = Vec::new();
+
+    for (profile, group) in &groups {
+        debug!("Scanning profile '{}' at {:?} for {} processes", profile.name, profile.config_dir, group.len());
+        profiles::remember(profile);
+
+        let found = scan_projects_dir(
+            &profile.projects_dir(),
+            group,
+            agent_type.clone(),
+            &STATUS_MACHINES,
+            &LAST_EXPLANATIONS,
+            now,
+            recording.as_mut(),
+        );
+        sessions.extend(found.into_iter().map(|mut session| {
+            session.profile = Some(profile.name.clone());
+            session
+        }));
+    }
+
+    if let Some(projects) = recording {
+        replay::record_poll(processes, projects, &sessions);
+    }
+
+    sessions
+}
+
+/// Scan a Claude projects directory for sessions belonging to `processes`.
+///
+/// `machines` and `now` drive status debouncing; the live scan passes the global
+/// state machines and the wall clock, replay passes its own map and a virtual
+/// clock. Each session's status explanation is stored in `explanations`. When
+/// `recording` is set, the inputs of every matched project are snapshotted
+/// into it.
+pub(crate) fn scan_projects_dir(
+    claude_dir: &Path,
+    processes: &[AgentProcess],
+    agent_type: AgentType,
+    machines: &Mutex<HashMap<String, StatusMachine>>,
+    explanations: &Mutex<HashMap<String, StatusExplanation>>,
+    now: std::time::Instant,
+    mut recording: Option<&mut Vec<replay::RecordedProject>>,
+) -> Vec<Session> {
     info!("=== Getting sessions for {:?} ===", agent_type);
     debug!("Found {} processes total", processes.len());
 
@@ -174,11 +262,6 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
         }
     }
 
-    // Scan ~/.claude/projects for session files
-    let claude_dir = dirs::home_dir()
-        .map(|h| h.join(".claude").join("projects"))
-        .unwrap_or_default();
-
     debug!("Claude projects directory: {:?}", claude_dir);
 
     if !claude_dir.exists() {
@@ -187,7 +270,7 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
     }
 
     // For each project directory
-    if let Ok(entries) = fs::read_dir(&claude_dir) {
+    if let Ok(entries) = fs::read_dir(claude_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if !path.is_dir() {
@@ -199,7 +282,9 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
                 .and_then(|n| n.to_str())
                 .unwrap_or("");
 
-            let mut project_path = convert_dir_name_to_path(dir_name);
+            // Prefer the cwd recorded in the transcripts; decoding the name is a last resort
+            let mut project_path = cwd_index::project_path_for_dir(&path)
+                .unwrap_or_else(|| convert_dir_name_to_path(dir_name));
             debug!("Checking project: {} -> {}", dir_name, project_path);
 
             // Check if this project has active processes
@@ -210,8 +295,7 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
             } else {
                 // Try to find a matching cwd by converting each cwd to a dir name and comparing
                 let matching_cwd = cwd_to_processes.keys().find(|cwd| {
-                    let cwd_as_dir = convert_path_to_dir_name(cwd);
-                    cwd_as_dir == dir_name
+                    cwd_index::dir_name_matches(cwd, dir_name)
                 });
 
                 match matching_cwd {
@@ -234,6 +318,10 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
             let jsonl_files = get_recently_active_jsonl_files(&path, matching_processes.len());
             debug!("Found {} JSONL files for project {}", jsonl_files.len(), project_path);
 
+            if let Some(projects) = recording.as_mut() {
+                projects.push(replay::snapshot_project(dir_name, &path));
+            }
+
             // Match processes to JSONL files
             // Use lsof to correctly match PIDs to their session files when multiple
             // processes share the same project directory (prevents status cross-contamination)
@@ -243,6 +331,9 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
                 HashMap::new()
             };
 
+            let thresholds = config::get_status_thresholds(&project_path);
+            let machine_config = StatusMachineConfig::from(&thresholds);
+
             let assigned_count = matching_processes.len();
             let mut used_indices: std::collections::HashSet<usize> = std::collections::HashSet::new();
             for process in matching_processes.iter() {
@@ -271,24 +362,44 @@ pub fn get_sessions_internal(processes: &[AgentProcess], agent_type: AgentType)
                 used_indices.insert(file_index);
 
                 debug!("Matching process pid={} to JSONL file index {}", process.pid, file_index);
-                if let Some(session) = find_session_for_process(&jsonl_files, &path, &project_path, process, file_index, agent_type.clone(), assigned_count) {
-                    // Track status transitions
-                    let mut prev_status_map = PREVIOUS_STATUS.lock().unwrap();
-                    let prev_status = prev_status_map.get(&session.id).cloned();
-
-                    // Log status transition if it changed
-                    if let Some(prev) = &prev_status {
-                        if *prev != session.status {
+                if let Some((mut session, mut explanation)) = find_session_for_process(&jsonl_files, &path, &project_path, process, file_index, agent_type.clone(), assigned_count, &thresholds) {
+                    // Debounce the raw status through the session's state machine
+                    let mut machines = machines.lock().unwrap();
+                    let raw_status = session.status.clone();
+
+                    if let Some(machine) = machines.get_mut(&session.id) {
+                        if let Some(transition) = machine.observe(raw_status.clone(), now, &machine_config) {
                             warn!(
-                                "STATUS TRANSITION: project={}, {:?} -> {:?}, cpu={:.1}%, file_age=?, last_msg_role={:?}",
-                                session.project_name, prev, session.status, session.cpu_usage, session.last_message_role
+                                "STATUS TRANSITION: project={}, {:?} -> {:?}, cpu={:.1}%, last_msg_role={:?}",
+                                session.project_name, transition.from, transition.to, session.cpu_usage, session.last_message_role
                             );
+                        } else if *machine.current() != raw_status {
+                            debug!(
+                                "Holding {:?} for session {} (observed {:?}, awaiting confirmation)",
+                                machine.current(), session.id, raw_status
+                            );
+                            explanation.decision_path.push(format!(
+                                "{:?}: debounced, observed {:?} awaiting confirmation",
+                                machine.current(), raw_status
+                            ));
                         }
+                        session.status = machine.current().clone();
+                    } else {
+                        machines.insert(session.id.clone(), StatusMachine::new(raw_status, now));
                     }
+                    drop(machines);
 
-                    // Update stored status
-                    prev_status_map.insert(session.id.clone(), session.status.clone());
-                    drop(prev_status_map);
+                    // A stopped agent can't make progress whatever its transcript says.
+                    // The machine keeps its state so resuming picks up where it was.
+                    if crate::process::is_paused(process.pid) {
+                        explanation.decision_path.push("Paused: process stopped by pause_session".to_string());
+                        session.status = SessionStatus::Paused;
+                    }
+
+                    explanation.final_status = session.status.clone();
+                    if let Ok(mut explanations) = explanations.lock() {
+                        explanations.insert(session.id.clone(), explanation);
+                    }
 
                     info!(
                         "Session created: id={}, project={}, status={:?}, pid={}, cpu={:.1}%",
@@ -320,7 +431,7 @@ fn is_subagent_file(path: &PathBuf) -> bool {
 
 /// Count active subagents for a given parent session.
 /// Subagent files live in <project_dir>/<session_id>/subagents/agent-*.jsonl
-fn count_active_subagents(project_dir: &PathBuf, parent_session_id: &str) -> usize {
+fn count_active_subagents(project_dir: &PathBuf, parent_session_id: &str, active_secs: u64) -> usize {
     use std::time::{Duration, SystemTime};
 
     let subagents_dir = project_dir.join(parent_session_id).join("subagents");
@@ -329,7 +440,7 @@ fn count_active_subagents(project_dir: &PathBuf, parent_session_id: &str) -> usi
         return 0;
     }
 
-    let active_threshold = Duration::from_secs(30);
+    let active_threshold = Duration::from_secs(active_secs);
     let now = SystemTime::now();
 
     let count = fs::read_dir(&subagents_dir)
@@ -483,6 +594,7 @@ fn match_processes_to_files_by_time(
 
 /// Find a session for a specific process from available JSONL files
 /// Checks unassigned recent files and uses the most "active" status found
+#[allow(clippy::too_many_arguments)]
 fn find_session_for_process(
     jsonl_files: &[PathBuf],
     project_dir: &PathBuf,
@@ -491,17 +603,32 @@ fn find_session_for_process(
     index: usize,
     agent_type: AgentType,
     assigned_count: usize,
-) -> Option<Session> {
+    thresholds: &StatusThresholds,
+) -> Option<(Session, StatusExplanation)> {
     use std::time::{Duration, SystemTime};
 
     // Get the primary JSONL file at the given index
     let primary_jsonl = jsonl_files.get(index)?;
 
     // Parse the primary file first
-    let mut session = parse_session_file(primary_jsonl, project_path, process.pid, process.cpu_usage, agent_type.clone())?;
+    let (mut session, mut explanation) = parse_session_file_explained(primary_jsonl, project_path, process.pid, process.cpu_usage, agent_type.clone(), thresholds)?;
+
+    // Editor-embedded agents have no terminal; show the hosting editor instead
+    if let Some(host) = &process.host {
+        session.terminal_app = host.clone();
+    }
+
+    // Work usually happens in children (cargo, pytest, node), so report the whole tree
+    session.tree_cpu_usage = process.tree.cpu_usage.max(process.cpu_usage);
+    session.memory_bytes = process.tree.memory.max(process.memory);
+    session.child_processes = process.tree.children.clone();
+
+    // Dev servers the agent started become links; they vanish once the server exits
+    crate::process::merge_dev_server_links(&mut session.session_links, &process.tree);
 
     // Count active subagents for this session
-    session.active_subagent_count = count_active_subagents(project_dir, &session.id);
+    session.active_subagent_count = count_active_subagents(project_dir, &session.id, thresholds.subagent_active_secs);
+    explanation.active_subagent_count = session.active_subagent_count;
 
     // If there are active subagents, the session is processing (not waiting for user input).
     // The main JSONL file goes quiet when a subagent runs (activity is in agent-*.jsonl),
@@ -513,6 +640,11 @@ fn find_session_for_process(
             "Overriding {:?} -> Processing: {} active subagents for session {}",
             session.status, session.active_subagent_count, session.id
         );
+        explanation.decision_path.push(format!(
+            "Processing: {} active subagent(s) override {:?}",
+            session.active_subagent_count, session.status
+        ));
+        explanation.subagent_override = true;
         session.status = SessionStatus::Processing;
     }
 
@@ -520,7 +652,7 @@ fn find_session_for_process(
     // Only check files NOT already assigned to another process (index >= assigned_count)
     // Files at indices 0..assigned_count are each assigned to a specific process
     let now = SystemTime::now();
-    let active_threshold = Duration::from_secs(10); // Check files modified in last 10 seconds
+    let active_threshold = Duration::from_secs(thresholds.sibling_active_secs);
 
     for (file_idx, jsonl_path) in jsonl_files.iter().enumerate() {
         if jsonl_path == primary_jsonl {
@@ -546,7 +678,7 @@ fn find_session_for_process(
         }
 
         // Parse this file and check its status
-        if let Some(other_session) = parse_session_file(jsonl_path, project_path, process.pid, process.cpu_usage, agent_type.clone()) {
+        if let Some((other_session, _)) = parse_session_file_explained(jsonl_path, project_path, process.pid, process.cpu_usage, agent_type.clone(), thresholds) {
             // If this file shows a more active status, use it
             let current_priority = status_sort_priority(&session.status);
             let other_priority = status_sort_priority(&other_session.status);
@@ -556,13 +688,23 @@ fn find_session_for_process(
                     "Found more active status in {:?}: {:?} -> {:?}",
                     jsonl_path, session.status, other_session.status
                 );
+                explanation.decision_path.push(format!(
+                    "{:?}: sibling file {:?} is more active than {:?}",
+                    other_session.status, jsonl_path.file_name().unwrap_or_default(), session.status
+                ));
+                explanation.sibling_override = Some(SiblingOverride {
+                    file: jsonl_path.to_string_lossy().to_string(),
+                    status: other_session.status.clone(),
+                });
                 session.status = other_session.status;
                 // Keep the original session's other fields (id, last_message, etc.)
             }
         }
     }
 
-    Some(session)
+    explanation.raw_status = session.status.clone();
+    explanation.final_status = session.status.clone();
+    Some((session, explanation))
 }
 
 /// Parse a JSONL session file and create a Session struct
@@ -573,6 +715,20 @@ pub fn parse_session_file(
     cpu_usage: f32,
     agent_type: AgentType,
 ) -> Option<Session> {
+    let thresholds = config::get_status_thresholds(project_path);
+    parse_session_file_explained(jsonl_path, project_path, pid, cpu_usage, agent_type, &thresholds)
+        .map(|(session, _)| session)
+}
+
+/// Parse a JSONL session file, also returning the inputs behind its status
+fn parse_session_file_explained(
+    jsonl_path: &PathBuf,
+    project_path: &str,
+    pid: u32,
+    cpu_usage: f32,
+    agent_type: AgentType,
+    thresholds: &StatusThresholds,
+) -> Option<(Session, StatusExplanation)> {
     use std::time::SystemTime;
 
     debug!("Parsing JSONL file: {:?}", jsonl_path);
@@ -606,13 +762,12 @@ pub fn parse_session_file(
     let mut last_is_interrupted = false;
     let mut found_status_info = false;
     let mut is_compacting = false;
+    let slash_commands = SlashCommands::for_project(project_path);
     let mut last_usage = None;
 
     // Read last N lines for efficiency
-    // Must be large enough to cover long stretches of progress entries during tool execution
-    // (observed up to 275 consecutive non-content lines in real sessions)
     let lines: Vec<_> = reader.lines().flatten().collect();
-    let recent_lines: Vec<_> = lines.iter().rev().take(500).collect();
+    let recent_lines: Vec<_> = lines.iter().rev().take(JSONL_LOOKBACK_LINES).collect();
 
     trace!("File has {} total lines, checking last {}", lines.len(), recent_lines.len());
 
@@ -668,7 +823,7 @@ pub fn parse_session_file(
                             last_role = content.role.clone();
                             last_has_tool_use = has_tool_use(c);
                             last_has_tool_result = has_tool_result(c);
-                            last_is_local_command = is_local_slash_command(c);
+                            last_is_local_command = is_local_slash_command_with(c, &slash_commands);
                             last_is_interrupted = is_interrupted_request(c);
                             found_status_info = true;
 
@@ -718,10 +873,10 @@ pub fn parse_session_file(
     let session_id = session_id?;
 
     // Determine status using message content + file age + CPU usage
-    let status = if is_compacting {
-        SessionStatus::Compacting
+    let (status, reason) = if is_compacting {
+        (SessionStatus::Compacting, "compact_boundary before any content message".to_string())
     } else {
-        determine_status(
+        determine_status_with_reason(
             last_msg_type.as_deref(),
             last_has_tool_use,
             last_has_tool_result,
@@ -729,6 +884,7 @@ pub fn parse_session_file(
             last_is_interrupted,
             file_age_secs,
             cpu_usage,
+            thresholds,
         )
     };
 
@@ -797,7 +953,28 @@ pub fn parse_session_file(
         _ => TerminalApp::Unknown,
     };
 
-    Some(Session {
+    let explanation = StatusExplanation {
+        session_id: session_id.clone(),
+        jsonl_path: jsonl_path.to_string_lossy().to_string(),
+        last_msg_type: last_msg_type.clone(),
+        last_role: last_role.clone(),
+        has_tool_use: last_has_tool_use,
+        has_tool_result: last_has_tool_result,
+        is_local_command: last_is_local_command,
+        is_interrupted: last_is_interrupted,
+        is_compacting,
+        file_age_secs,
+        cpu_usage,
+        content_status: status.clone(),
+        active_subagent_count: 0,
+        subagent_override: false,
+        sibling_override: None,
+        raw_status: status.clone(),
+        final_status: status.clone(),
+        decision_path: vec![format!("{:?}: {}", status, reason)],
+    };
+
+    let session = Session {
         id: session_id,
         agent_type,
         project_name,
@@ -820,5 +997,11 @@ pub fn parse_session_file(
         context_window_percent,
         project_links,
         session_links,
-    })
+        profile: None,
+        tree_cpu_usage: cpu_usage,
+        memory_bytes: 0,
+        child_processes: Vec::new(),
+    };
+
+    Some((session, explanation))
 }
@@ -0,0 +1,140 @@
+//! Claude config directories ("profiles").
+//!
+//! Claude Code keeps its transcripts under `$CLAUDE_CONFIG_DIR/projects`,
+//! defaulting to `~/.claude`. People running separate accounts point each
+//! shell at a different config dir, so the detector reads `CLAUDE_CONFIG_DIR`
+//! from every process's environment and scans each distinct root.
+//!
+//! Profiles are named after their directory (`~/.claude-work` -> `claude-work`),
+//! or explicitly via `claude_profiles` in `~/.agent-sessions/config.json`:
+//!
+//! ```json
+//! { "claude_profiles": { "work": "~/.claude-work" } }
+//! ```
+
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Name of the profile using the default config directory
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A Claude config directory and the name it is shown under
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClaudeProfile {
+    pub name: String,
+    pub config_dir: PathBuf,
+}
+
+impl ClaudeProfile {
+    /// Directory holding one transcript folder per project
+    pub fn projects_dir(&self) -> PathBuf {
+        self.config_dir.join("projects")
+    }
+
+    /// Directory holding user-level custom slash commands
+    pub fn commands_dir(&self) -> PathBuf {
+        self.config_dir.join("commands")
+    }
+}
+
+/// Profiles seen on recent polls, so actions that write into a profile (like
+/// CLAUDE.md session links) can reach every root sessions were found in
+static SEEN_PROFILES: Lazy<Mutex<HashSet<ClaudeProfile>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Config directory used when a process doesn't set `CLAUDE_CONFIG_DIR`:
+/// our own `CLAUDE_CONFIG_DIR` if set, otherwise `~/.claude`
+pub fn default_config_dir() -> PathBuf {
+    std::env::var_os("CLAUDE_CONFIG_DIR")
+        .filter(|v| !v.is_empty())
+        .map(|v| expand_home(&PathBuf::from(v)))
+        .or_else(|| dirs::home_dir().map(|h| h.join(".claude")))
+        .unwrap_or_default()
+}
+
+/// The default profile
+pub fn default_profile() -> ClaudeProfile {
+    profile_for(&default_config_dir(), &default_config_dir())
+}
+
+/// Name the profile for `config_dir`, given the default config directory
+pub fn profile_for(config_dir: &Path, default_dir: &Path) -> ClaudeProfile {
+    let config_dir = expand_home(config_dir);
+    let name = if same_dir(&config_dir, default_dir) {
+        DEFAULT_PROFILE.to_string()
+    } else {
+        configured_name(&config_dir).unwrap_or_else(|| dir_label(&config_dir))
+    };
+
+    ClaudeProfile { name, config_dir }
+}
+
+/// Read `CLAUDE_CONFIG_DIR` out of a process environment (`KEY=value` entries)
+pub fn config_dir_from_environ<S: AsRef<std::ffi::OsStr>>(environ: &[S]) -> Option<PathBuf> {
+    environ.iter().find_map(|entry| {
+        let entry = entry.as_ref().to_string_lossy();
+        entry
+            .strip_prefix("CLAUDE_CONFIG_DIR=")
+            .filter(|v| !v.is_empty())
+            .map(|v| expand_home(Path::new(v)))
+    })
+}
+
+/// Every profile we know about: the default, the configured ones and any seen on a poll
+pub fn known_profiles() -> Vec<ClaudeProfile> {
+    let default_dir = default_config_dir();
+    let mut profiles = vec![profile_for(&default_dir, &default_dir)];
+
+    for dir in crate::settings::get().claude_profiles.values() {
+        let profile = profile_for(Path::new(dir), &default_dir);
+        if !profiles.contains(&profile) {
+            profiles.push(profile);
+        }
+    }
+    for profile in SEEN_PROFILES.lock().unwrap().iter() {
+        if !profiles.contains(profile) {
+            profiles.push(profile.clone());
+        }
+    }
+
+    profiles
+}
+
+pub(crate) fn remember(profile: &ClaudeProfile) {
+    SEEN_PROFILES.lock().unwrap().insert(profile.clone());
+}
+
+fn configured_name(config_dir: &Path) -> Option<String> {
+    crate::settings::get()
+        .claude_profiles
+        .into_iter()
+        .find(|(_, dir)| same_dir(&expand_home(Path::new(dir)), config_dir))
+        .map(|(name, _)| name)
+}
+
+/// `~/.claude-work` -> `claude-work`
+fn dir_label(config_dir: &Path) -> String {
+    config_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().trim_start_matches('.').to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| config_dir.to_string_lossy().to_string())
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    if a == b {
+        return true;
+    }
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => dirs::home_dir().map(|h| h.join(rest)).unwrap_or_else(|| path.to_path_buf()),
+        Err(_) => path.to_path_buf(),
+    }
+}
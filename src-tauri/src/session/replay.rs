@@ -0,0 +1,354 @@
+//! Record-and-replay of status engine inputs.
+//!
+//! Set `AGENT_SESSIONS_RECORD=/path/to/fixture.jsonl` and every poll appends one
+//! `PollFixture` line: the agent processes with their CPU, and for each matched
+//! project the JSONL tails and file ages. `replay` materializes those polls in
+//! a scratch directory and runs them back through the real scan with a virtual
+//! clock, so a bug report can be checked in as a regression test.
+//!
+//! File creation times can't be restored, so the start-time based PID-to-file
+//! matching for shared project directories falls back to mtime order on replay.
+
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::agent::AgentProcess;
+use super::model::{AgentType, Session, SessionStatus, StatusExplanation};
+use super::parser::{scan_projects_dir, JSONL_LOOKBACK_LINES};
+
+// ---------------------------------------------------------------------------
+// Fixture schema
+// ---------------------------------------------------------------------------
+
+/// An agent process as seen by one poll
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedProcess {
+    pub pid: u32,
+    pub cpu_usage: f32,
+    pub cwd: Option<PathBuf>,
+    #[serde(default)]
+    pub start_time: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_dir: Option<PathBuf>,
+}
+
+/// A file inside a Claude project directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedFile {
+    /// Path relative to the project directory, e.g. `abc.jsonl` or `abc/subagents/agent-1.jsonl`
+    pub path: String,
+    /// Age of the file's mtime at poll time
+    pub age_ms: u64,
+    /// Trailing lines of the file (empty for subagent files, only their mtime matters)
+    #[serde(default)]
+    pub lines: Vec<String>,
+}
+
+/// A Claude project directory (`~/.claude/projects/<dir_name>`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedProject {
+    pub dir_name: String,
+    pub files: Vec<RecordedFile>,
+}
+
+/// Status a session is expected to have after a poll
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectedStatus {
+    pub session_id: String,
+    pub status: SessionStatus,
+}
+
+/// Inputs of one poll, plus the statuses it should produce
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollFixture {
+    /// Milliseconds since the first recorded poll
+    pub at_ms: u64,
+    pub processes: Vec<RecordedProcess>,
+    pub projects: Vec<RecordedProject>,
+    /// Pre-filled with what the app showed while recording; edit the wrong
+    /// entries to turn a recording into a regression test
+    #[serde(default)]
+    pub expected: Vec<ExpectedStatus>,
+}
+
+impl From<&AgentProcess> for RecordedProcess {
+    fn from(p: &AgentProcess) -> Self {
+        RecordedProcess {
+            pid: p.pid,
+            cpu_usage: p.cpu_usage,
+            cwd: p.cwd.clone(),
+            start_time: p.start_time,
+            config_dir: p.config_dir.clone(),
+        }
+    }
+}
+
+impl From<&RecordedProcess> for AgentProcess {
+    fn from(p: &RecordedProcess) -> Self {
+        AgentProcess {
+            pid: p.pid,
+            cpu_usage: p.cpu_usage,
+            cwd: p.cwd.clone(),
+            start_time: p.start_time,
+            config_dir: p.config_dir.clone(),
+            host: None,
+            memory: 0,
+            tree: Default::default(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+struct Recorder {
+    path: PathBuf,
+    started: SystemTime,
+}
+
+static RECORDER: Lazy<Mutex<Option<Recorder>>> = Lazy::new(|| {
+    let recorder = std::env::var_os("AGENT_SESSIONS_RECORD").map(|path| Recorder {
+        path: PathBuf::from(path),
+        started: SystemTime::now(),
+    });
+    if let Some(r) = &recorder {
+        warn!("Recording status engine inputs to {:?}", r.path);
+    }
+    Mutex::new(recorder)
+});
+
+/// Whether `AGENT_SESSIONS_RECORD` is set
+pub fn is_recording() -> bool {
+    RECORDER.lock().map(|r| r.is_some()).unwrap_or(false)
+}
+
+/// Snapshot the session files of one project directory
+pub(crate) fn snapshot_project(dir_name: &str, project_dir: &Path) -> RecordedProject {
+    let now = SystemTime::now();
+    let mut files = Vec::new();
+
+    let age_ms = |path: &Path| {
+        path.metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    };
+
+    for entry in fs::read_dir(project_dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        if path.extension().map(|e| e == "jsonl").unwrap_or(false) {
+            files.push(RecordedFile {
+                path: name,
+                age_ms: age_ms(&path),
+                lines: read_tail(&path),
+            });
+        } else if path.is_dir() {
+            // Subagent activity is judged by mtime alone
+            let subagents = path.join("subagents");
+            for sub in fs::read_dir(&subagents).into_iter().flatten().flatten() {
+                let sub_path = sub.path();
+                if sub_path.extension().map(|e| e == "jsonl").unwrap_or(false) {
+                    files.push(RecordedFile {
+                        path: format!("{}/subagents/{}", name, sub.file_name().to_string_lossy()),
+                        age_ms: age_ms(&sub_path),
+                        lines: Vec::new(),
+                    });
+                }
+            }
+        }
+    }
+
+    RecordedProject {
+        dir_name: dir_name.to_string(),
+        files,
+    }
+}
+
+fn read_tail(path: &Path) -> Vec<String> {
+    let lines: Vec<String> = File::open(path)
+        .map(|f| BufReader::new(f).lines().map_while(Result::ok).collect())
+        .unwrap_or_default();
+    let skip = lines.len().saturating_sub(JSONL_LOOKBACK_LINES);
+    lines.into_iter().skip(skip).collect()
+}
+
+/// Append one poll to the recording file
+pub(crate) fn record_poll(processes: &[AgentProcess], projects: Vec<RecordedProject>, sessions: &[Session]) {
+    let guard = match RECORDER.lock() {
+        Ok(g) => g,
+        Err(_) => return,
+    };
+    let recorder = match guard.as_ref() {
+        Some(r) => r,
+        None => return,
+    };
+
+    let poll = PollFixture {
+        at_ms: SystemTime::now()
+            .duration_since(recorder.started)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        processes: processes.iter().map(RecordedProcess::from).collect(),
+        projects,
+        expected: sessions
+            .iter()
+            .map(|s| ExpectedStatus {
+                session_id: s.id.clone(),
+                status: s.status.clone(),
+            })
+            .collect(),
+    };
+
+    let line = match serde_json::to_string(&poll) {
+        Ok(l) => l,
+        Err(e) => {
+            warn!("Failed to serialize poll fixture: {}", e);
+            return;
+        }
+    };
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&recorder.path)
+        .and_then(|mut f| writeln!(f, "{}", line));
+    if let Err(e) = result {
+        warn!("Failed to write poll fixture to {:?}: {}", recorder.path, e);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Replay
+// ---------------------------------------------------------------------------
+
+/// Parse a fixture file's contents (one `PollFixture` per line)
+pub fn parse_fixture(content: &str) -> Result<Vec<PollFixture>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| format!("Invalid poll on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+/// Load a fixture file written by the recorder
+pub fn load_fixture(path: &Path) -> Result<Vec<PollFixture>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    parse_fixture(&content)
+}
+
+impl PollFixture {
+    /// Recreate this poll's project directories under `root`, with each
+    /// file's mtime set `age_ms` before `now`. Replaces anything already there.
+    pub fn write_to(&self, root: &Path, now: SystemTime) -> Result<(), String> {
+        if root.exists() {
+            fs::remove_dir_all(root).map_err(|e| format!("Failed to clear {:?}: {}", root, e))?;
+        }
+
+        for project in &self.projects {
+            let project_dir = root.join(&project.dir_name);
+            for file in &project.files {
+                let path = project_dir.join(&file.path);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+                }
+
+                let mut contents = file.lines.join("\n");
+                if !contents.is_empty() {
+                    contents.push('\n');
+                }
+                fs::write(&path, contents).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+
+                let mtime = now - Duration::from_millis(file.age_ms);
+                File::options()
+                    .write(true)
+                    .open(&path)
+                    .and_then(|f| f.set_modified(mtime))
+                    .map_err(|e| format!("Failed to set mtime of {:?}: {}", path, e))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome of replaying one poll
+#[derive(Debug, Clone)]
+pub struct ReplayStep {
+    pub at_ms: u64,
+    pub statuses: HashMap<String, SessionStatus>,
+    /// How each status was decided, as `explain_status` would report it
+    pub explanations: HashMap<String, StatusExplanation>,
+    pub expected: Vec<ExpectedStatus>,
+}
+
+impl ReplayStep {
+    /// Expected statuses that the replay did not reproduce, as readable lines
+    pub fn mismatches(&self) -> Vec<String> {
+        self.expected
+            .iter()
+            .filter(|e| self.statuses.get(&e.session_id) != Some(&e.status))
+            .map(|e| {
+                format!(
+                    "t={}ms session {}: expected {:?}, got {:?}",
+                    self.at_ms,
+                    e.session_id,
+                    e.status,
+                    self.statuses.get(&e.session_id)
+                )
+            })
+            .collect()
+    }
+}
+
+/// Replay recorded polls through the status engine.
+///
+/// Each poll is materialized under `work_dir` and scanned with its own set of
+/// state machines, clocked at `at_ms` past a virtual origin, so results don't
+/// depend on how fast the replay runs or on any live session state.
+pub fn replay(polls: &[PollFixture], work_dir: &Path) -> Result<Vec<ReplayStep>, String> {
+    let machines = Mutex::new(HashMap::new());
+    let origin = Instant::now();
+    let mut steps = Vec::with_capacity(polls.len());
+
+    for poll in polls {
+        poll.write_to(work_dir, SystemTime::now())?;
+
+        let processes: Vec<AgentProcess> = poll.processes.iter().map(AgentProcess::from).collect();
+        let now = origin + Duration::from_millis(poll.at_ms);
+        let explanations = Mutex::new(HashMap::new());
+        let sessions = scan_projects_dir(work_dir, &processes, AgentType::Claude, &machines, &explanations, now, None);
+
+        debug!("Replayed poll at {}ms: {} sessions", poll.at_ms, sessions.len());
+
+        steps.push(ReplayStep {
+            at_ms: poll.at_ms,
+            statuses: sessions.into_iter().map(|s| (s.id, s.status)).collect(),
+            explanations: explanations.into_inner().unwrap_or_default(),
+            expected: poll.expected.clone(),
+        });
+    }
+
+    Ok(steps)
+}
@@ -0,0 +1,216 @@
+//! Which slash commands are handled locally by the CLI.
+//!
+//! A local command (`/clear`, `/context`, ...) never gets an assistant reply, so
+//! a session whose last message is one must not be shown as Thinking. The
+//! built-in list ships as `local_commands.json`; a newer copy dropped at
+//! `~/.agent-sessions/local_commands.json` takes precedence, so new built-ins
+//! can be picked up without an app release.
+//!
+//! Custom commands (`<config dir>/commands/*.md`, `<project>/.claude/commands/*.md`)
+//! expand to a prompt and always invoke the model, even when they shadow a
+//! built-in name.
+
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::profiles;
+
+const BUNDLED_LOCAL_COMMANDS: &str = include_str!("local_commands.json");
+
+/// How long a project's discovered custom commands are reused before rescanning
+const CUSTOM_COMMANDS_TTL: Duration = Duration::from_secs(30);
+
+/// Schema of `local_commands.json`
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocalCommandList {
+    pub version: u32,
+    pub local: Vec<String>,
+}
+
+/// Slash commands that apply to one project
+#[derive(Debug, Clone, Default)]
+pub struct SlashCommands {
+    local: HashSet<String>,
+    custom: HashSet<String>,
+}
+
+impl SlashCommands {
+    /// Built-in local commands with no custom commands
+    pub fn builtin() -> Self {
+        SlashCommands {
+            local: builtin_local_commands().local.into_iter().collect(),
+            custom: HashSet::new(),
+        }
+    }
+
+    /// Built-in local commands plus the custom commands visible from `project_path`
+    pub fn for_project(project_path: &str) -> Self {
+        SlashCommands {
+            custom: custom_commands_for_project(project_path),
+            ..SlashCommands::builtin()
+        }
+    }
+
+    /// Build a set from explicit lists (names include the leading `/`)
+    pub fn from_lists<I, J>(local: I, custom: J) -> Self
+    where
+        I: IntoIterator<Item = String>,
+        J: IntoIterator<Item = String>,
+    {
+        SlashCommands {
+            local: local.into_iter().collect(),
+            custom: custom.into_iter().collect(),
+        }
+    }
+
+    /// Whether `/name` is handled by the CLI without a model turn
+    pub fn is_local(&self, command: &str) -> bool {
+        !self.custom.contains(command) && self.local.contains(command)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Built-in list
+// ---------------------------------------------------------------------------
+
+struct CachedList {
+    modified: Option<SystemTime>,
+    list: LocalCommandList,
+}
+
+static BUILTIN_CACHE: Lazy<Mutex<Option<CachedList>>> = Lazy::new(|| Mutex::new(None));
+
+fn bundled_local_commands() -> LocalCommandList {
+    serde_json::from_str(BUNDLED_LOCAL_COMMANDS).expect("bundled local_commands.json is valid")
+}
+
+fn override_path() -> Option<PathBuf> {
+    crate::settings::app_dir().map(|d| d.join("local_commands.json"))
+}
+
+/// The active built-in list: the user's override if it is at least as new as
+/// the bundled one, otherwise the bundled list
+fn builtin_local_commands() -> LocalCommandList {
+    let path = override_path();
+    let modified = path
+        .as_ref()
+        .and_then(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok());
+
+    let mut cache = BUILTIN_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        if cached.modified == modified {
+            return cached.list.clone();
+        }
+    }
+
+    let bundled = bundled_local_commands();
+    let list = match path.filter(|_| modified.is_some()) {
+        Some(path) => match load_override(&path) {
+            Some(user) if user.version >= bundled.version => {
+                debug!("Using local command list v{} from {:?}", user.version, path);
+                user
+            }
+            Some(user) => {
+                debug!(
+                    "Ignoring local command list v{} from {:?}, bundled list is v{}",
+                    user.version, path, bundled.version
+                );
+                bundled
+            }
+            None => bundled,
+        },
+        None => bundled,
+    };
+
+    *cache = Some(CachedList {
+        modified,
+        list: list.clone(),
+    });
+    list
+}
+
+fn load_override(path: &Path) -> Option<LocalCommandList> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(list) => Some(list),
+        Err(e) => {
+            warn!("Failed to parse {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Custom command discovery
+// ---------------------------------------------------------------------------
+
+/// Discovered commands per project path, with when they were scanned
+type CustomCommandCache = HashMap<String, (HashSet<String>, Instant)>;
+
+static CUSTOM_CACHE: Lazy<Mutex<CustomCommandCache>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn custom_commands_for_project(project_path: &str) -> HashSet<String> {
+    if let Some((commands, inserted_at)) = CUSTOM_CACHE.lock().unwrap().get(project_path) {
+        if inserted_at.elapsed() <= CUSTOM_COMMANDS_TTL {
+            return commands.clone();
+        }
+    }
+
+    let mut commands = HashSet::new();
+    for profile in profiles::known_profiles() {
+        commands.extend(discover_custom_commands(&profile.commands_dir()));
+    }
+    commands.extend(discover_custom_commands(&Path::new(project_path).join(".claude").join("commands")));
+
+    if !commands.is_empty() {
+        debug!("Custom slash commands for {}: {:?}", project_path, commands);
+    }
+
+    CUSTOM_CACHE
+        .lock()
+        .unwrap()
+        .insert(project_path.to_string(), (commands.clone(), Instant::now()));
+    commands
+}
+
+/// Find custom command files under a `commands` directory.
+///
+/// `deploy.md` becomes `/deploy`. Files in subdirectories are registered both
+/// by name and namespaced, so `frontend/build.md` yields `/build` and
+/// `/frontend:build`.
+pub fn discover_custom_commands(commands_dir: &Path) -> HashSet<String> {
+    let mut commands = HashSet::new();
+    collect_commands(commands_dir, &[], &mut commands);
+    commands
+}
+
+fn collect_commands(dir: &Path, namespace: &[String], commands: &mut HashSet<String>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let mut nested = namespace.to_vec();
+            nested.push(entry.file_name().to_string_lossy().to_string());
+            collect_commands(&path, &nested, commands);
+        } else if path.extension().map(|e| e == "md").unwrap_or(false) {
+            let name = match path.file_stem() {
+                Some(stem) => stem.to_string_lossy().to_string(),
+                None => continue,
+            };
+            if !namespace.is_empty() {
+                commands.insert(format!("/{}:{}", namespace.join(":"), name));
+            }
+            commands.insert(format!("/{}", name));
+        }
+    }
+}
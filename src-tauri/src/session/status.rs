@@ -1,4 +1,77 @@
+use serde::{Deserialize, Serialize};
+
 use super::model::SessionStatus;
+use super::slash_commands::SlashCommands;
+
+/// Tunable thresholds for status detection.
+///
+/// Defaults are tuned for a fast laptop; slower machines may need longer quiet
+/// windows. Loaded from the global config and overridable per project, see
+/// `config::get_status_thresholds`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusThresholds {
+    /// File quiet time after an assistant text message before it counts as Idle
+    pub text_quiet_secs: f32,
+    /// File quiet time after an assistant tool_use before it counts as Waiting
+    pub tool_quiet_secs: f32,
+    /// CPU usage above which a session with a pending tool_use is Processing
+    pub cpu_active_percent: f32,
+    /// A subagent file modified within this window counts as an active subagent
+    pub subagent_active_secs: u64,
+    /// A sibling JSONL file modified within this window may lend its status
+    pub sibling_active_secs: u64,
+    /// Consecutive quiet polls needed to go from Thinking/Processing to Waiting/Idle
+    pub quiet_confirmations: u32,
+}
+
+impl Default for StatusThresholds {
+    fn default() -> Self {
+        StatusThresholds {
+            text_quiet_secs: 3.0,
+            tool_quiet_secs: 8.0,
+            cpu_active_percent: 5.0,
+            subagent_active_secs: 30,
+            sibling_active_secs: 10,
+            quiet_confirmations: 2,
+        }
+    }
+}
+
+impl StatusThresholds {
+    /// Check that every value is usable. Returns one message per problem.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        let positive_secs = [
+            ("text_quiet_secs", self.text_quiet_secs),
+            ("tool_quiet_secs", self.tool_quiet_secs),
+        ];
+        for (name, value) in positive_secs {
+            if !value.is_finite() || value <= 0.0 {
+                errors.push(format!("{} must be a positive number of seconds, got {}", name, value));
+            }
+        }
+        if !self.cpu_active_percent.is_finite() || self.cpu_active_percent < 0.0 {
+            errors.push(format!("cpu_active_percent must be >= 0, got {}", self.cpu_active_percent));
+        }
+        if self.subagent_active_secs == 0 {
+            errors.push("subagent_active_secs must be at least 1".to_string());
+        }
+        if self.sibling_active_secs == 0 {
+            errors.push("sibling_active_secs must be at least 1".to_string());
+        }
+        if self.quiet_confirmations == 0 {
+            errors.push("quiet_confirmations must be at least 1".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
 
 /// Check if content array contains only "thinking" blocks (no text or tool_use).
 /// During extended thinking, Claude writes assistant messages with only thinking blocks.
@@ -68,6 +141,12 @@ pub fn is_interrupted_request(content: &serde_json::Value) -> bool {
 /// Check if message content is a local slash command that doesn't trigger Claude response
 /// These commands are handled locally by Claude Code and don't require thinking
 pub fn is_local_slash_command(content: &serde_json::Value) -> bool {
+    is_local_slash_command_with(content, &SlashCommands::builtin())
+}
+
+/// Like `is_local_slash_command`, against a specific command set (e.g. one that
+/// knows about a project's custom commands)
+pub fn is_local_slash_command_with(content: &serde_json::Value, commands: &SlashCommands) -> bool {
     let text = extract_text_content(content);
     let trimmed = text.trim();
 
@@ -91,31 +170,11 @@ pub fn is_local_slash_command(content: &serde_json::Value) -> bool {
         trimmed
     };
 
-    // Local commands that don't trigger Claude to think
-    // These are handled by the CLI itself
-    let local_commands = [
-        "/clear",
-        "/compact",
-        "/help",
-        "/config",
-        "/cost",
-        "/doctor",
-        "/init",
-        "/login",
-        "/logout",
-        "/memory",
-        "/model",
-        "/permissions",
-        "/pr-comments",
-        "/review",
-        "/status",
-        "/terminal-setup",
-        "/vim",
-    ];
-
-    local_commands.iter().any(|cmd| {
-        command_text == *cmd || command_text.starts_with(&format!("{} ", cmd))
-    })
+    if !command_text.starts_with('/') {
+        return false;
+    }
+    let command = command_text.split_whitespace().next().unwrap_or(command_text);
+    commands.is_local(command)
 }
 
 /// Returns sort priority for status (lower = higher priority in list)
@@ -127,12 +186,14 @@ pub fn status_sort_priority(status: &SessionStatus) -> u8 {
         SessionStatus::Compacting => 0,  // Active - compressing context - show first
         SessionStatus::Waiting => 1,     // Needs attention - show second
         SessionStatus::Idle => 2,        // Inactive - show last
+        SessionStatus::Paused => 2,      // Frozen by the user - inactive
     }
 }
 
 /// Determine session status based on the last message in the conversation
 ///
-/// Status is determined from message content + file age + CPU usage:
+/// Status is determined from message content + file age + CPU usage,
+/// using the default `StatusThresholds`:
 /// - assistant with tool_use + file active (< 8s) or CPU high -> Processing
 /// - assistant with tool_use + file quiet + CPU low -> Waiting (blocked on user)
 /// - assistant text-only + file quiet (> 3s) -> Idle (Claude finished)
@@ -142,53 +203,93 @@ pub fn status_sort_priority(status: &SessionStatus) -> u8 {
 pub fn determine_status(
     last_msg_type: Option<&str>,
     has_tool_use: bool,
-    _has_tool_result: bool,
+    has_tool_result: bool,
     is_local_command: bool,
     is_interrupted: bool,
     file_age_secs: Option<f32>,
     cpu_usage: f32,
 ) -> SessionStatus {
+    determine_status_with_reason(
+        last_msg_type,
+        has_tool_use,
+        has_tool_result,
+        is_local_command,
+        is_interrupted,
+        file_age_secs,
+        cpu_usage,
+        &StatusThresholds::default(),
+    )
+    .0
+}
+
+/// Same as `determine_status`, with explicit thresholds and a human-readable
+/// reason for the decision
+#[allow(clippy::too_many_arguments)]
+pub fn determine_status_with_reason(
+    last_msg_type: Option<&str>,
+    has_tool_use: bool,
+    _has_tool_result: bool,
+    is_local_command: bool,
+    is_interrupted: bool,
+    file_age_secs: Option<f32>,
+    cpu_usage: f32,
+    thresholds: &StatusThresholds,
+) -> (SessionStatus, String) {
     // Two thresholds: tight for text-only (quick Idle), generous for tool_use
-    let file_recently_modified = file_age_secs.map(|age| age < 3.0).unwrap_or(false);
-    let file_active_for_tool = file_age_secs.map(|age| age < 8.0).unwrap_or(false);
-    let cpu_active = cpu_usage > 5.0;
+    let text_quiet = thresholds.text_quiet_secs;
+    let tool_quiet = thresholds.tool_quiet_secs;
+    let cpu_threshold = thresholds.cpu_active_percent;
+    let file_recently_modified = file_age_secs.map(|age| age < text_quiet).unwrap_or(false);
+    let file_active_for_tool = file_age_secs.map(|age| age < tool_quiet).unwrap_or(false);
+    let cpu_active = cpu_usage > cpu_threshold;
+    let age = file_age_secs
+        .map(|a| format!("{:.1}s", a))
+        .unwrap_or_else(|| "unknown".to_string());
 
     match last_msg_type {
         Some("assistant") => {
             if has_tool_use {
                 if file_active_for_tool || cpu_active {
-                    // Tool is actively running: file was modified within 8s,
+                    // Tool is actively running: file was modified within the tool window,
                     // or process is using significant CPU (tool execution, streaming)
-                    SessionStatus::Processing
+                    (SessionStatus::Processing, format!(
+                        "assistant tool_use with file age {} (< {}s: {}) or CPU {:.1}% (> {}%: {})",
+                        age, tool_quiet, file_active_for_tool, cpu_usage, cpu_threshold, cpu_active
+                    ))
                 } else {
-                    // Tool_use sent, file quiet for 8+ seconds, low CPU
+                    // Tool_use sent, file quiet past the tool window, low CPU
                     // -> waiting for user permission/answer
-                    SessionStatus::Waiting
+                    (SessionStatus::Waiting, format!(
+                        "assistant tool_use, file quiet for {} (>= {}s) and CPU {:.1}% (<= {}%)",
+                        age, tool_quiet, cpu_usage, cpu_threshold
+                    ))
                 }
             } else if file_recently_modified {
                 // Text response but file is still being written to
                 // (streaming, compacting, or about to send tool_use)
-                SessionStatus::Processing
+                (SessionStatus::Processing, format!("assistant text, file still being written ({} < {}s)", age, text_quiet))
             } else {
                 // Assistant sent a text response and file is quiet - done, no pending questions
-                SessionStatus::Idle
+                (SessionStatus::Idle, format!("assistant text, file quiet for {} (>= {}s)", age, text_quiet))
             }
         }
         Some("user") => {
-            if is_local_command || is_interrupted {
+            if is_local_command {
                 // Local slash commands and interrupted requests don't trigger Claude
-                SessionStatus::Idle
+                (SessionStatus::Idle, "user local slash command (no model response expected)".to_string())
+            } else if is_interrupted {
+                (SessionStatus::Idle, "user interrupted the request".to_string())
             } else {
                 // User sent a message or tool result - Claude is working
-                SessionStatus::Thinking
+                (SessionStatus::Thinking, "user message or tool_result awaiting a response".to_string())
             }
         }
         _ => {
             // Couldn't determine message type (e.g., only progress entries in lookback)
             if file_recently_modified {
-                SessionStatus::Processing
+                (SessionStatus::Processing, format!("no content message in lookback, file active ({} < {}s)", age, text_quiet))
             } else {
-                SessionStatus::Idle
+                (SessionStatus::Idle, format!("no content message in lookback, file quiet ({})", age))
             }
         }
     }
@@ -0,0 +1,161 @@
+use std::time::{Duration, Instant};
+
+use super::model::SessionStatus;
+use super::status::StatusThresholds;
+
+/// Gate for a transition between two statuses.
+///
+/// A rule matches when `from` and `to` match the current and observed status
+/// (`None` matches any status). The first matching rule in
+/// `StatusMachineConfig::rules` wins; transitions with no matching rule are
+/// committed on the first observation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionRule {
+    pub from: Option<SessionStatus>,
+    pub to: Option<SessionStatus>,
+    /// Consecutive observations of the new status required before committing
+    pub confirmations: u32,
+    /// Minimum time the current status must have been held before leaving it
+    pub min_dwell: Duration,
+}
+
+impl TransitionRule {
+    fn matches(&self, from: &SessionStatus, to: &SessionStatus) -> bool {
+        self.from.as_ref().map(|f| f == from).unwrap_or(true)
+            && self.to.as_ref().map(|t| t == to).unwrap_or(true)
+    }
+}
+
+/// Debouncing and hysteresis rules applied on top of `determine_status`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusMachineConfig {
+    pub rules: Vec<TransitionRule>,
+}
+
+impl Default for StatusMachineConfig {
+    /// Going quiet (Thinking/Processing -> Waiting/Idle) needs two consecutive
+    /// quiet observations. A single quiet poll is usually a gap between
+    /// progress writes, which is what caused most of the historical flicker.
+    /// Everything else - in particular becoming active again - is immediate.
+    fn default() -> Self {
+        StatusMachineConfig::with_quiet_confirmations(2)
+    }
+}
+
+impl From<&StatusThresholds> for StatusMachineConfig {
+    fn from(thresholds: &StatusThresholds) -> Self {
+        StatusMachineConfig::with_quiet_confirmations(thresholds.quiet_confirmations)
+    }
+}
+
+impl StatusMachineConfig {
+    /// The default rule set, requiring `confirmations` quiet polls before going quiet
+    pub fn with_quiet_confirmations(confirmations: u32) -> Self {
+        let quiet_after_active = |from: SessionStatus, to: SessionStatus| TransitionRule {
+            from: Some(from),
+            to: Some(to),
+            confirmations,
+            min_dwell: Duration::ZERO,
+        };
+
+        StatusMachineConfig {
+            rules: vec![
+                quiet_after_active(SessionStatus::Processing, SessionStatus::Waiting),
+                quiet_after_active(SessionStatus::Processing, SessionStatus::Idle),
+                quiet_after_active(SessionStatus::Thinking, SessionStatus::Waiting),
+                quiet_after_active(SessionStatus::Thinking, SessionStatus::Idle),
+            ],
+        }
+    }
+
+    fn rule_for(&self, from: &SessionStatus, to: &SessionStatus) -> (u32, Duration) {
+        self.rules
+            .iter()
+            .find(|r| r.matches(from, to))
+            .map(|r| (r.confirmations.max(1), r.min_dwell))
+            .unwrap_or((1, Duration::ZERO))
+    }
+}
+
+/// A committed status change
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusTransition {
+    pub from: SessionStatus,
+    pub to: SessionStatus,
+    pub at: Instant,
+}
+
+/// Per-session status state machine.
+///
+/// Holds the committed status and a pending candidate. Each poll feeds the raw
+/// status from `determine_status` into `observe` together with the poll time;
+/// the committed status only changes once the matching `TransitionRule` is
+/// satisfied. Time is always passed in, so tests can drive it with a fake clock.
+#[derive(Debug, Clone)]
+pub struct StatusMachine {
+    current: SessionStatus,
+    entered_at: Instant,
+    pending: Option<(SessionStatus, u32)>,
+}
+
+impl StatusMachine {
+    /// Start a machine in `initial`. The first observation of a session is
+    /// trusted as-is since there is no previous state to flicker from.
+    pub fn new(initial: SessionStatus, now: Instant) -> Self {
+        StatusMachine {
+            current: initial,
+            entered_at: now,
+            pending: None,
+        }
+    }
+
+    /// The committed status
+    pub fn current(&self) -> &SessionStatus {
+        &self.current
+    }
+
+    /// When the committed status was entered
+    pub fn entered_at(&self) -> Instant {
+        self.entered_at
+    }
+
+    /// How long the committed status has been held as of `now`
+    pub fn dwell(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.entered_at)
+    }
+
+    /// Feed a new raw observation. Returns the transition if one was committed.
+    pub fn observe(
+        &mut self,
+        observed: SessionStatus,
+        now: Instant,
+        config: &StatusMachineConfig,
+    ) -> Option<StatusTransition> {
+        if observed == self.current {
+            // Any pending candidate was a blip - start counting again next time
+            self.pending = None;
+            return None;
+        }
+
+        let count = match &self.pending {
+            Some((candidate, n)) if *candidate == observed => n + 1,
+            _ => 1,
+        };
+
+        let (confirmations, min_dwell) = config.rule_for(&self.current, &observed);
+        if count < confirmations || self.dwell(now) < min_dwell {
+            self.pending = Some((observed, count));
+            return None;
+        }
+
+        let from = std::mem::replace(&mut self.current, observed);
+        self.entered_at = now;
+        self.pending = None;
+
+        Some(StatusTransition {
+            from,
+            to: self.current.clone(),
+            at: now,
+        })
+    }
+}
@@ -0,0 +1,140 @@
+//! Global settings from `~/.agent-sessions/config.json`.
+//!
+//! The file is re-read whenever its mtime changes, so edits apply on the next
+//! poll without restarting the app. Each top-level key is parsed on its own:
+//! a malformed section falls back to its defaults without taking the rest of
+//! the file down with it.
+
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::api::HttpApiSettings;
+use crate::hooks::HookSettings;
+use crate::notifications::NotificationSettings;
+use crate::process::KillSettings;
+use crate::rules::AutomationSettings;
+use crate::session::StatusThresholds;
+use crate::tray_status::TraySettings;
+use crate::webhooks::WebhookSettings;
+
+#[derive(Debug, Clone, Default)]
+pub struct GlobalSettings {
+    /// Chrome profile directory name (e.g. "Profile 1") for companion windows.
+    /// `None` uses isolated per-project profiles.
+    pub chrome_profile: Option<String>,
+    pub status_thresholds: StatusThresholds,
+    /// Named Claude config directories, e.g. `{"work": "~/.claude-work"}`
+    pub claude_profiles: HashMap<String, String>,
+    /// Extra command-line patterns identifying Claude Code launches (`*` wildcards)
+    pub claude_process_patterns: Vec<String>,
+    /// Also show agents embedded in editors (Zed agent panel, VS Code extension).
+    /// Off by default since they aren't user-started terminal sessions.
+    pub include_editor_agents: bool,
+    /// Signal, grace period and tree teardown used when killing a session
+    pub kill: KillSettings,
+    /// Local HTTP API; read once at startup
+    pub http_api: HttpApiSettings,
+    /// Which status transitions show a desktop notification, and when
+    pub notifications: NotificationSettings,
+    /// Webhooks posted on status transitions
+    pub webhooks: WebhookSettings,
+    /// User-defined rules evaluated after every scan
+    pub automation: AutomationSettings,
+    /// Shell commands run on session start, status changes and end
+    pub hooks: HookSettings,
+    /// Tray title template and status icon
+    pub tray: TraySettings,
+}
+
+struct CachedSettings {
+    modified: Option<SystemTime>,
+    settings: GlobalSettings,
+}
+
+static SETTINGS: Lazy<Mutex<Option<CachedSettings>>> = Lazy::new(|| Mutex::new(None));
+
+/// Directory holding the app's own state (`~/.agent-sessions`)
+pub fn app_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".agent-sessions"))
+}
+
+/// Path of the global config file
+pub fn config_path() -> Option<PathBuf> {
+    app_dir().map(|d| d.join("config.json"))
+}
+
+/// Current global settings, reloaded if the config file changed since the last call
+pub fn get() -> GlobalSettings {
+    let path = match config_path() {
+        Some(p) => p,
+        None => return GlobalSettings::default(),
+    };
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+
+    let mut cache = SETTINGS.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        if cached.modified == modified {
+            return cached.settings.clone();
+        }
+    }
+
+    let settings = match std::fs::read_to_string(&path) {
+        Ok(content) => parse(&content),
+        Err(_) => GlobalSettings::default(),
+    };
+    debug!("Loaded global settings from {:?}", path);
+
+    *cache = Some(CachedSettings {
+        modified,
+        settings: settings.clone(),
+    });
+    settings
+}
+
+/// Parse the global config file contents
+pub fn parse(content: &str) -> GlobalSettings {
+    let value: serde_json::Value = match serde_json::from_str(content) {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("Failed to parse global config: {}", e);
+            return GlobalSettings::default();
+        }
+    };
+
+    let mut status_thresholds: StatusThresholds = section(&value, "status_thresholds");
+    if let Err(errors) = status_thresholds.validate() {
+        warn!("Invalid status_thresholds in global config, using defaults: {}", errors.join("; "));
+        status_thresholds = StatusThresholds::default();
+    }
+
+    GlobalSettings {
+        chrome_profile: section(&value, "chrome_profile"),
+        status_thresholds,
+        claude_profiles: section(&value, "claude_profiles"),
+        claude_process_patterns: section(&value, "claude_process_patterns"),
+        include_editor_agents: section(&value, "include_editor_agents"),
+        kill: section(&value, "kill"),
+        http_api: section(&value, "http_api"),
+        notifications: section(&value, "notifications"),
+        webhooks: section(&value, "webhooks"),
+        automation: section(&value, "automation"),
+        hooks: section(&value, "hooks"),
+        tray: section(&value, "tray"),
+    }
+}
+
+/// Deserialize one top-level key, falling back to its default if missing or malformed
+fn section<T: DeserializeOwned + Default>(value: &serde_json::Value, key: &str) -> T {
+    match value.get(key) {
+        None | Some(serde_json::Value::Null) => T::default(),
+        Some(v) => serde_json::from_value(v.clone()).unwrap_or_else(|e| {
+            warn!("Ignoring malformed `{}` in global config: {}", key, e);
+            T::default()
+        }),
+    }
+}
@@ -0,0 +1,69 @@
+use std::process::Command;
+use super::applescript::execute_applescript;
+use super::vscode::activate_app_window;
+
+/// Raise the editor window hosting an embedded agent (Zed agent panel,
+/// VS Code extension). These processes have no TTY, so the window is found
+/// by project folder name and the app brought to the front.
+pub fn focus_editor_window(app_name: &str, project_path: &str) -> Result<(), String> {
+    let raised = activate_app_window(app_name, project_path);
+
+    // Only the frontmost flag - `activate` would bring every window forward
+    let script = format!(
+        r#"
+        tell application "System Events"
+            if exists process "{app_name}" then
+                set frontmost of process "{app_name}" to true
+                return "found"
+            end if
+        end tell
+        return "not-found"
+    "#,
+        app_name = app_name
+    );
+    let activated = execute_applescript(&script);
+
+    raised.or(activated)
+}
+
+/// Focus the VS Code-family editor that spawned an extension-embedded agent
+pub fn focus_vscode_extension_host(pid: u32, project_path: &str) -> Result<(), String> {
+    let app_name = editor_app_for_pid(pid).unwrap_or("Visual Studio Code");
+    focus_editor_window(app_name, project_path)
+}
+
+/// Walk up the process tree to find which VS Code-family editor owns `pid`
+fn editor_app_for_pid(pid: u32) -> Option<&'static str> {
+    let mut current_pid = pid;
+
+    // Walk up to 10 levels to avoid infinite loops
+    for _ in 0..10 {
+        let output = Command::new("ps")
+            .args(["-p", &current_pid.to_string(), "-o", "ppid=,comm="])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())?;
+
+        let line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let mut parts = line.splitn(2, |c: char| c.is_whitespace());
+        let ppid = parts.next()?.trim().parse::<u32>().ok()?;
+        let comm = parts.next().unwrap_or("").trim();
+
+        if comm.contains("Cursor") {
+            return Some("Cursor");
+        }
+        if comm.contains("Windsurf") {
+            return Some("Windsurf");
+        }
+        if comm.contains("Code") {
+            return Some("Visual Studio Code");
+        }
+
+        if ppid <= 1 {
+            break;
+        }
+        current_pid = ppid;
+    }
+
+    None
+}
@@ -1,14 +1,24 @@
 mod applescript;
+mod editor;
 mod iterm;
 mod terminal_app;
 mod tmux;
 pub mod vscode;
 mod warp;
 
+pub use tmux::{same_tty, session_on_tty};
+
 use applescript::execute_applescript;
 
 /// Focus the terminal containing the Claude process with the given PID
 pub fn focus_terminal_for_pid(pid: u32, hint: &str, project_path: &str) -> Result<(), String> {
+    // Editor-embedded agents have no TTY - raise the owning editor instead
+    match hint {
+        "zed" => return editor::focus_editor_window("Zed", project_path),
+        "vscode-extension" => return editor::focus_vscode_extension_host(pid, project_path),
+        _ => {}
+    }
+
     // First, get the TTY for this process
     let tty = get_tty_for_pid(pid)?;
 
@@ -133,7 +143,7 @@ fn detect_terminal_for_pid_uncached(pid: u32) -> String {
     {
         if output.status.success() {
             let panes = String::from_utf8_lossy(&output.stdout);
-            if panes.lines().any(|line| line.contains(&tty)) {
+            if panes.lines().any(|line| tmux::same_tty(line, &tty)) {
                 return "tmux".to_string();
             }
         }
@@ -167,7 +177,7 @@ fn detect_terminal_for_pid_uncached(pid: u32) -> String {
 }
 
 /// Get the TTY device for a given PID using ps command
-fn get_tty_for_pid(pid: u32) -> Result<String, String> {
+pub fn get_tty_for_pid(pid: u32) -> Result<String, String> {
     use std::process::Command;
 
     let output = Command::new("ps")
@@ -187,6 +197,33 @@ fn get_tty_for_pid(pid: u32) -> Result<String, String> {
     }
 }
 
+/// TTY of each of `pids` that has one, from a single `ps` call
+pub fn ttys_for_pids(pids: &[u32]) -> HashMap<u32, String> {
+    if pids.is_empty() {
+        return HashMap::new();
+    }
+    let list = pids.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+    std::process::Command::new("ps")
+        .args(["-o", "pid=,tty=", "-p", &list])
+        .output()
+        .map(|output| parse_ps_ttys(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Parse `ps -o pid=,tty=` output, skipping processes without a TTY
+/// ("??" on macOS, "?" on Linux)
+pub fn parse_ps_ttys(output: &str) -> HashMap<u32, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let tty = fields.next()?;
+            (tty != "??" && tty != "?").then(|| (pid, tty.to_string()))
+        })
+        .collect()
+}
+
 /// Walk up the process tree to find a known terminal application
 fn detect_terminal_from_parent(pid: u32) -> String {
     let mut current_pid = pid;
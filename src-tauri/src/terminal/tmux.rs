@@ -3,6 +3,26 @@ use super::applescript::execute_applescript;
 use super::iterm;
 use super::terminal_app;
 use super::warp;
+use crate::session::Session;
+
+/// Whether two TTY names are the same device, with or without the `/dev/`
+/// prefix (`ps` prints "ttys003" or "pts/3", tmux "/dev/ttys003")
+pub fn same_tty(a: &str, b: &str) -> bool {
+    let name = |tty: &str| tty.trim().trim_start_matches("/dev/").to_string();
+    !a.trim().is_empty() && name(a) == name(b)
+}
+
+/// The session whose agent runs on `tty`, e.g. a pane's `#{pane_tty}`.
+/// `tty_of` gives a PID's TTY.
+pub fn session_on_tty<'a>(
+    sessions: &'a [Session],
+    tty: &str,
+    tty_of: impl Fn(u32) -> Option<String>,
+) -> Option<&'a Session> {
+    sessions
+        .iter()
+        .find(|s| tty_of(s.pid).is_some_and(|t| same_tty(&t, tty)))
+}
 
 /// Focus a tmux pane by matching its TTY
 /// Returns Ok if the pane was found and focused, Err otherwise
@@ -27,8 +47,7 @@ pub fn focus_tmux_pane_by_tty(tty: &str) -> Result<(), String> {
             let pane_tty = parts[0];
             let target = parts[1];
 
-            // Match TTY (handle both with and without /dev/ prefix)
-            if pane_tty.contains(tty) || pane_tty.ends_with(tty) {
+            if same_tty(pane_tty, tty) {
                 // Select the window and pane in tmux
                 let _ = Command::new("tmux")
                     .args(["select-window", "-t", target])
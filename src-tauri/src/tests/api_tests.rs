@@ -0,0 +1,143 @@
+use crate::api::{fetch_sessions, is_authorized, parse_request, route, serve, EventHub, HttpApiSettings};
+use crate::session::SessionsResponse;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::cli_tests::session;
+
+const TOKEN: &str = "test-token";
+
+/// A server with its own event hub, so tests don't see the app's polls
+fn start_server() -> (u16, Arc<EventHub>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let hub = Arc::new(EventHub::new());
+    let server_hub = hub.clone();
+    std::thread::spawn(move || serve(listener, TOKEN.to_string(), server_hub));
+    (port, hub)
+}
+
+fn send(port: u16, request: &str) -> TcpStream {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    stream
+}
+
+fn response(sessions: Vec<crate::session::Session>) -> SessionsResponse {
+    SessionsResponse {
+        total_count: sessions.len(),
+        waiting_count: 0,
+        sessions,
+    }
+}
+
+#[test]
+fn test_http_api_settings_default_to_loopback() {
+    let settings = HttpApiSettings::default();
+    assert!(!settings.enabled);
+    assert_eq!(settings.bind, "127.0.0.1");
+    assert!(settings.token.is_none());
+}
+
+#[test]
+fn test_parse_request() {
+    let request = parse_request("GET /sessions/abc%20def?token=t%2B1&x HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer xyz\r\n").unwrap();
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, "/sessions/abc def");
+    assert_eq!(request.query.get("token").map(String::as_str), Some("t+1"));
+    assert_eq!(request.header("authorization"), Some("Bearer xyz"));
+
+    assert!(parse_request("GET /").is_err());
+    assert!(parse_request("").is_err());
+}
+
+#[test]
+fn test_is_authorized() {
+    let with_header = parse_request("GET / HTTP/1.1\r\nAuthorization: Bearer test-token\r\n").unwrap();
+    let with_query = parse_request("GET /events?token=test-token HTTP/1.1\r\n").unwrap();
+    let wrong = parse_request("GET / HTTP/1.1\r\nAuthorization: Bearer test-tokem\r\n").unwrap();
+    let missing = parse_request("GET / HTTP/1.1\r\n").unwrap();
+
+    assert!(is_authorized(&with_header, TOKEN));
+    assert!(is_authorized(&with_query, TOKEN));
+    assert!(!is_authorized(&wrong, TOKEN));
+    assert!(!is_authorized(&missing, TOKEN));
+}
+
+#[test]
+fn test_route_unknown_and_wrong_method() {
+    let not_found = route(&parse_request("GET /nope HTTP/1.1\r\n").unwrap(), &EventHub::new());
+    assert_eq!(not_found.status, 404);
+
+    let wrong_method = route(&parse_request("DELETE /sessions HTTP/1.1\r\n").unwrap(), &EventHub::new());
+    assert_eq!(wrong_method.status, 405);
+    assert!(wrong_method.body.contains("error"));
+}
+
+#[test]
+fn test_server_rejects_missing_token() {
+    let (port, _) = start_server();
+    let mut stream = send(port, "GET /sessions HTTP/1.1\r\nHost: localhost\r\n\r\n");
+
+    let mut body = String::new();
+    stream.read_to_string(&mut body).unwrap();
+    assert!(body.starts_with("HTTP/1.1 401"), "{}", body);
+    assert!(body.contains("WWW-Authenticate: Bearer"));
+}
+
+#[test]
+fn test_server_streams_status_transitions() {
+    let (port, hub) = start_server();
+    let stream = send(port, "GET /events HTTP/1.1\r\nAuthorization: Bearer test-token\r\n\r\n");
+    let mut reader = BufReader::new(stream);
+
+    let mut status_line = String::new();
+    reader.read_line(&mut status_line).unwrap();
+    assert!(status_line.starts_with("HTTP/1.1 200"), "{}", status_line);
+
+    // The subscription is registered before the head is sent
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        if line == "\r\n" {
+            break;
+        }
+    }
+    hub.publish_poll(&response(vec![session("api-test-session", 1, "thinking")]));
+    hub.publish_poll(&response(vec![session("api-test-session", 1, "waiting")]));
+
+    let mut event = String::new();
+    while Instant::now() < deadline {
+        line.clear();
+        if reader.read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        event.push_str(&line);
+        if event.contains("event: changed") && event.ends_with("\n\n") {
+            break;
+        }
+    }
+
+    assert!(event.contains("event: changed"), "{}", event);
+    assert!(event.contains("\"id\":\"api-test-session\""), "{}", event);
+    assert!(event.contains("\"from\":\"thinking\""), "{}", event);
+}
+
+#[test]
+fn test_fetch_sessions_reads_the_latest_poll() {
+    let (port, hub) = start_server();
+    hub.publish_poll(&response(vec![session("api-client-session", 7, "waiting")]));
+    let address = format!("127.0.0.1:{}", port);
+
+    let fetched = fetch_sessions(&address, TOKEN).unwrap();
+    assert_eq!(fetched.sessions.len(), 1);
+    assert_eq!(fetched.sessions[0].id, "api-client-session");
+
+    let err = fetch_sessions(&address, "wrong-token").unwrap_err();
+    assert!(err.contains("401"), "{}", err);
+}
@@ -0,0 +1,116 @@
+use crate::cli::{find_session, format_event, format_table, parse_args, Command};
+use crate::session::{diff_sessions, Session, SessionEvent, SessionStatus};
+use std::time::Duration;
+
+pub(super) fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+pub(super) fn session(id: &str, pid: u32, status: &str) -> Session {
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "agentType": "claude",
+        "projectName": "my-project",
+        "projectPath": "/tmp/my-project",
+        "gitBranch": "main",
+        "githubUrl": null,
+        "status": status,
+        "lastMessage": null,
+        "lastMessageRole": null,
+        "lastActivityAt": "2026-01-01T00:00:00Z",
+        "pid": pid,
+        "cpuUsage": 1.0,
+        "activeSubagentCount": 0,
+        "terminalApp": "iterm2",
+        "isWorktree": false,
+        "repoName": null,
+        "prInfo": null,
+        "commitsAhead": null,
+        "commitsBehind": null,
+        "contextWindowPercent": null,
+        "projectLinks": [],
+        "sessionLinks": []
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_parse_args_commands() {
+    assert_eq!(parse_args(&args(&[])).unwrap(), Command::Help);
+    assert_eq!(parse_args(&args(&["list"])).unwrap(), Command::List { json: false });
+    assert_eq!(parse_args(&args(&["list", "--json"])).unwrap(), Command::List { json: true });
+    assert_eq!(
+        parse_args(&args(&["watch", "--interval", "0.5"])).unwrap(),
+        Command::Watch { json: false, interval: Duration::from_millis(500) }
+    );
+    assert_eq!(
+        parse_args(&args(&["kill", "abc", "--grace-ms", "100", "--no-tree"])).unwrap(),
+        Command::Kill { id: "abc".to_string(), grace_ms: Some(100), no_tree: true }
+    );
+    assert_eq!(
+        parse_args(&args(&["--json", "explain", "abc"])).unwrap(),
+        Command::Explain { id: "abc".to_string(), json: true }
+    );
+    assert_eq!(parse_args(&args(&["mcp"])).unwrap(), Command::Mcp);
+    assert_eq!(
+        parse_args(&args(&["mcp", "register", "/repo"])).unwrap(),
+        Command::McpRegister { project: Some("/repo".to_string()) }
+    );
+}
+
+#[test]
+fn test_parse_args_errors() {
+    assert!(parse_args(&args(&["focus"])).is_err());
+    assert!(parse_args(&args(&["kill", "a", "b"])).is_err());
+    assert!(parse_args(&args(&["list", "extra"])).is_err());
+    assert!(parse_args(&args(&["watch", "--interval", "0"])).is_err());
+    assert!(parse_args(&args(&["list", "--verbose"])).is_err());
+    assert!(parse_args(&args(&["frobnicate"])).is_err());
+    assert!(parse_args(&args(&["mcp", "serve"])).is_err());
+}
+
+#[test]
+fn test_find_session_by_prefix_and_pid() {
+    let sessions = vec![
+        session("abc12345-0000", 100, "idle"),
+        session("abd67890-0000", 200, "waiting"),
+    ];
+
+    assert_eq!(find_session(&sessions, "abc").unwrap().pid, 100);
+    assert_eq!(find_session(&sessions, "abd67890-0000").unwrap().pid, 200);
+    assert_eq!(find_session(&sessions, "200").unwrap().id, "abd67890-0000");
+
+    let ambiguous = find_session(&sessions, "ab").unwrap_err();
+    assert!(ambiguous.contains("ambiguous"), "{}", ambiguous);
+    assert!(find_session(&sessions, "zzz").is_err());
+}
+
+#[test]
+fn test_diff_sessions() {
+    let before = vec![session("a", 1, "thinking"), session("b", 2, "idle")];
+    let after = vec![session("a", 1, "waiting"), session("c", 3, "thinking")];
+
+    let events = diff_sessions(&before, &after);
+    assert_eq!(events.len(), 3);
+    assert!(matches!(&events[0], SessionEvent::Changed { from: SessionStatus::Thinking, session } if session.id == "a"));
+    assert!(matches!(&events[1], SessionEvent::Added { session } if session.id == "c"));
+    assert!(matches!(&events[2], SessionEvent::Removed { id, .. } if id == "b"));
+
+    assert!(diff_sessions(&after, &after).is_empty());
+
+    let json = serde_json::to_value(&events[0]).unwrap();
+    assert_eq!(json["event"], "changed");
+    assert_eq!(json["from"], "thinking");
+    assert_eq!(json["session"]["status"], "waiting");
+    assert_eq!(format_event(&events[0]), "~ a  my-project  thinking -> waiting");
+}
+
+#[test]
+fn test_format_table() {
+    let table = format_table(&[session("abc12345-ffff", 4242, "waiting")]);
+    let lines: Vec<&str> = table.lines().collect();
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("ID        STATUS   PID   CPU  PROJECT     BRANCH"), "{}", lines[0]);
+    assert!(lines[1].starts_with("abc12345  waiting  4242  1%   my-project  main"), "{}", lines[1]);
+}
@@ -0,0 +1,154 @@
+use crate::hooks::{run_hook, Hook, HookContext, HookRun, HookSettings};
+use crate::jsonl_log;
+use crate::session::{SessionEvent, SessionStatus};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+use super::cli_tests::session;
+
+fn hooks(json: serde_json::Value) -> HookSettings {
+    serde_json::from_value(json).unwrap()
+}
+
+fn context(session_id: &str) -> HookContext {
+    HookContext {
+        session_id: session_id.to_string(),
+        project_path: "/nonexistent/project".to_string(),
+        status: "waiting".to_string(),
+        previous_status: "thinking".to_string(),
+        branch: "main".to_string(),
+    }
+}
+
+#[test]
+fn test_hooks_for_events() {
+    let settings = hooks(serde_json::json!({
+        "on_session_start": "start",
+        "on_status_change": "change",
+        "on_waiting": "waiting",
+        "on_session_end": "  "
+    }));
+    assert_eq!(settings.timeout_secs, 30);
+
+    let added = SessionEvent::Added { session: Box::new(session("a", 1, "thinking")) };
+    assert_eq!(settings.hooks_for(&added), vec![(Hook::OnSessionStart, "start")]);
+
+    let waiting = SessionEvent::Changed {
+        from: SessionStatus::Thinking,
+        session: Box::new(session("a", 1, "waiting")),
+    };
+    assert_eq!(
+        settings.hooks_for(&waiting),
+        vec![(Hook::OnStatusChange, "change"), (Hook::OnWaiting, "waiting")]
+    );
+
+    let idle = SessionEvent::Changed {
+        from: SessionStatus::Waiting,
+        session: Box::new(session("a", 1, "idle")),
+    };
+    assert_eq!(settings.hooks_for(&idle), vec![(Hook::OnStatusChange, "change")]);
+
+    // Blank commands count as unset
+    let removed = SessionEvent::Removed { id: "a".into(), project: "my-project".into() };
+    assert!(settings.hooks_for(&removed).is_empty());
+    assert!(HookSettings::default().hooks_for(&added).is_empty());
+}
+
+#[test]
+fn test_context_for_events() {
+    let changed = SessionEvent::Changed {
+        from: SessionStatus::Processing,
+        session: Box::new(session("a", 1, "waiting")),
+    };
+    let known = HashMap::from([("a".to_string(), session("a", 1, "idle"))]);
+
+    assert_eq!(
+        HookContext::for_event(&changed, &HashMap::new()),
+        HookContext {
+            session_id: "a".into(),
+            project_path: "/tmp/my-project".into(),
+            status: "waiting".into(),
+            previous_status: "processing".into(),
+            branch: "main".into(),
+        }
+    );
+
+    let removed = SessionEvent::Removed { id: "a".into(), project: "my-project".into() };
+    let ended = HookContext::for_event(&removed, &known);
+    assert_eq!(ended.status, "ended");
+    assert_eq!(ended.previous_status, "idle");
+    assert_eq!(ended.project_path, "/tmp/my-project");
+
+    let unknown = HookContext::for_event(&removed, &HashMap::new());
+    assert_eq!(unknown.session_id, "a");
+    assert_eq!(unknown.status, "ended");
+    assert_eq!(unknown.project_path, "");
+}
+
+#[test]
+fn test_run_hook_env_and_output() {
+    let run = run_hook(
+        Hook::OnWaiting,
+        "echo \"$AGENT_SESSION_ID $AGENT_PROJECT_PATH $AGENT_STATUS $AGENT_PREVIOUS_STATUS $AGENT_BRANCH $AGENT_HOOK\"; echo oops >&2; exit 3",
+        &context("s1"),
+        Duration::from_secs(10),
+    );
+
+    assert_eq!(run.stdout, "s1 /nonexistent/project waiting thinking main on_waiting\n");
+    assert_eq!(run.stderr, "oops\n");
+    assert_eq!(run.exit_code, Some(3));
+    assert!(!run.timed_out);
+    assert_eq!(run.error, None);
+    assert_eq!(run.session_id, "s1");
+}
+
+#[test]
+fn test_run_hook_timeout_kills_children() {
+    let started = Instant::now();
+    let run = run_hook(
+        Hook::OnSessionStart,
+        "echo before; sleep 30 & sleep 30",
+        &context("s1"),
+        Duration::from_millis(300),
+    );
+
+    assert!(run.timed_out);
+    assert_eq!(run.exit_code, None);
+    assert_eq!(run.stdout, "before\n");
+    // Returning at all means the backgrounded sleep was killed too: it held the pipe open
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn test_run_hook_kills_background_jobs_holding_output() {
+    let started = Instant::now();
+    let run = run_hook(
+        Hook::OnSessionEnd,
+        "(sleep 30; echo late) & echo hi",
+        &context("s1"),
+        Duration::from_millis(300),
+    );
+
+    // The shell exits at once, but its background job keeps stdout open
+    assert_eq!(run.exit_code, Some(0));
+    assert!(run.timed_out);
+    assert_eq!(run.stdout, "hi\n");
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn test_hooks_log_round_trip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("hooks.log");
+    let run = run_hook(Hook::OnStatusChange, "echo hi", &context("s1"), Duration::from_secs(10));
+
+    jsonl_log::append(&path, &run);
+    jsonl_log::append(&path, &run);
+    let runs: Vec<HookRun> = jsonl_log::read(&path);
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0], run);
+
+    let line = std::fs::read_to_string(&path).unwrap();
+    assert!(line.contains("\"hook\":\"on_status_change\""));
+}
@@ -0,0 +1,108 @@
+use crate::hotkeys::{
+    in_display_order, normalize_shortcut, plan, session_at, shortcuts, HotkeyAction, HotkeyBindings, KillConfirm, KILL_CONFIRM_WINDOW,
+};
+use std::time::{Duration, Instant};
+
+use super::cli_tests::session;
+
+fn bindings(json: serde_json::Value) -> HotkeyBindings {
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn test_default_bindings_keep_the_toggle_hotkey() {
+    let defaults = bindings(serde_json::json!({}));
+    assert_eq!(defaults.toggle_window.as_deref(), Some("Control+Space"));
+    assert_eq!(shortcuts(&defaults), vec![("Control+Space".to_string(), HotkeyAction::ToggleWindow)]);
+}
+
+#[test]
+fn test_focus_session_binds_one_to_nine() {
+    let b = bindings(serde_json::json!({"toggleWindow": null, "focusSession": "Control+Option"}));
+    let all = shortcuts(&b);
+
+    assert_eq!(all.len(), 9);
+    assert_eq!(all[0], ("Control+Option+1".to_string(), HotkeyAction::FocusSession(1)));
+    assert_eq!(all[8], ("Control+Option+9".to_string(), HotkeyAction::FocusSession(9)));
+}
+
+#[test]
+fn test_normalize_shortcut() {
+    assert_eq!(normalize_shortcut("Option+Ctrl+k").unwrap(), "ctrl+alt+K");
+    assert_eq!(normalize_shortcut("Control+Alt+K").unwrap(), "ctrl+alt+K");
+    assert_eq!(normalize_shortcut("Command+Shift+Space").unwrap(), "super+shift+SPACE");
+    assert_eq!(normalize_shortcut("F13").unwrap(), "F13");
+
+    assert!(normalize_shortcut("K").unwrap_err().contains("needs a modifier"));
+    assert!(normalize_shortcut("Control+Shift").unwrap_err().contains("no key"));
+    assert!(normalize_shortcut("Hyper+K").unwrap_err().contains("Unknown modifier"));
+    assert!(normalize_shortcut("Control++").is_err());
+}
+
+#[test]
+fn test_plan_reports_duplicates_and_invalid_bindings() {
+    let b = bindings(serde_json::json!({
+        "toggleWindow": "Control+Space",
+        "nextWaiting": "Ctrl+space",
+        "focusSession": "Control+Option",
+        "killFocused": "Q"
+    }));
+    let (planned, conflicts) = plan(&b);
+
+    // The toggle keeps its shortcut, all nine focus slots register
+    assert_eq!(planned.len(), 10);
+    assert_eq!(planned[0].1, HotkeyAction::ToggleWindow);
+
+    assert_eq!(conflicts.len(), 2);
+    assert_eq!(conflicts[0].binding, "nextWaiting");
+    assert_eq!(conflicts[0].reason, "Already used by \"Show/hide window\"");
+    assert_eq!(conflicts[1].binding, "killFocused");
+    assert!(conflicts[1].reason.contains("needs a modifier"));
+
+    let json = serde_json::to_value(&conflicts[0]).unwrap();
+    assert_eq!(json["shortcut"], "Ctrl+space");
+}
+
+#[test]
+fn test_plan_reports_clash_with_a_focus_slot() {
+    let b = bindings(serde_json::json!({"focusSession": "Control+Option", "killFocused": "Option+Control+3"}));
+    let (_, conflicts) = plan(&b);
+
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].binding, "killFocused");
+    assert_eq!(conflicts[0].reason, "Already used by \"Focus session 3\"");
+}
+
+#[test]
+fn test_session_at_position() {
+    let sessions = vec![session("a", 1, "waiting"), session("b", 2, "idle")];
+    assert_eq!(session_at(&sessions, 1).unwrap().id, "a");
+    assert_eq!(session_at(&sessions, 2).unwrap().id, "b");
+    assert!(session_at(&sessions, 3).is_none());
+    assert!(session_at(&sessions, 0).is_none());
+}
+
+#[test]
+fn test_display_order_follows_the_window() {
+    let sessions = vec![session("a", 1, "waiting"), session("b", 2, "idle"), session("c", 3, "thinking")];
+    let order = vec!["c".to_string(), "gone".to_string(), "a".to_string()];
+
+    let ids: Vec<String> = in_display_order(&sessions, &order).into_iter().map(|s| s.id).collect();
+    assert_eq!(ids, vec!["c", "a", "b"]);
+}
+
+#[test]
+fn test_kill_needs_a_second_press() {
+    let start = Instant::now();
+    let mut confirm = KillConfirm::new();
+
+    assert!(!confirm.press("a", start));
+    assert!(confirm.press("a", start + Duration::from_secs(1)));
+    // Confirmed presses don't carry over
+    assert!(!confirm.press("a", start + Duration::from_secs(2)));
+
+    // Another session, or too late, starts over
+    assert!(!confirm.press("b", start + Duration::from_secs(3)));
+    assert!(!confirm.press("b", start + Duration::from_secs(4) + KILL_CONFIRM_WINDOW));
+    assert!(confirm.press("b", start + Duration::from_secs(5) + KILL_CONFIRM_WINDOW));
+}
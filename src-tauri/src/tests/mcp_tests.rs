@@ -0,0 +1,180 @@
+use crate::mcp::{register_in_project, McpServer, SERVER_NAME};
+use crate::session::edits::edited_files;
+use crate::session::notes::{add_note_in, notes_for_in};
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+fn server() -> McpServer {
+    McpServer::new(Vec::new())
+}
+
+#[test]
+fn test_mcp_initialize() {
+    let reply = server()
+        .handle(json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2024-11-05"}}))
+        .unwrap();
+
+    assert_eq!(reply["id"], 1);
+    assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+    assert_eq!(reply["result"]["serverInfo"]["name"], SERVER_NAME);
+    assert!(reply["result"]["capabilities"]["tools"].is_object());
+
+    let unknown_version = server()
+        .handle(json!({"jsonrpc": "2.0", "id": 2, "method": "initialize", "params": {"protocolVersion": "1999-01-01"}}))
+        .unwrap();
+    assert_eq!(unknown_version["result"]["protocolVersion"], "2025-03-26");
+}
+
+#[test]
+fn test_mcp_tools_list() {
+    let reply = server().handle(json!({"jsonrpc": "2.0", "id": "a", "method": "tools/list"})).unwrap();
+    let names: Vec<&str> = reply["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+
+    assert_eq!(names, vec!["list_sessions", "get_session", "files_touched_by_other_sessions", "post_note"]);
+}
+
+#[test]
+fn test_mcp_notifications_and_errors() {
+    let server = server();
+
+    assert!(server.handle(json!({"jsonrpc": "2.0", "method": "notifications/initialized"})).is_none());
+
+    let unknown = server.handle(json!({"jsonrpc": "2.0", "id": 3, "method": "resources/list"})).unwrap();
+    assert_eq!(unknown["error"]["code"], -32601);
+
+    let garbage = server.handle_text("{not json").unwrap();
+    assert_eq!(garbage["error"]["code"], -32700);
+
+    let batch = server
+        .handle(json!([
+            {"jsonrpc": "2.0", "id": 4, "method": "ping"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"}
+        ]))
+        .unwrap();
+    assert_eq!(batch.as_array().unwrap().len(), 1);
+    assert_eq!(batch[0]["id"], 4);
+}
+
+#[test]
+fn test_edited_files_from_transcript() {
+    let dir = TempDir::new().unwrap();
+    let transcript = dir.path().join("session.jsonl");
+    let lines = [
+        json!({"type": "user", "message": {"role": "user", "content": "fix the bug"}}),
+        json!({"type": "assistant", "timestamp": "2026-01-01T00:00:01Z", "message": {"content": [
+            {"type": "tool_use", "name": "Edit", "input": {"file_path": "/repo/src/a.rs"}},
+            {"type": "tool_use", "name": "Read", "input": {"file_path": "/repo/src/b.rs"}}
+        ]}}),
+        json!({"type": "assistant", "timestamp": "2026-01-01T00:00:02Z", "message": {"content": [
+            {"type": "tool_use", "name": "Write", "input": {"file_path": "/repo/src/c.rs"}},
+            {"type": "tool_use", "name": "NotebookEdit", "input": {"notebook_path": "/repo/nb.ipynb"}}
+        ]}}),
+        json!({"type": "assistant", "timestamp": "2026-01-01T00:00:03Z", "message": {"content": [
+            {"type": "tool_use", "name": "MultiEdit", "input": {"file_path": "/repo/src/a.rs"}}
+        ]}}),
+    ];
+    let content: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+    fs::write(&transcript, content.join("\n")).unwrap();
+
+    let files = edited_files(&transcript);
+    let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+
+    // Most recently edited first; reads don't count
+    assert_eq!(paths[0], "/repo/src/a.rs");
+    assert_eq!(files[0].last_edited_at.as_deref(), Some("2026-01-01T00:00:03Z"));
+    assert_eq!(files.len(), 3);
+    assert!(paths.contains(&"/repo/src/c.rs"));
+    assert!(paths.contains(&"/repo/nb.ipynb"));
+    assert!(!paths.contains(&"/repo/src/b.rs"));
+}
+
+#[test]
+fn test_notes_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes.json");
+
+    add_note_in(&path, "target", "I renamed Config::load", Some("sender")).unwrap();
+    add_note_in(&path, "target", "  second  ", None).unwrap();
+    assert!(add_note_in(&path, "target", "   ", None).is_err());
+    assert!(add_note_in(&path, "target", &"x".repeat(5000), None).is_err());
+
+    let notes = notes_for_in(&path, "target");
+    assert_eq!(notes.len(), 2);
+    assert_eq!(notes[0].text, "I renamed Config::load");
+    assert_eq!(notes[0].from_session_id.as_deref(), Some("sender"));
+    assert_eq!(notes[1].text, "second");
+    assert!(notes_for_in(&path, "other").is_empty());
+}
+
+#[test]
+fn test_notes_prune_expired() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes.json");
+    fs::write(
+        &path,
+        json!({"old": [{"text": "stale", "fromSessionId": null, "createdAt": "2020-01-01T00:00:00Z"}]}).to_string(),
+    )
+    .unwrap();
+
+    add_note_in(&path, "new", "fresh", None).unwrap();
+
+    assert!(notes_for_in(&path, "old").is_empty());
+    assert_eq!(notes_for_in(&path, "new").len(), 1);
+}
+
+#[test]
+fn test_notes_concurrent_writers_keep_every_note() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes.json");
+
+    let writers: Vec<_> = (0..8)
+        .map(|i| {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                for n in 0..10 {
+                    add_note_in(&path, &format!("session-{}", i), &format!("note {}", n), None).unwrap();
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    for i in 0..8 {
+        assert_eq!(notes_for_in(&path, &format!("session-{}", i)).len(), 10);
+    }
+}
+
+#[test]
+fn test_register_in_project_keeps_other_servers() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join(".mcp.json"),
+        json!({"mcpServers": {"other": {"command": "other-server"}}}).to_string(),
+    )
+    .unwrap();
+
+    let path = register_in_project(dir.path(), std::path::Path::new("/usr/local/bin/agent-sessions")).unwrap();
+    let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+
+    assert_eq!(config["mcpServers"]["other"]["command"], "other-server");
+    assert_eq!(config["mcpServers"][SERVER_NAME]["command"], "/usr/local/bin/agent-sessions");
+    assert_eq!(config["mcpServers"][SERVER_NAME]["args"], json!(["mcp"]));
+}
+
+#[test]
+fn test_register_in_project_creates_file() {
+    let dir = TempDir::new().unwrap();
+    register_in_project(dir.path(), std::path::Path::new("agent-sessions")).unwrap();
+
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join(".mcp.json")).unwrap()).unwrap();
+    assert_eq!(config["mcpServers"][SERVER_NAME]["type"], "stdio");
+}
@@ -0,0 +1,83 @@
+use crate::metrics::{escape_label, record_poll_at, record_scan, render_at};
+use crate::session::{Session, SessionsResponse};
+use std::time::{Duration, Instant};
+
+use super::cli_tests::session;
+
+fn response(sessions: Vec<Session>) -> SessionsResponse {
+    SessionsResponse {
+        total_count: sessions.len(),
+        waiting_count: 0,
+        sessions,
+    }
+}
+
+fn metric_line<'a>(metrics: &'a str, prefix: &str) -> Option<&'a str> {
+    metrics.lines().find(|l| l.starts_with(prefix))
+}
+
+#[test]
+fn test_metrics_status_counts_and_session_gauges() {
+    let mut waiting = session("metrics-waiting", 1, "waiting");
+    waiting.context_window_percent = Some(42.5);
+    waiting.tree_cpu_usage = 12.0;
+    let metrics = render_at(&response(vec![waiting, session("metrics-idle", 2, "idle")]), Instant::now());
+
+    assert!(metrics.contains("# TYPE agent_sessions_sessions gauge"));
+    assert!(metrics.contains("agent_sessions_sessions{status=\"waiting\"} 1\n"));
+    assert!(metrics.contains("agent_sessions_sessions{status=\"thinking\"} 0\n"));
+    assert!(metrics.contains("agent_sessions_sessions_by_agent{agent_type=\"claude\"} 2\n"));
+    assert!(metrics.contains(
+        "agent_sessions_context_window_percent{session_id=\"metrics-waiting\",project=\"my-project\",branch=\"main\"} 42.5\n"
+    ));
+    assert!(metrics.contains(
+        "agent_sessions_cpu_percent{session_id=\"metrics-waiting\",project=\"my-project\",branch=\"main\"} 12\n"
+    ));
+    // No context window known for the idle session
+    assert!(metric_line(&metrics, "agent_sessions_context_window_percent{session_id=\"metrics-idle\"").is_none());
+}
+
+#[test]
+fn test_metrics_transitions_and_status_duration() {
+    let start = Instant::now();
+    record_poll_at(&response(vec![session("metrics-transition", 1, "thinking")]), start);
+    record_poll_at(&response(vec![session("metrics-transition", 1, "waiting")]), start + Duration::from_secs(10));
+
+    let current = response(vec![session("metrics-transition", 1, "waiting")]);
+    record_poll_at(&current, start + Duration::from_secs(20));
+    let metrics = render_at(&current, start + Duration::from_secs(70));
+
+    let transitions = metric_line(&metrics, "agent_sessions_status_transitions_total{from=\"thinking\",to=\"waiting\"}")
+        .expect("transition counter");
+    let count: f64 = transitions.rsplit(' ').next().unwrap().parse().unwrap();
+    assert!(count >= 1.0);
+
+    // Waiting since the second poll, 60s before rendering
+    let duration = metric_line(&metrics, "agent_sessions_session_status_seconds{session_id=\"metrics-transition\"")
+        .expect("status duration");
+    assert!(duration.ends_with("status=\"waiting\"} 60"), "{}", duration);
+}
+
+#[test]
+fn test_metrics_scan_histogram() {
+    record_scan("metrics-test-detector", Duration::from_millis(30));
+    record_scan("metrics-test-detector", Duration::from_millis(700));
+    let metrics = render_at(&response(Vec::new()), Instant::now());
+
+    assert!(metrics.contains("# TYPE agent_sessions_detector_scan_duration_seconds histogram"));
+    assert!(metrics.contains(
+        "agent_sessions_detector_scan_duration_seconds_bucket{detector=\"metrics-test-detector\",le=\"0.025\"} 0\n"
+    ));
+    assert!(metrics.contains(
+        "agent_sessions_detector_scan_duration_seconds_bucket{detector=\"metrics-test-detector\",le=\"0.05\"} 1\n"
+    ));
+    assert!(metrics.contains(
+        "agent_sessions_detector_scan_duration_seconds_bucket{detector=\"metrics-test-detector\",le=\"+Inf\"} 2\n"
+    ));
+    assert!(metrics.contains("agent_sessions_detector_scan_duration_seconds_count{detector=\"metrics-test-detector\"} 2\n"));
+}
+
+#[test]
+fn test_escape_label() {
+    assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+}
@@ -1,2 +1,16 @@
+mod api_tests;
+mod cli_tests;
+mod hooks_tests;
+mod hotkeys_tests;
+mod mcp_tests;
+mod metrics_tests;
+mod notifications_tests;
 mod process_tests;
+mod rules_tests;
 mod session_tests;
+mod status_machine_tests;
+mod tmux_tests;
+mod tray_menu_tests;
+mod tray_status_tests;
+mod replay_tests;
+mod webhooks_tests;
@@ -0,0 +1,134 @@
+use crate::notifications::{NotificationSettings, Notifier, QuietHours};
+use crate::session::{SessionEvent, SessionStatus};
+use chrono::NaiveTime;
+use std::time::{Duration, Instant};
+
+use super::cli_tests::session;
+
+fn changed(id: &str, from: &str, to: &str) -> SessionEvent {
+    SessionEvent::Changed {
+        from: serde_json::from_value(serde_json::json!(from)).unwrap(),
+        session: Box::new(session(id, 1, to)),
+    }
+}
+
+fn time(s: &str) -> NaiveTime {
+    NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+}
+
+fn settings(json: serde_json::Value) -> NotificationSettings {
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn test_default_rules() {
+    let settings = NotificationSettings::default();
+    let waiting = session("s", 1, "waiting");
+    let idle = session("s", 1, "idle");
+
+    assert_eq!(settings.dwell_for(&waiting, &SessionStatus::Processing), Some(Duration::from_secs(5)));
+    assert_eq!(settings.dwell_for(&idle, &SessionStatus::Thinking), Some(Duration::from_secs(5)));
+    // Going quiet after waiting on the user, or resuming, isn't "finished"
+    assert_eq!(settings.dwell_for(&idle, &SessionStatus::Waiting), None);
+    assert_eq!(settings.dwell_for(&idle, &SessionStatus::Paused), None);
+    assert_eq!(settings.dwell_for(&session("s", 1, "thinking"), &SessionStatus::Idle), None);
+}
+
+#[test]
+fn test_project_overrides() {
+    let overrides = settings(serde_json::json!({
+        "on": [{"to": "waiting", "min_dwell_secs": 2}],
+        "min_dwell_secs": 10,
+        "projects": {
+            "/tmp/my-project": {"on": [{"to": "compacting"}, {"to": "waiting"}], "min_dwell_secs": 0}
+        }
+    }));
+    let compacting = session("s", 1, "compacting");
+    let waiting = session("s", 1, "waiting");
+
+    assert_eq!(overrides.dwell_for(&compacting, &SessionStatus::Thinking), Some(Duration::ZERO));
+    assert_eq!(overrides.dwell_for(&waiting, &SessionStatus::Thinking), Some(Duration::ZERO));
+
+    let muted = settings(serde_json::json!({"projects": {"my-project": {"enabled": false}}}));
+    assert_eq!(muted.dwell_for(&waiting, &SessionStatus::Thinking), None);
+
+    let only_project = settings(serde_json::json!({"enabled": false, "projects": {"my-project": {"enabled": true}}}));
+    assert!(only_project.dwell_for(&waiting, &SessionStatus::Thinking).is_some());
+}
+
+#[test]
+fn test_quiet_hours() {
+    let overnight = QuietHours { start: "22:00".into(), end: "08:00".into() };
+    assert!(overnight.contains(time("23:30")));
+    assert!(overnight.contains(time("07:59")));
+    assert!(!overnight.contains(time("08:00")));
+    assert!(!overnight.contains(time("12:00")));
+
+    let lunch = QuietHours { start: "12:00".into(), end: "13:00".into() };
+    assert!(lunch.contains(time("12:30")));
+    assert!(!lunch.contains(time("13:30")));
+
+    let malformed = QuietHours { start: "noon".into(), end: "13:00".into() };
+    assert!(!malformed.contains(time("12:30")));
+}
+
+#[test]
+fn test_notifier_waits_for_dwell() {
+    let settings = NotificationSettings::default();
+    let mut notifier = Notifier::new();
+    let start = Instant::now();
+    let noon = time("12:00");
+
+    notifier.observe(&changed("a", "processing", "waiting"), &settings, start);
+    let sessions = vec![session("a", 1, "waiting")];
+
+    assert!(notifier.due(&sessions, &settings, start + Duration::from_secs(2), noon).is_empty());
+    let due = notifier.due(&sessions, &settings, start + Duration::from_secs(5), noon);
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].session_id, "a");
+    assert_eq!(due[0].title, "my-project needs your input");
+    assert_eq!(due[0].body, "main");
+    assert_eq!(due[0].terminal_app, "iterm2");
+
+    // Fired once only
+    assert!(notifier.due(&sessions, &settings, start + Duration::from_secs(10), noon).is_empty());
+    assert!(!notifier.has_pending());
+}
+
+#[test]
+fn test_notifier_drops_transitions_that_did_not_stick() {
+    let settings = NotificationSettings::default();
+    let mut notifier = Notifier::new();
+    let start = Instant::now();
+    let later = start + Duration::from_secs(30);
+
+    // Left Waiting before the dwell passed
+    notifier.observe(&changed("a", "processing", "waiting"), &settings, start);
+    notifier.observe(&changed("a", "waiting", "processing"), &settings, start + Duration::from_secs(1));
+    assert!(notifier.due(&[session("a", 1, "processing")], &settings, later, time("12:00")).is_empty());
+
+    // Ended
+    notifier.observe(&changed("b", "thinking", "idle"), &settings, start);
+    notifier.observe(
+        &SessionEvent::Removed { id: "b".into(), project: "my-project".into() },
+        &settings,
+        start,
+    );
+    assert!(!notifier.has_pending());
+
+    // Already moved on when the dwell passed, with its event still in flight
+    notifier.observe(&changed("c", "thinking", "idle"), &settings, start);
+    assert!(notifier.due(&[session("c", 1, "thinking")], &settings, later, time("12:00")).is_empty());
+}
+
+#[test]
+fn test_notifier_quiet_hours_suppress() {
+    let settings = settings(serde_json::json!({"quiet_hours": {"start": "22:00", "end": "08:00"}}));
+    let mut notifier = Notifier::new();
+    let start = Instant::now();
+
+    notifier.observe(&changed("a", "processing", "waiting"), &settings, start);
+    let sessions = vec![session("a", 1, "waiting")];
+    assert!(notifier.due(&sessions, &settings, start + Duration::from_secs(5), time("23:00")).is_empty());
+    assert!(!notifier.has_pending());
+}
@@ -1,4 +1,8 @@
-use crate::process::{find_claude_processes, is_orphaned_process, ClaudeProcess};
+use crate::process::{
+    children_map, classify_claude_command, hidden_launchers, describe_command, dev_server_links, find_claude_processes,
+    is_orphaned_process, is_paused_in, is_vscode_extension_command, kill_process, listening_ports, parse_lsof_listen, parse_proc_net_tcp,
+    pause_process_in, process_tree, resume_process_in, wildcard_match, ClaudeLaunch, ClaudeProcess, KillSettings, KillSignal, ListeningPort,
+};
 use std::path::PathBuf;
 use sysinfo::{ProcessRefreshKind, RefreshKind, System};
 
@@ -10,6 +14,9 @@ fn test_claude_process_creation() {
         cpu_usage: 5.5,
         memory: 1024,
         start_time: 0,
+        config_dir: None,
+        host: None,
+        tree: Default::default(),
     };
 
     assert_eq!(process.pid, 12345);
@@ -29,6 +36,9 @@ fn test_claude_process_without_cwd() {
         cpu_usage: 0.0,
         memory: 0,
         start_time: 0,
+        config_dir: None,
+        host: None,
+        tree: Default::default(),
     };
 
     assert_eq!(process.pid, 99999);
@@ -43,6 +53,9 @@ fn test_claude_process_clone() {
         cpu_usage: 10.0,
         memory: 2048,
         start_time: 0,
+        config_dir: None,
+        host: None,
+        tree: Default::default(),
     };
 
     let cloned = process.clone();
@@ -60,6 +73,9 @@ fn test_claude_process_serialization() {
         cpu_usage: 5.5,
         memory: 1024,
         start_time: 0,
+        config_dir: None,
+        host: None,
+        tree: Default::default(),
     };
 
     let json = serde_json::to_string(&process).unwrap();
@@ -160,3 +176,378 @@ fn test_is_orphaned_process_with_launchd() {
         let _ = is_orphaned_process(&system, process);
     }
 }
+
+fn argv(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn test_classify_claude_binary() {
+    assert_eq!(classify_claude_command(&argv(&["claude"]), &[]), Some(ClaudeLaunch::Direct));
+    assert_eq!(classify_claude_command(&argv(&["/opt/homebrew/bin/claude", "--resume"]), &[]), Some(ClaudeLaunch::Direct));
+    assert_eq!(classify_claude_command(&argv(&["claude-sessions"]), &[]), None);
+    assert_eq!(classify_claude_command(&argv(&[]), &[]), None);
+}
+
+#[test]
+fn test_classify_js_runtime_launches() {
+    let node = argv(&["node", "--no-warnings", "/usr/local/lib/node_modules/@anthropic-ai/claude-code/cli.js"]);
+    assert_eq!(classify_claude_command(&node, &[]), Some(ClaudeLaunch::Direct));
+
+    let versioned = argv(&["/Users/me/.nvm/versions/node/v20.11.0/bin/node20", "/Users/me/.nvm/versions/node/v20.11.0/bin/claude"]);
+    assert_eq!(classify_claude_command(&versioned, &[]), Some(ClaudeLaunch::Direct));
+
+    let bun = argv(&["bun", "/Users/me/.bun/install/global/node_modules/@anthropic-ai/claude-code/cli.js"]);
+    assert_eq!(classify_claude_command(&bun, &[]), Some(ClaudeLaunch::Direct));
+
+    let bun_x = argv(&["bun", "x", "@anthropic-ai/claude-code"]);
+    assert_eq!(classify_claude_command(&bun_x, &[]), Some(ClaudeLaunch::Launcher));
+
+    // Unrelated node processes and the Zed ACP adapter are not Claude Code
+    assert_eq!(classify_claude_command(&argv(&["node", "server.js"]), &[]), None);
+    let acp = argv(&["node", "/Users/me/Library/Application Support/Zed/node/@zed-industries/claude-code-acp/dist/index.js"]);
+    assert_eq!(classify_claude_command(&acp, &[]), None);
+}
+
+#[test]
+fn test_classify_package_runner_launches() {
+    assert_eq!(classify_claude_command(&argv(&["npx", "@anthropic-ai/claude-code"]), &[]), Some(ClaudeLaunch::Launcher));
+    assert_eq!(classify_claude_command(&argv(&["npx", "-y", "@anthropic-ai/claude-code@1.0.80"]), &[]), Some(ClaudeLaunch::Launcher));
+    assert_eq!(classify_claude_command(&argv(&["pnpm", "dlx", "@anthropic-ai/claude-code"]), &[]), Some(ClaudeLaunch::Launcher));
+    assert_eq!(classify_claude_command(&argv(&["npx", "@anthropic-ai/claude-code-router"]), &[]), None);
+    assert_eq!(classify_claude_command(&argv(&["npx", "prettier"]), &[]), None);
+}
+
+#[test]
+fn test_hidden_launchers_look_through_shells() {
+    use sysinfo::Pid;
+    let pid = Pid::from_u32;
+
+    // npx -> sh -c -> node cli.js, a wrapper running the CLI directly, an npx
+    // still starting up, and a sub-agent of the npx session
+    let launches: std::collections::HashMap<Pid, ClaudeLaunch> = [
+        (10, ClaudeLaunch::Launcher),
+        (12, ClaudeLaunch::Direct),
+        (13, ClaudeLaunch::Direct),
+        (20, ClaudeLaunch::Launcher),
+        (21, ClaudeLaunch::Direct),
+        (30, ClaudeLaunch::Launcher),
+    ]
+    .into_iter()
+    .map(|(p, launch)| (pid(p), launch))
+    .collect();
+    let parents = [(11, 10), (12, 11), (13, 12), (21, 20), (10, 1), (20, 1), (30, 1)];
+    let parent_of = |child: Pid| parents.iter().find(|(c, _)| pid(*c) == child).map(|(_, p)| pid(*p));
+
+    let hidden = hidden_launchers(&launches, parent_of);
+    assert!(hidden.contains(&pid(10)), "launcher with a shell before the CLI");
+    assert!(hidden.contains(&pid(20)));
+    assert!(!hidden.contains(&pid(30)), "a launcher without a CLI below it stays visible");
+    assert_eq!(hidden.len(), 2);
+}
+
+#[test]
+fn test_classify_extra_patterns() {
+    let patterns = vec!["acme-ai *--agent".to_string(), "/opt/corp/bin/cc-wrapper".to_string()];
+
+    assert_eq!(classify_claude_command(&argv(&["acme-ai", "chat", "--agent"]), &patterns), Some(ClaudeLaunch::Launcher));
+    assert_eq!(classify_claude_command(&argv(&["/opt/corp/bin/cc-wrapper", "--team", "infra"]), &patterns), Some(ClaudeLaunch::Launcher));
+    assert_eq!(classify_claude_command(&argv(&["acme-ai", "chat"]), &patterns), None);
+}
+
+#[test]
+fn test_wildcard_match() {
+    assert!(wildcard_match("claude", "/usr/bin/claude --resume"));
+    assert!(wildcard_match("*cli.js", "node /x/cli.js"));
+    assert!(wildcard_match("node *claude*", "node /x/claude/cli.js"));
+    assert!(!wildcard_match("node *claude", "node /x/claude/cli.js"));
+    assert!(!wildcard_match("bun *", "node x"));
+}
+
+#[test]
+fn test_is_vscode_extension_command() {
+    let extension = argv(&["/Users/me/.vscode/extensions/anthropic.claude-code-2.0.14-darwin-arm64/resources/native-binary/claude"]);
+    assert!(is_vscode_extension_command(&extension));
+    assert_eq!(classify_claude_command(&extension, &[]), Some(ClaudeLaunch::Direct));
+
+    let cursor = argv(&["node", "/Users/me/.cursor/extensions/anthropic.claude-code-1.0.98/cli.js"]);
+    assert!(is_vscode_extension_command(&cursor));
+    assert_eq!(classify_claude_command(&cursor, &[]), Some(ClaudeLaunch::Direct));
+
+    assert!(!is_vscode_extension_command(&argv(&["/opt/homebrew/bin/claude"])));
+}
+
+#[test]
+fn test_describe_command() {
+    assert_eq!(describe_command(&argv(&["/usr/bin/cargo", "test", "--workspace"]), "cargo"), "cargo test --workspace");
+    assert_eq!(describe_command(&[], "node"), "node");
+
+    let long = describe_command(&argv(&["pytest", &"x".repeat(200)]), "pytest");
+    assert_eq!(long.chars().count(), 80);
+    assert!(long.ends_with('…'));
+}
+
+#[test]
+fn test_process_tree_lists_commands_under_shells() {
+    let mut child = std::process::Command::new("sh")
+        .args(["-c", "sleep 30; true"])
+        .spawn()
+        .expect("spawn sh");
+
+    // Give the shell a moment to start sleep
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::All,
+        ProcessRefreshKind::new()
+            .with_cmd(sysinfo::UpdateKind::Always)
+            .with_memory()
+    );
+    let children = children_map(&system);
+    let root = sysinfo::Pid::from_u32(std::process::id());
+    let tree = process_tree(&system, &children, root, u64::MAX / 2);
+
+    child.kill().ok();
+    child.wait().ok();
+
+    assert!(tree.memory > 0);
+    assert!(
+        tree.children.iter().any(|c| c.command == "sleep 30"),
+        "expected sleep under the shell wrapper, got {:?}",
+        tree.children
+    );
+    assert!(
+        !tree.children.iter().any(|c| c.command.starts_with("sh ")),
+        "shell wrappers should be looked through: {:?}",
+        tree.children
+    );
+}
+
+#[test]
+fn test_process_tree_skips_processes_started_directly() {
+    // Stands in for an MCP server the agent launches without a shell
+    let mut child = std::process::Command::new("sleep")
+        .arg("31")
+        .spawn()
+        .expect("spawn sleep");
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::All,
+        ProcessRefreshKind::new()
+            .with_cmd(sysinfo::UpdateKind::Always)
+            .with_memory()
+    );
+    let children = children_map(&system);
+    let root = sysinfo::Pid::from_u32(std::process::id());
+    let tree = process_tree(&system, &children, root, u64::MAX / 2);
+
+    child.kill().ok();
+    child.wait().ok();
+
+    assert!(tree.descendants.contains(&child.id()));
+    assert!(
+        !tree.children.iter().any(|c| c.pid == child.id()),
+        "directly started processes should not be listed: {:?}",
+        tree.children
+    );
+}
+
+#[test]
+fn test_parse_proc_net_tcp() {
+    let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1435 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 412345 1 0000000000000000 100 0 0 10 0
+   1: 00000000:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 412346 1 0000000000000000 100 0 0 10 0
+   2: 0100007F:9C40 0100007F:1435 01 00000000:00000000 00:00000000 00000000  1000        0 412347 1 0000000000000000 20 4 30 10 -1
+";
+    let inodes = parse_proc_net_tcp(table);
+    assert_eq!(inodes.get(&412345), Some(&5173));
+    assert_eq!(inodes.get(&412346), Some(&3000));
+    // Established connections are not listeners
+    assert!(!inodes.contains_key(&412347));
+}
+
+#[test]
+fn test_parse_lsof_listen() {
+    let output = "p4242\nf21\nn*:5173\nf22\nn[::1]:5173\np4243\nf9\nn127.0.0.1:8080\n";
+    let ports = parse_lsof_listen(output);
+    assert_eq!(
+        ports,
+        vec![ListeningPort { pid: 4242, port: 5173 }, ListeningPort { pid: 4243, port: 8080 }]
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_listening_ports_finds_own_listener() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let ports = listening_ports(&[std::process::id()]);
+    assert!(ports.iter().any(|p| p.port == port), "expected port {} in {:?}", port, ports);
+
+    let links = dev_server_links(&[std::process::id()]);
+    let link = links.iter().find(|l| l.url == format!("http://localhost:{}", port)).expect("link");
+    assert!(link.detected);
+}
+
+/// Spawn `sh -c script` and wait until it has at least one child process
+fn spawn_shell_with_child(script: &str) -> (std::process::Child, u32) {
+    let mut child = std::process::Command::new("sh").args(["-c", script]).spawn().expect("spawn sh");
+    let shell = sysinfo::Pid::from_u32(child.id());
+
+    for _ in 0..40 {
+        let mut system = System::new();
+        system.refresh_processes_specifics(sysinfo::ProcessesToUpdate::All, ProcessRefreshKind::new());
+        if let Some(grandchild) = children_map(&system).get(&shell).and_then(|c| c.first()) {
+            return (child, grandchild.as_u32());
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    child.kill().ok();
+    child.wait().ok();
+    panic!("shell never started its child");
+}
+
+fn is_running(pid: u32) -> bool {
+    let mut system = System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    system.refresh_processes_specifics(sysinfo::ProcessesToUpdate::Some(&[pid]), ProcessRefreshKind::new());
+    system
+        .process(pid)
+        .map(|p| p.status() != sysinfo::ProcessStatus::Zombie)
+        .unwrap_or(false)
+}
+
+#[test]
+fn test_kill_settings_parse() {
+    let settings: KillSettings = serde_json::from_str(r#"{"signal": "sigint", "grace_period_ms": 500}"#).unwrap();
+    assert_eq!(settings.signal, KillSignal::Sigint);
+    assert_eq!(settings.grace_period_ms, 500);
+    assert!(settings.kill_tree, "unset fields keep their defaults");
+}
+
+#[test]
+fn test_kill_process_missing_pid() {
+    assert!(kill_process(u32::MAX - 1, &KillSettings::default()).is_err());
+}
+
+#[test]
+fn test_kill_process_terminates_tree() {
+    let (mut shell, sleeper) = spawn_shell_with_child("sleep 30 & wait");
+
+    let report = kill_process(shell.id(), &KillSettings::default()).unwrap();
+    shell.wait().ok();
+
+    assert!(report.terminated.contains(&shell.id()), "{:?}", report);
+    assert!(report.terminated.contains(&sleeper), "{:?}", report);
+    assert!(report.force_killed.is_empty(), "{:?}", report);
+    assert!(!is_running(sleeper));
+}
+
+#[test]
+fn test_kill_process_leaves_tree_when_disabled() {
+    let (mut shell, sleeper) = spawn_shell_with_child("sleep 30 & wait");
+    let settings = KillSettings {
+        kill_tree: false,
+        ..KillSettings::default()
+    };
+
+    let report = kill_process(shell.id(), &settings).unwrap();
+    shell.wait().ok();
+    let survived = is_running(sleeper);
+    std::process::Command::new("kill").arg(sleeper.to_string()).output().ok();
+
+    assert_eq!(report.all(), vec![shell.id()]);
+    assert!(survived, "descendants are left alone without kill_tree");
+}
+
+#[test]
+fn test_kill_process_escalates_to_sigkill() {
+    // Ignored signals stay ignored across exec, so neither process honours SIGTERM
+    let (mut shell, sleeper) = spawn_shell_with_child("trap '' TERM; sleep 30 & wait");
+    let settings = KillSettings {
+        grace_period_ms: 300,
+        ..KillSettings::default()
+    };
+
+    let report = kill_process(shell.id(), &settings).unwrap();
+    shell.wait().ok();
+
+    assert!(report.force_killed.contains(&shell.id()), "{:?}", report);
+    assert!(report.force_killed.contains(&sleeper), "{:?}", report);
+    assert!(!is_running(sleeper));
+}
+
+fn process_status(pid: u32) -> Option<sysinfo::ProcessStatus> {
+    let mut system = System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    system.refresh_processes_specifics(sysinfo::ProcessesToUpdate::Some(&[pid]), ProcessRefreshKind::new());
+    system.process(pid).map(|p| p.status())
+}
+
+#[test]
+fn test_pause_and_resume_tree() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("paused.json");
+    let (mut shell, sleeper) = spawn_shell_with_child("sleep 30 & wait");
+    let pid = shell.id();
+
+    let stopped = pause_process_in(&path, pid).unwrap();
+    assert_eq!(stopped.first(), Some(&pid), "the agent is stopped first");
+    assert!(stopped.contains(&sleeper));
+    assert!(is_paused_in(&path, pid));
+    assert!(std::fs::read_to_string(&path).unwrap().contains(&pid.to_string()));
+    assert_eq!(process_status(sleeper), Some(sysinfo::ProcessStatus::Stop));
+
+    let continued = resume_process_in(&path, pid).unwrap();
+    assert_eq!(continued.last(), Some(&pid), "the agent is continued last");
+    assert!(!is_paused_in(&path, pid));
+    assert_ne!(process_status(sleeper), Some(sysinfo::ProcessStatus::Stop));
+
+    kill_process(pid, &KillSettings::default()).ok();
+    shell.wait().ok();
+}
+
+#[test]
+fn test_is_paused_forgets_externally_continued_process() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("paused.json");
+    let (mut shell, _) = spawn_shell_with_child("sleep 30 & wait");
+    let pid = shell.id();
+
+    pause_process_in(&path, pid).unwrap();
+    std::process::Command::new("kill").args(["-CONT", &pid.to_string()]).output().unwrap();
+
+    assert!(!is_paused_in(&path, pid), "a process continued elsewhere is no longer paused");
+
+    // The sleeper is still stopped
+    resume_process_in(&path, pid).ok();
+    kill_process(pid, &KillSettings::default()).ok();
+    shell.wait().ok();
+}
+
+#[test]
+fn test_pause_missing_process() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("paused.json");
+    assert!(pause_process_in(&path, u32::MAX - 1).is_err());
+    assert!(!is_paused_in(&path, u32::MAX - 1));
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_kill_paused_process_without_waiting_out_grace_period() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut shell, sleeper) = spawn_shell_with_child("sleep 30 & wait");
+    pause_process_in(&dir.path().join("paused.json"), shell.id()).unwrap();
+
+    let started = std::time::Instant::now();
+    let report = kill_process(shell.id(), &KillSettings::default()).unwrap();
+    shell.wait().ok();
+
+    assert!(report.force_killed.is_empty(), "{:?}", report);
+    assert!(report.terminated.contains(&sleeper), "{:?}", report);
+    assert!(started.elapsed() < std::time::Duration::from_secs(2), "took {:?}", started.elapsed());
+}
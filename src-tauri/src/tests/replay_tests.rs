@@ -0,0 +1,86 @@
+use crate::session::replay::{parse_fixture, replay, snapshot_project, PollFixture};
+use std::time::SystemTime;
+use tempfile::TempDir;
+
+/// Replay a fixture and fail with every mismatching poll
+fn assert_replay(fixture: &str) {
+    let polls = parse_fixture(fixture).expect("fixture parses");
+    let work_dir = TempDir::new().unwrap();
+    let steps = replay(&polls, &work_dir.path().join("projects")).expect("replay runs");
+
+    assert_eq!(steps.len(), polls.len());
+    let mismatches: Vec<String> = steps.iter().flat_map(|s| s.mismatches()).collect();
+    assert!(mismatches.is_empty(), "Replay mismatches:\n{}", mismatches.join("\n"));
+}
+
+#[test]
+fn test_replay_tool_gap_does_not_flicker_to_waiting() {
+    // Tool running with a long gap between progress writes: a single quiet
+    // poll must not flip the card to Waiting
+    assert_replay(include_str!("fixtures/tool_gap_flicker.jsonl"));
+}
+
+#[test]
+fn test_replay_active_subagent_keeps_session_processing() {
+    assert_replay(include_str!("fixtures/subagent_override.jsonl"));
+}
+
+#[test]
+fn test_parse_fixture_reports_bad_line() {
+    let err = parse_fixture("{\"atMs\":0,\"processes\":[],\"projects\":[]}\nnot json\n").unwrap_err();
+    assert!(err.contains("line 2"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_parse_fixture_skips_blank_lines_and_defaults_expected() {
+    let polls = parse_fixture("\n{\"atMs\":5,\"processes\":[],\"projects\":[]}\n\n").unwrap();
+    assert_eq!(polls.len(), 1);
+    assert_eq!(polls[0].at_ms, 5);
+    assert!(polls[0].expected.is_empty());
+}
+
+#[test]
+fn test_snapshot_round_trips_through_write_to() {
+    let fixture = include_str!("fixtures/subagent_override.jsonl");
+    let poll: PollFixture = parse_fixture(fixture).unwrap().remove(0);
+
+    let root = TempDir::new().unwrap();
+    poll.write_to(root.path(), SystemTime::now()).unwrap();
+
+    let project = &poll.projects[0];
+    let snapshot = snapshot_project(&project.dir_name, &root.path().join(&project.dir_name));
+
+    assert_eq!(snapshot.files.len(), project.files.len());
+    for original in &project.files {
+        let copy = snapshot.files.iter().find(|f| f.path == original.path)
+            .unwrap_or_else(|| panic!("{} missing from snapshot", original.path));
+        assert_eq!(copy.lines, original.lines);
+        // mtimes survive the round trip (allow for time spent in the test)
+        assert!(copy.age_ms >= original.age_ms && copy.age_ms < original.age_ms + 2000,
+            "age {} drifted from {}", copy.age_ms, original.age_ms);
+    }
+}
+
+#[test]
+fn test_replay_empty_poll_yields_no_sessions() {
+    let polls = parse_fixture("{\"atMs\":0,\"processes\":[],\"projects\":[],\"expected\":[]}").unwrap();
+    let work_dir = TempDir::new().unwrap();
+    let steps = replay(&polls, &work_dir.path().join("projects")).unwrap();
+    assert!(steps[0].statuses.is_empty());
+}
+
+#[test]
+fn test_explain_status_reports_subagent_override() {
+    let polls = parse_fixture(include_str!("fixtures/subagent_override.jsonl")).unwrap();
+    let work_dir = TempDir::new().unwrap();
+    let steps = replay(&polls[..1], &work_dir.path().join("projects")).unwrap();
+
+    let explanation = steps[0].explanations.get("replay-subagent").expect("explanation recorded by scan");
+    assert_eq!(explanation.last_msg_type.as_deref(), Some("assistant"));
+    assert!(!explanation.has_tool_use);
+    assert_eq!(explanation.content_status, crate::session::SessionStatus::Idle);
+    assert!(explanation.subagent_override);
+    assert_eq!(explanation.active_subagent_count, 1);
+    assert_eq!(explanation.final_status, crate::session::SessionStatus::Processing);
+    assert_eq!(explanation.decision_path.len(), 2);
+}
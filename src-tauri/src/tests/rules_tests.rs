@@ -0,0 +1,230 @@
+use crate::cli::{format_rule_entry, parse_args, Command};
+use crate::notifications::Notification;
+use crate::jsonl_log;
+use crate::rules::{execute, run_command, AutomationSettings, Firing, Rule, RuleEngine, RuleHost, RuleLogEntry};
+use crate::session::Session;
+use std::cell::RefCell;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+use super::cli_tests::session;
+
+fn automation(json: serde_json::Value) -> AutomationSettings {
+    serde_json::from_value(json).unwrap()
+}
+
+fn rule(json: serde_json::Value) -> Rule {
+    serde_json::from_value(json).unwrap()
+}
+
+#[derive(Default)]
+struct RecordingHost {
+    notified: RefCell<Vec<Notification>>,
+    focused: RefCell<Vec<String>>,
+}
+
+impl RuleHost for RecordingHost {
+    fn notify(&self, notification: &Notification) {
+        self.notified.borrow_mut().push(notification.clone());
+    }
+
+    fn focus(&self, session: &Session) -> Result<(), String> {
+        self.focused.borrow_mut().push(session.id.clone());
+        Ok(())
+    }
+}
+
+fn read_log(path: &Path) -> Vec<RuleLogEntry> {
+    jsonl_log::read(path)
+}
+
+fn wait_for(path: &Path) -> String {
+    for _ in 0..100 {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            if !content.is_empty() {
+                return content;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    panic!("{:?} was never written", path);
+}
+
+#[test]
+fn test_status_dwell_fires_once_per_episode() {
+    let settings = automation(serde_json::json!({
+        "rules": [{"name": "idle 2h", "when": {"status": ["idle"], "for_secs": 7200}, "then": [{"action": "kill"}]}]
+    }));
+    let mut engine = RuleEngine::new();
+    let start = Instant::now();
+    let idle = vec![session("a", 1, "idle")];
+    let hours = |h: u64| start + Duration::from_secs(h * 3600);
+
+    assert!(engine.evaluate(&idle, &settings, start).is_empty());
+    assert!(engine.evaluate(&idle, &settings, hours(1)).is_empty());
+
+    let firings = engine.evaluate(&idle, &settings, hours(2));
+    assert_eq!(firings.len(), 1);
+    assert_eq!(firings[0].rule.name, "idle 2h");
+    assert_eq!(firings[0].session.id, "a");
+    assert!(!firings[0].dry_run);
+
+    // Still matching: doesn't fire again
+    assert!(engine.evaluate(&idle, &settings, hours(3)).is_empty());
+
+    // Leaving Idle resets the dwell
+    engine.evaluate(&[session("a", 1, "thinking")], &settings, hours(3));
+    assert!(engine.evaluate(&idle, &settings, hours(4)).is_empty());
+    assert_eq!(engine.evaluate(&idle, &settings, hours(6)).len(), 1);
+}
+
+#[test]
+fn test_conditions() {
+    let mut low = session("low", 1, "thinking");
+    low.context_window_percent = Some(8.0);
+    let mut high = session("high", 2, "thinking");
+    high.context_window_percent = Some(60.0);
+    let unknown = session("unknown", 3, "thinking");
+
+    let settings = automation(serde_json::json!({
+        "rules": [{"name": "ctx", "when": {"context_below": 10}, "then": [{"action": "notify"}]}]
+    }));
+    let firings = RuleEngine::new().evaluate(&[low.clone(), high, unknown], &settings, Instant::now());
+    assert_eq!(firings.len(), 1);
+    assert_eq!(firings[0].session.id, "low");
+
+    let when = |json: serde_json::Value| rule(serde_json::json!({"name": "r", "when": json, "then": []})).when;
+    assert!(when(serde_json::json!({"project": "my-project", "branch": "main"})).matches(&low, Duration::ZERO));
+    assert!(when(serde_json::json!({"project": "/tmp/my-project"})).matches(&low, Duration::ZERO));
+    assert!(!when(serde_json::json!({"project": "other"})).matches(&low, Duration::ZERO));
+    assert!(!when(serde_json::json!({"agent_type": "opencode"})).matches(&low, Duration::ZERO));
+    assert!(when(serde_json::json!({"cpu_above": 0.5})).matches(&low, Duration::ZERO));
+    assert!(!when(serde_json::json!({"memory_above_mb": 1})).matches(&low, Duration::ZERO));
+    assert!(!when(serde_json::json!({"status": ["waiting", "idle"]})).matches(&low, Duration::ZERO));
+}
+
+#[test]
+fn test_misspelled_condition_skips_only_that_rule() {
+    let settings = crate::settings::parse(
+        r#"{"automation": {"rules": [
+            {"name": "typo", "when": {"stauts": ["idle"]}, "then": [{"action": "kill"}]},
+            {"name": "no actions"},
+            {"name": "ok", "when": {"status": ["waiting"]}, "then": [{"action": "focus"}]}
+        ]}}"#,
+    );
+    let names: Vec<_> = settings.automation.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["ok"]);
+
+    let settings = crate::settings::parse(
+        r#"{"automation": {"dry_run": true, "rules": [{"name": "r", "when": {"status": ["idle"]}, "then": [{"action": "kill"}]}]}}"#,
+    );
+    assert!(settings.automation.dry_run);
+    assert_eq!(settings.automation.rules.len(), 1);
+}
+
+#[test]
+fn test_rule_edits_reset_only_that_rule() {
+    let mut settings = automation(serde_json::json!({
+        "rules": [
+            {"name": "waiting", "when": {"status": ["waiting"]}, "then": [{"action": "focus"}]},
+            {"name": "any", "then": [{"action": "notify"}]}
+        ]
+    }));
+    let mut engine = RuleEngine::new();
+    let sessions = vec![session("a", 1, "waiting")];
+    let now = Instant::now();
+
+    assert_eq!(engine.evaluate(&sessions, &settings, now).len(), 2);
+    assert!(engine.evaluate(&sessions, &settings, now).is_empty());
+
+    settings.rules[0].dry_run = true;
+    let firings = engine.evaluate(&sessions, &settings, now);
+    assert_eq!(firings.len(), 1);
+    assert_eq!(firings[0].rule.name, "waiting");
+    assert!(firings[0].dry_run);
+
+    // Moving a rule doesn't make it fire again
+    settings.rules.reverse();
+    assert!(engine.evaluate(&sessions, &settings, now).is_empty());
+}
+
+#[test]
+fn test_dry_run_only_logs() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("rules.log");
+    let marker = dir.path().join("ran");
+    let host = RecordingHost::default();
+
+    let firing = Firing {
+        rule: rule(serde_json::json!({
+            "name": "everything",
+            "then": [
+                {"action": "notify", "message": "{project} on {branch}"},
+                {"action": "focus"},
+                {"action": "command", "command": format!("echo ran > {}", marker.display())}
+            ]
+        })),
+        session: session("a", 1, "waiting"),
+        dry_run: true,
+    };
+
+    let entries = execute(&firing, &host, Some(&log));
+    assert_eq!(entries.len(), 3);
+    assert!(entries.iter().all(|e| e.dry_run && e.error.is_none()));
+    assert!(host.notified.borrow().is_empty());
+    assert!(host.focused.borrow().is_empty());
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!marker.exists());
+    assert_eq!(read_log(&log), entries);
+
+    let firing = Firing { dry_run: false, ..firing };
+    let entries = execute(&firing, &host, Some(&log));
+    assert!(entries.iter().all(|e| !e.dry_run && e.error.is_none()));
+    assert_eq!(host.notified.borrow()[0].title, "everything");
+    assert_eq!(host.notified.borrow()[0].body, "my-project on main");
+    assert_eq!(*host.focused.borrow(), vec!["a".to_string()]);
+    wait_for(&marker);
+    assert_eq!(read_log(&log).len(), 6);
+}
+
+#[test]
+fn test_command_gets_session_env() {
+    let dir = tempdir().unwrap();
+    let out = dir.path().join("env");
+    let rule = rule(serde_json::json!({"name": "env", "then": []}));
+    let command = format!(
+        "echo \"$AGENT_SESSION_ID $AGENT_PID $AGENT_PROJECT $AGENT_BRANCH $AGENT_STATUS $AGENT_RULE\" > {}",
+        out.display()
+    );
+
+    run_command(&command, &rule, &session("abc", 42, "idle")).unwrap();
+    assert_eq!(wait_for(&out).trim(), "abc 42 my-project main idle env");
+}
+
+#[test]
+fn test_rules_cli() {
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    assert_eq!(parse_args(&args(&["rules"])).unwrap(), Command::Rules { json: false, limit: 20 });
+    assert_eq!(
+        parse_args(&args(&["rules", "--json", "--limit", "5"])).unwrap(),
+        Command::Rules { json: true, limit: 5 }
+    );
+    assert!(parse_args(&args(&["rules", "--limit", "x"])).is_err());
+
+    let entry = crate::rules::RuleLogEntry {
+        at: "not a time".to_string(),
+        rule: "idle 2h".to_string(),
+        session_id: "0123456789abcdef".to_string(),
+        project: "my-project".to_string(),
+        action: "kill".to_string(),
+        dry_run: true,
+        error: None,
+    };
+    assert_eq!(format_rule_entry(&entry), "not a time  idle 2h  would kill on 01234567  my-project");
+    let failed = crate::rules::RuleLogEntry { dry_run: false, error: Some("no such process".to_string()), ..entry };
+    assert_eq!(
+        format_rule_entry(&failed),
+        "not a time  idle 2h  failed (no such process): kill on 01234567  my-project"
+    );
+}
@@ -1,7 +1,9 @@
 use crate::session::{
     AgentType, SessionStatus, parse_session_file, convert_dir_name_to_path, convert_path_to_dir_name,
-    determine_status, status_sort_priority, has_tool_use, has_tool_result, is_local_slash_command,
-    is_interrupted_request, is_thinking_only, cleanup_stale_status_entries, get_sessions_internal
+    determine_status, determine_status_with_reason, status_sort_priority, has_tool_use, has_tool_result, is_local_slash_command,
+    is_interrupted_request, is_thinking_only, cleanup_stale_status_entries, get_sessions_internal, StatusThresholds,
+    SlashCommands, is_local_slash_command_with, slash_commands::discover_custom_commands,
+    get_sessions_in, profiles, profiles::config_dir_from_environ, cwd_index
 };
 use crate::agent::AgentProcess;
 use serde_json::json;
@@ -652,6 +654,10 @@ fn test_get_sessions_internal_process_without_cwd_is_skipped() {
         cpu_usage: 0.0,
         cwd: None,
         start_time: 0,
+        config_dir: None,
+        host: None,
+        memory: 0,
+        tree: Default::default(),
     }];
     let sessions = get_sessions_internal(&processes, AgentType::Claude);
     assert!(sessions.is_empty(), "Process without CWD should be skipped");
@@ -664,6 +670,10 @@ fn test_get_sessions_internal_process_with_nonexistent_project_is_skipped() {
         cpu_usage: 0.0,
         cwd: Some(std::path::PathBuf::from("/nonexistent/path/that/does/not/match/any/project")),
         start_time: 0,
+        config_dir: None,
+        host: None,
+        memory: 0,
+        tree: Default::default(),
     }];
     let sessions = get_sessions_internal(&processes, AgentType::Claude);
     assert!(sessions.is_empty(), "Process with non-matching CWD should produce no sessions");
@@ -748,3 +758,269 @@ fn test_parse_jsonl_thinking_then_text_is_not_thinking() {
     assert!(!matches!(session.status, SessionStatus::Thinking),
         "Expected non-Thinking when assistant has thinking+text, got {:?}", session.status);
 }
+
+/// (msg_type, has_tool_use, is_local_command, is_interrupted, file_age_secs, cpu_usage)
+type StatusCase<'a> = (Option<&'a str>, bool, bool, bool, Option<f32>, f32);
+
+#[test]
+fn test_determine_status_with_reason_matches_determine_status() {
+    let cases: &[StatusCase] = &[
+        (Some("assistant"), true, false, false, Some(1.0), 0.0),
+        (Some("assistant"), true, false, false, Some(20.0), 0.0),
+        (Some("assistant"), true, false, false, Some(20.0), 50.0),
+        (Some("assistant"), false, false, false, Some(1.0), 0.0),
+        (Some("assistant"), false, false, false, Some(20.0), 0.0),
+        (Some("user"), false, true, false, Some(20.0), 0.0),
+        (Some("user"), false, false, true, Some(20.0), 0.0),
+        (None, false, false, false, None, 0.0),
+    ];
+
+    for &(msg_type, tool_use, local_cmd, interrupted, age, cpu) in cases {
+        let (status, reason) = determine_status_with_reason(msg_type, tool_use, false, local_cmd, interrupted, age, cpu, &StatusThresholds::default());
+        assert_eq!(status, determine_status(msg_type, tool_use, false, local_cmd, interrupted, age, cpu));
+        assert!(!reason.is_empty());
+    }
+
+    let (_, reason) = determine_status_with_reason(Some("assistant"), true, false, false, false, Some(20.0), 0.0, &StatusThresholds::default());
+    assert!(reason.contains("20.0s"), "reason should cite the file age: {}", reason);
+}
+
+#[test]
+fn test_determine_status_with_reason_uses_thresholds() {
+    let thresholds = StatusThresholds {
+        tool_quiet_secs: 30.0,
+        cpu_active_percent: 60.0,
+        ..StatusThresholds::default()
+    };
+
+    // 20s after a tool_use: quiet by default, still running with a 30s window
+    let (status, _) = determine_status_with_reason(Some("assistant"), true, false, false, false, Some(20.0), 0.0, &StatusThresholds::default());
+    assert_eq!(status, SessionStatus::Waiting);
+    let (status, _) = determine_status_with_reason(Some("assistant"), true, false, false, false, Some(20.0), 0.0, &thresholds);
+    assert_eq!(status, SessionStatus::Processing);
+
+    // 50% CPU no longer counts as active with a 60% threshold
+    let (status, _) = determine_status_with_reason(Some("assistant"), true, false, false, false, Some(40.0), 50.0, &thresholds);
+    assert_eq!(status, SessionStatus::Waiting);
+}
+
+#[test]
+fn test_status_thresholds_validate() {
+    assert!(StatusThresholds::default().validate().is_ok());
+
+    let invalid = StatusThresholds {
+        text_quiet_secs: -1.0,
+        quiet_confirmations: 0,
+        ..StatusThresholds::default()
+    };
+    assert_eq!(invalid.validate().unwrap_err().len(), 2);
+}
+
+#[test]
+fn test_global_settings_parse_sections_independently() {
+    let settings = crate::settings::parse(r#"{
+        "chrome_profile": "Profile 1",
+        "status_thresholds": { "tool_quiet_secs": 12.5 }
+    }"#);
+    assert_eq!(settings.chrome_profile.as_deref(), Some("Profile 1"));
+    assert_eq!(settings.status_thresholds.tool_quiet_secs, 12.5);
+    assert_eq!(settings.status_thresholds.text_quiet_secs, 3.0);
+
+    // A malformed section falls back to defaults without losing the others
+    let settings = crate::settings::parse(r#"{
+        "chrome_profile": "Default",
+        "status_thresholds": { "quiet_confirmations": 0 }
+    }"#);
+    assert_eq!(settings.chrome_profile.as_deref(), Some("Default"));
+    assert_eq!(settings.status_thresholds.quiet_confirmations, 2);
+}
+
+#[test]
+fn test_project_threshold_overrides_apply_on_top_of_base() {
+    use crate::session::config::StatusThresholdOverrides;
+
+    let overrides: StatusThresholdOverrides =
+        serde_json::from_value(json!({ "toolQuietSecs": 20.0, "quietConfirmations": 3 })).unwrap();
+    let merged = overrides.apply_to(&StatusThresholds::default());
+
+    assert_eq!(merged.tool_quiet_secs, 20.0);
+    assert_eq!(merged.quiet_confirmations, 3);
+    assert_eq!(merged.text_quiet_secs, StatusThresholds::default().text_quiet_secs);
+}
+
+#[test]
+fn test_is_local_slash_command_newer_builtins() {
+    assert!(is_local_slash_command(&json!("/context")));
+    assert!(is_local_slash_command(&json!("<command-name>/agents</command-name>\n            <command-message>agents</command-message>\n            <command-args></command-args>")));
+    assert!(is_local_slash_command(&json!("/mcp")));
+}
+
+#[test]
+fn test_custom_command_shadowing_builtin_invokes_model() {
+    let commands = SlashCommands::from_lists(
+        vec!["/review".to_string(), "/clear".to_string()],
+        vec!["/review".to_string()],
+    );
+
+    assert!(is_local_slash_command_with(&json!("/clear"), &commands));
+    assert!(!is_local_slash_command_with(&json!("/review"), &commands));
+    assert!(!is_local_slash_command_with(&json!("<command-name>/review</command-name>\n            <command-message>review is running…</command-message>"), &commands));
+}
+
+#[test]
+fn test_discover_custom_commands() {
+    let dir = tempfile::tempdir().unwrap();
+    let commands_dir = dir.path().join("commands");
+    std::fs::create_dir_all(commands_dir.join("frontend")).unwrap();
+    std::fs::write(commands_dir.join("deploy.md"), "Deploy $ARGUMENTS").unwrap();
+    std::fs::write(commands_dir.join("frontend").join("build.md"), "Build it").unwrap();
+    std::fs::write(commands_dir.join("notes.txt"), "not a command").unwrap();
+
+    let found = discover_custom_commands(&commands_dir);
+    assert!(found.contains("/deploy"));
+    assert!(found.contains("/build"));
+    assert!(found.contains("/frontend:build"));
+    assert_eq!(found.len(), 3);
+
+    assert!(discover_custom_commands(&dir.path().join("missing")).is_empty());
+}
+
+#[test]
+fn test_project_custom_commands_override_local_list() {
+    let dir = tempfile::tempdir().unwrap();
+    let commands_dir = dir.path().join(".claude").join("commands");
+    std::fs::create_dir_all(&commands_dir).unwrap();
+    std::fs::write(commands_dir.join("init.md"), "Set up the repo the way we like it").unwrap();
+
+    let commands = SlashCommands::for_project(dir.path().to_str().unwrap());
+    assert!(!commands.is_local("/init"));
+    assert!(commands.is_local("/clear"));
+}
+
+// Tests for Claude config profiles (CLAUDE_CONFIG_DIR)
+
+fn write_profile_session(config_dir: &std::path::Path, project_path: &std::path::Path, session_id: &str) {
+    let project_dir = config_dir.join("projects").join(convert_path_to_dir_name(&project_path.to_string_lossy()));
+    std::fs::create_dir_all(&project_dir).unwrap();
+    let line = json!({
+        "sessionId": session_id,
+        "type": "assistant",
+        "timestamp": "2026-01-01T00:00:00Z",
+        "message": {"role": "assistant", "content": "Done."}
+    });
+    std::fs::write(project_dir.join(format!("{}.jsonl", session_id)), format!("{}\n", line)).unwrap();
+}
+
+#[test]
+fn test_get_sessions_in_scans_each_config_dir() {
+    let root = tempfile::tempdir().unwrap();
+    let default_dir = root.path().join(".claude");
+    let work_dir = root.path().join(".claude-work");
+    let project = root.path().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+
+    write_profile_session(&default_dir, &project, "personal-session");
+    write_profile_session(&work_dir, &project, "work-session");
+
+    let process = |pid, config_dir: Option<std::path::PathBuf>| AgentProcess {
+        pid,
+        cpu_usage: 0.0,
+        cwd: Some(project.clone()),
+        start_time: 0,
+        config_dir,
+        host: None,
+        memory: 0,
+        tree: Default::default(),
+    };
+    let processes = vec![process(424301, None), process(424302, Some(work_dir.clone()))];
+
+    let sessions = get_sessions_in(&processes, AgentType::Claude, &default_dir);
+    assert_eq!(sessions.len(), 2);
+
+    let personal = sessions.iter().find(|s| s.id == "personal-session").expect("default profile session");
+    assert_eq!(personal.profile.as_deref(), Some("default"));
+    assert_eq!(personal.pid, 424301);
+
+    let work = sessions.iter().find(|s| s.id == "work-session").expect("work profile session");
+    assert_eq!(work.profile.as_deref(), Some("claude-work"));
+    assert_eq!(work.pid, 424302);
+}
+
+#[test]
+fn test_config_dir_from_environ() {
+    let environ = ["PATH=/usr/bin", "CLAUDE_CONFIG_DIR=/tmp/claude-work", "HOME=/home/me"];
+    assert_eq!(config_dir_from_environ(&environ), Some(std::path::PathBuf::from("/tmp/claude-work")));
+
+    assert_eq!(config_dir_from_environ(&["PATH=/usr/bin"]), None);
+    assert_eq!(config_dir_from_environ(&["CLAUDE_CONFIG_DIR="]), None);
+}
+
+#[test]
+fn test_profile_for_names_profiles() {
+    let default_dir = std::path::Path::new("/tmp/profiles/.claude");
+    assert_eq!(profiles::profile_for(default_dir, default_dir).name, "default");
+
+    let work = profiles::profile_for(std::path::Path::new("/tmp/profiles/.claude-work"), default_dir);
+    assert_eq!(work.name, "claude-work");
+    assert_eq!(work.projects_dir(), std::path::PathBuf::from("/tmp/profiles/.claude-work/projects"));
+}
+
+// Tests for the transcript cwd index
+
+#[test]
+fn test_dir_name_matches_claude_encoding() {
+    assert!(cwd_index::dir_name_matches("/Users/me/Projects/my-app", "-Users-me-Projects-my-app"));
+    assert!(cwd_index::dir_name_matches("/Users/me/Projects/site.io", "-Users-me-Projects-site-io"));
+    assert!(cwd_index::dir_name_matches("/Users/me/repo/.worktrees/fix", "-Users-me-repo--worktrees-fix"));
+    assert!(!cwd_index::dir_name_matches("/Users/me/Projects/other", "-Users-me-Projects-my-app"));
+}
+
+#[test]
+fn test_cwd_index_resolves_deleted_and_dotted_paths() {
+    let root = tempfile::tempdir().unwrap();
+    // Neither path exists on disk, so the filesystem probe can't recover them
+    let project_path = "/nonexistent/agent-sessions/my.dotted-app";
+    let project_dir = root.path().join("-nonexistent-agent-sessions-my-dotted-app");
+    std::fs::create_dir_all(&project_dir).unwrap();
+
+    let lines = [
+        json!({"type": "summary", "summary": "Earlier work"}),
+        json!({"sessionId": "s1", "cwd": "/somewhere/else", "type": "user"}),
+        json!({"sessionId": "s1", "cwd": project_path, "type": "user"}),
+    ];
+    let content: String = lines.iter().map(|l| format!("{}\n", l)).collect();
+    std::fs::write(project_dir.join("s1.jsonl"), content).unwrap();
+
+    assert_eq!(cwd_index::project_path_for_dir(&project_dir).as_deref(), Some(project_path));
+    assert_ne!(convert_dir_name_to_path("-nonexistent-agent-sessions-my-dotted-app"), project_path);
+}
+
+#[test]
+fn test_cwd_index_without_cwd_returns_none() {
+    let root = tempfile::tempdir().unwrap();
+    let project_dir = root.path().join("-no-cwd-here");
+    std::fs::create_dir_all(&project_dir).unwrap();
+    std::fs::write(project_dir.join("s1.jsonl"), format!("{}\n", json!({"sessionId": "s1", "type": "user"}))).unwrap();
+
+    assert_eq!(cwd_index::project_path_for_dir(&project_dir), None);
+}
+
+#[test]
+fn test_cwd_index_finds_cwd_appended_after_first_lookup() {
+    use std::io::Write;
+
+    let root = tempfile::tempdir().unwrap();
+    let project_path = "/nonexistent/late-cwd";
+    let project_dir = root.path().join("-nonexistent-late-cwd");
+    std::fs::create_dir_all(&project_dir).unwrap();
+    let transcript = project_dir.join("s1.jsonl");
+    std::fs::write(&transcript, format!("{}\n", json!({"type": "summary", "summary": "Starting"}))).unwrap();
+
+    assert_eq!(cwd_index::project_path_for_dir(&project_dir), None);
+
+    // Appending doesn't touch the folder's mtime
+    let mut file = std::fs::OpenOptions::new().append(true).open(&transcript).unwrap();
+    writeln!(file, "{}", json!({"sessionId": "s1", "cwd": project_path, "type": "user"})).unwrap();
+
+    assert_eq!(cwd_index::project_path_for_dir(&project_dir).as_deref(), Some(project_path));
+}
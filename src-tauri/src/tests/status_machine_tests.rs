@@ -0,0 +1,163 @@
+use crate::session::{SessionStatus, StatusMachine, StatusMachineConfig, TransitionRule};
+use std::time::{Duration, Instant};
+
+/// Fake clock: a fixed origin advanced manually, so tests never sleep
+struct FakeClock {
+    now: Instant,
+}
+
+impl FakeClock {
+    fn new() -> Self {
+        FakeClock { now: Instant::now() }
+    }
+
+    fn advance(&mut self, secs: u64) -> Instant {
+        self.now += Duration::from_secs(secs);
+        self.now
+    }
+}
+
+#[test]
+fn test_status_machine_starts_in_initial_state() {
+    let clock = FakeClock::new();
+    let machine = StatusMachine::new(SessionStatus::Thinking, clock.now);
+
+    assert_eq!(*machine.current(), SessionStatus::Thinking);
+    assert_eq!(machine.entered_at(), clock.now);
+}
+
+#[test]
+fn test_status_machine_processing_to_waiting_needs_two_quiet_observations() {
+    let config = StatusMachineConfig::default();
+    let mut clock = FakeClock::new();
+    let mut machine = StatusMachine::new(SessionStatus::Processing, clock.now);
+
+    // First quiet poll is held back
+    let t = clock.advance(2);
+    assert!(machine.observe(SessionStatus::Waiting, t, &config).is_none());
+    assert_eq!(*machine.current(), SessionStatus::Processing);
+
+    // Second consecutive quiet poll commits the transition
+    let t = clock.advance(2);
+    let transition = machine.observe(SessionStatus::Waiting, t, &config).expect("transition");
+    assert_eq!(transition.from, SessionStatus::Processing);
+    assert_eq!(transition.to, SessionStatus::Waiting);
+    assert_eq!(transition.at, t);
+    assert_eq!(*machine.current(), SessionStatus::Waiting);
+    assert_eq!(machine.entered_at(), t);
+}
+
+#[test]
+fn test_status_machine_blip_resets_confirmation_count() {
+    let config = StatusMachineConfig::default();
+    let mut clock = FakeClock::new();
+    let mut machine = StatusMachine::new(SessionStatus::Processing, clock.now);
+
+    // Waiting, Processing, Waiting: never two in a row, so no flicker
+    assert!(machine.observe(SessionStatus::Waiting, clock.advance(2), &config).is_none());
+    assert!(machine.observe(SessionStatus::Processing, clock.advance(2), &config).is_none());
+    assert!(machine.observe(SessionStatus::Waiting, clock.advance(2), &config).is_none());
+    assert_eq!(*machine.current(), SessionStatus::Processing);
+}
+
+#[test]
+fn test_status_machine_switching_candidate_restarts_count() {
+    let config = StatusMachineConfig::default();
+    let mut clock = FakeClock::new();
+    let mut machine = StatusMachine::new(SessionStatus::Thinking, clock.now);
+
+    // Idle then Waiting are different candidates - neither is confirmed twice
+    assert!(machine.observe(SessionStatus::Idle, clock.advance(2), &config).is_none());
+    assert!(machine.observe(SessionStatus::Waiting, clock.advance(2), &config).is_none());
+    assert_eq!(*machine.current(), SessionStatus::Thinking);
+
+    assert!(machine.observe(SessionStatus::Waiting, clock.advance(2), &config).is_some());
+    assert_eq!(*machine.current(), SessionStatus::Waiting);
+}
+
+#[test]
+fn test_status_machine_becoming_active_is_immediate() {
+    let config = StatusMachineConfig::default();
+    let mut clock = FakeClock::new();
+    let mut machine = StatusMachine::new(SessionStatus::Idle, clock.now);
+
+    let transition = machine.observe(SessionStatus::Thinking, clock.advance(2), &config);
+    assert!(transition.is_some());
+    assert_eq!(*machine.current(), SessionStatus::Thinking);
+
+    let transition = machine.observe(SessionStatus::Compacting, clock.advance(2), &config);
+    assert!(transition.is_some());
+    assert_eq!(*machine.current(), SessionStatus::Compacting);
+}
+
+#[test]
+fn test_status_machine_same_status_is_not_a_transition() {
+    let config = StatusMachineConfig::default();
+    let mut clock = FakeClock::new();
+    let mut machine = StatusMachine::new(SessionStatus::Waiting, clock.now);
+
+    assert!(machine.observe(SessionStatus::Waiting, clock.advance(2), &config).is_none());
+    assert_eq!(machine.dwell(clock.now), Duration::from_secs(2));
+}
+
+#[test]
+fn test_status_machine_min_dwell_holds_state() {
+    let config = StatusMachineConfig {
+        rules: vec![TransitionRule {
+            from: Some(SessionStatus::Waiting),
+            to: None,
+            confirmations: 1,
+            min_dwell: Duration::from_secs(5),
+        }],
+    };
+    let mut clock = FakeClock::new();
+    let mut machine = StatusMachine::new(SessionStatus::Waiting, clock.now);
+
+    // Dwell not yet satisfied
+    assert!(machine.observe(SessionStatus::Processing, clock.advance(2), &config).is_none());
+    assert!(machine.observe(SessionStatus::Processing, clock.advance(2), &config).is_none());
+    assert_eq!(*machine.current(), SessionStatus::Waiting);
+
+    // 6s in Waiting: the rule is satisfied
+    assert!(machine.observe(SessionStatus::Processing, clock.advance(2), &config).is_some());
+    assert_eq!(*machine.current(), SessionStatus::Processing);
+}
+
+#[test]
+fn test_status_machine_first_matching_rule_wins() {
+    let config = StatusMachineConfig {
+        rules: vec![
+            TransitionRule {
+                from: Some(SessionStatus::Processing),
+                to: Some(SessionStatus::Idle),
+                confirmations: 3,
+                min_dwell: Duration::ZERO,
+            },
+            TransitionRule {
+                from: None,
+                to: None,
+                confirmations: 1,
+                min_dwell: Duration::ZERO,
+            },
+        ],
+    };
+    let mut clock = FakeClock::new();
+    let mut machine = StatusMachine::new(SessionStatus::Processing, clock.now);
+
+    assert!(machine.observe(SessionStatus::Idle, clock.advance(2), &config).is_none());
+    assert!(machine.observe(SessionStatus::Idle, clock.advance(2), &config).is_none());
+    assert!(machine.observe(SessionStatus::Idle, clock.advance(2), &config).is_some());
+
+    // Catch-all rule: immediate
+    assert!(machine.observe(SessionStatus::Thinking, clock.advance(2), &config).is_some());
+}
+
+#[test]
+fn test_status_machine_empty_config_is_passthrough() {
+    let config = StatusMachineConfig { rules: Vec::new() };
+    let mut clock = FakeClock::new();
+    let mut machine = StatusMachine::new(SessionStatus::Processing, clock.now);
+
+    assert!(machine.observe(SessionStatus::Waiting, clock.advance(1), &config).is_some());
+    assert_eq!(*machine.current(), SessionStatus::Waiting);
+}
@@ -0,0 +1,94 @@
+use crate::cli::{format_tmux_pane, format_tmux_status, parse_args, Command, DEFAULT_TMUX_PANE_FORMAT, DEFAULT_TMUX_STATUS_FORMAT};
+use crate::terminal::{parse_ps_ttys, same_tty, session_on_tty};
+use chrono::{DateTime, Utc};
+
+use super::cli_tests::{args, session};
+
+fn now() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2026-01-01T00:12:30Z").unwrap().with_timezone(&Utc)
+}
+
+#[test]
+fn test_parse_tmux_commands() {
+    assert_eq!(
+        parse_args(&args(&["tmux", "status"])).unwrap(),
+        Command::TmuxStatus { format: DEFAULT_TMUX_STATUS_FORMAT.to_string() }
+    );
+    assert_eq!(
+        parse_args(&args(&["tmux", "status", "--format", "{waiting}/{total}"])).unwrap(),
+        Command::TmuxStatus { format: "{waiting}/{total}".to_string() }
+    );
+    assert_eq!(
+        parse_args(&args(&["tmux", "pane", "/dev/ttys003"])).unwrap(),
+        Command::TmuxPane {
+            tty: "/dev/ttys003".to_string(),
+            format: DEFAULT_TMUX_PANE_FORMAT.to_string()
+        }
+    );
+
+    assert!(parse_args(&args(&["tmux"])).is_err());
+    assert!(parse_args(&args(&["tmux", "pane"])).is_err());
+    assert!(parse_args(&args(&["tmux", "status", "extra"])).is_err());
+    assert!(parse_args(&args(&["tmux", "window"])).unwrap_err().contains("unknown tmux command"));
+    assert!(parse_args(&args(&["tmux", "status", "--format"])).is_err());
+}
+
+#[test]
+fn test_tmux_status_line() {
+    let sessions = vec![
+        session("a", 1, "waiting"),
+        session("b", 2, "waiting"),
+        session("c", 3, "thinking"),
+        session("d", 4, "processing"),
+        session("e", 5, "compacting"),
+        session("f", 6, "idle"),
+    ];
+
+    assert_eq!(format_tmux_status(&sessions, DEFAULT_TMUX_STATUS_FORMAT, now()), "⏳2 ⚙3 ✓1");
+    assert_eq!(format_tmux_status(&sessions[2..], DEFAULT_TMUX_STATUS_FORMAT, now()), "⚙3 ✓1");
+    assert_eq!(format_tmux_status(&[], DEFAULT_TMUX_STATUS_FORMAT, now()), "");
+
+    // Waiting time counts from the last activity
+    assert_eq!(format_tmux_status(&sessions, "[{waiting} for {longest_wait}]", now()), "2 for 12m");
+}
+
+#[test]
+fn test_same_tty() {
+    assert!(same_tty("/dev/ttys003", "ttys003"));
+    assert!(same_tty("/dev/pts/3", "pts/3"));
+    assert!(!same_tty("/dev/pts/31", "pts/3"));
+    assert!(!same_tty("", ""));
+}
+
+#[test]
+fn test_session_on_tty() {
+    let sessions = vec![session("a", 1, "waiting"), session("b", 2, "idle")];
+    let tty_of = |pid: u32| match pid {
+        1 => Some("pts/1".to_string()),
+        2 => Some("pts/2".to_string()),
+        _ => None,
+    };
+
+    assert_eq!(session_on_tty(&sessions, "/dev/pts/2", tty_of).unwrap().id, "b");
+    assert!(session_on_tty(&sessions, "/dev/pts/12", tty_of).is_none());
+}
+
+#[test]
+fn test_parse_ps_ttys() {
+    let ttys = parse_ps_ttys("  101 ttys003\n  102 ??\n  103 pts/4\n  104 ?\nnonsense\n");
+    assert_eq!(ttys.len(), 2);
+    assert_eq!(ttys.get(&101).map(String::as_str), Some("ttys003"));
+    assert_eq!(ttys.get(&103).map(String::as_str), Some("pts/4"));
+}
+
+#[test]
+fn test_tmux_pane_line_stays_on_one_line() {
+    let mut waiting = session("a", 1, "waiting");
+    waiting.last_message = Some("Should I\n\n  run the   migration?\n".to_string());
+
+    assert_eq!(format_tmux_pane(&waiting, DEFAULT_TMUX_PANE_FORMAT), "waiting");
+    assert_eq!(
+        format_tmux_pane(&waiting, "{status}: {message}"),
+        "waiting: Should I run the migration?"
+    );
+}
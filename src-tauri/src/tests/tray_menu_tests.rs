@@ -0,0 +1,94 @@
+use crate::session::{SessionStatus, StatusDwell};
+use crate::tray_menu::{format_dwell, menu_model, next_waiting, session_label, TrayAction, JUMP_TO_WAITING_ID};
+use std::time::{Duration, Instant};
+
+use super::cli_tests::session;
+
+#[test]
+fn test_menu_ids_round_trip() {
+    let actions = [
+        TrayAction::JumpToWaiting,
+        TrayAction::Focus("abc".to_string()),
+        TrayAction::Kill("abc".to_string()),
+        TrayAction::OpenPr("abc".to_string()),
+        TrayAction::OpenGithub("a:b".to_string()),
+    ];
+    for action in actions {
+        assert_eq!(TrayAction::from_menu_id(&action.menu_id()), Some(action));
+    }
+
+    assert_eq!(TrayAction::JumpToWaiting.menu_id(), JUMP_TO_WAITING_ID);
+    assert_eq!(TrayAction::Kill("abc".to_string()).menu_id(), "session:kill:abc");
+    assert_eq!(TrayAction::from_menu_id("show"), None);
+    assert_eq!(TrayAction::from_menu_id("quit"), None);
+    assert_eq!(TrayAction::from_menu_id("session:explode:abc"), None);
+}
+
+#[test]
+fn test_format_dwell() {
+    assert_eq!(format_dwell(Duration::from_secs(59)), "<1m");
+    assert_eq!(format_dwell(Duration::from_secs(12 * 60 + 30)), "12m");
+    assert_eq!(format_dwell(Duration::from_secs(3 * 3600 + 5 * 60)), "3h05m");
+}
+
+#[test]
+fn test_session_label() {
+    let mut s = session("a", 1, "waiting");
+    assert_eq!(session_label(&s, Duration::from_secs(12 * 60)), "● my-project (main) · 12m");
+
+    s.status = SessionStatus::Idle;
+    s.git_branch = None;
+    s.project_name = "a-project-name-far-too-long-for-a-menu".to_string();
+    assert_eq!(session_label(&s, Duration::ZERO), "○ a-project-name-far-too-long… · <1m");
+}
+
+#[test]
+fn test_next_waiting_wraps_around() {
+    let sessions = vec![
+        session("a", 1, "waiting"),
+        session("b", 2, "thinking"),
+        session("c", 3, "waiting"),
+    ];
+
+    assert_eq!(next_waiting(&sessions, None).unwrap().id, "a");
+    assert_eq!(next_waiting(&sessions, Some("a")).unwrap().id, "c");
+    assert_eq!(next_waiting(&sessions, Some("c")).unwrap().id, "a");
+    // Last jump no longer waiting: start over
+    assert_eq!(next_waiting(&sessions, Some("b")).unwrap().id, "a");
+    assert!(next_waiting(&sessions[1..2], None).is_none());
+}
+
+#[test]
+fn test_next_waiting_starts_with_the_longest_waiting() {
+    let mut sessions = vec![session("a", 1, "waiting"), session("b", 2, "waiting")];
+    sessions[0].last_activity_at = "2026-01-01T00:10:00Z".to_string();
+    sessions[1].last_activity_at = "2026-01-01T00:05:00.500Z".to_string();
+
+    assert_eq!(next_waiting(&sessions, None).unwrap().id, "b");
+    assert_eq!(next_waiting(&sessions, Some("b")).unwrap().id, "a");
+}
+
+#[test]
+fn test_menu_model_tracks_dwell_and_links() {
+    let start = Instant::now();
+    let mut sessions = vec![session("a", 1, "waiting"), session("b", 2, "thinking")];
+    sessions[1].github_url = Some("https://github.com/me/my-project".to_string());
+
+    let mut dwell = StatusDwell::new();
+    dwell.update(&sessions, start);
+    let model = menu_model(&sessions, &dwell, start + Duration::from_secs(5 * 60));
+
+    assert_eq!(model.waiting_count, 1);
+    assert_eq!(model.sessions[0].label, "● my-project (main) · 5m");
+    assert_eq!(model.sessions[0].github_url, None);
+    assert_eq!(model.sessions[1].github_url.as_deref(), Some("https://github.com/me/my-project"));
+    assert_eq!(model.sessions[1].pr_url, None);
+
+    // A status change restarts the clock, and the model differs
+    sessions[1].status = SessionStatus::Waiting;
+    dwell.update(&sessions, start + Duration::from_secs(5 * 60));
+    let later = menu_model(&sessions, &dwell, start + Duration::from_secs(6 * 60));
+    assert_eq!(later.waiting_count, 2);
+    assert_eq!(later.sessions[1].label, "● my-project (main) · 1m");
+    assert_ne!(later, model);
+}
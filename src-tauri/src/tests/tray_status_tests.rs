@@ -0,0 +1,88 @@
+use crate::tray_status::{render_title, TrayIcon, TraySettings, TraySummary, DEFAULT_TITLE};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+use super::cli_tests::session;
+
+fn summary(total: usize, waiting: usize) -> TraySummary {
+    TraySummary {
+        total,
+        waiting,
+        ..TraySummary::default()
+    }
+}
+
+#[test]
+fn test_default_title_matches_the_old_format() {
+    assert_eq!(render_title(DEFAULT_TITLE, &summary(0, 0)), "");
+    assert_eq!(render_title(DEFAULT_TITLE, &summary(3, 0)), "3");
+    assert_eq!(render_title(DEFAULT_TITLE, &summary(3, 1)), "3 (1 waiting)");
+}
+
+#[test]
+fn test_render_title_placeholders() {
+    let s = TraySummary {
+        total: 4,
+        waiting: 1,
+        thinking: 1,
+        processing: 1,
+        compacting: 1,
+        longest_wait: Some(Duration::from_secs(12 * 60)),
+        lowest_context: Some(18.4),
+        ..TraySummary::default()
+    };
+
+    assert_eq!(
+        render_title("{waiting}/{total} · {working} busy · {processing} tool", &s),
+        "1/4 · 3 busy · 1 tool"
+    );
+    assert_eq!(render_title("{longest_wait} | {lowest_context}", &s), "12m | 18%");
+    assert_eq!(render_title("[⏳{longest_wait}][ ⚠{failing}]", &s), "⏳12m");
+    // Unknown placeholders and stray brackets stay as written
+    assert_eq!(render_title("{nope} {total} [{total}", &s), "{nope} 4 [4");
+}
+
+#[test]
+fn test_summary_from_sessions() {
+    let mut sessions = vec![
+        session("a", 1, "waiting"),
+        session("b", 2, "waiting"),
+        session("c", 3, "thinking"),
+        session("d", 4, "paused"),
+    ];
+    sessions[2].context_window_percent = Some(42.0);
+    sessions[3].context_window_percent = Some(7.5);
+
+    sessions[1].last_activity_at = "2026-01-01T00:02:00Z".to_string();
+
+    // Waiting time counts from each waiting session's last activity
+    let now = DateTime::parse_from_rfc3339("2026-01-01T00:05:00Z").unwrap().with_timezone(&Utc);
+    let s = TraySummary::new(&sessions, now);
+    assert_eq!((s.total, s.waiting, s.thinking, s.paused), (4, 2, 1, 1));
+    assert_eq!(s.longest_wait, Some(Duration::from_secs(300)));
+    assert_eq!(s.lowest_context, Some(7.5));
+    assert_eq!(render_title("{lowest_context}", &s), "8%");
+}
+
+#[test]
+fn test_icon_for_summary() {
+    assert_eq!(TrayIcon::for_summary(&summary(0, 0)), TrayIcon::Idle);
+    assert_eq!(TrayIcon::for_summary(&summary(2, 0)), TrayIcon::Idle);
+
+    let working = TraySummary { compacting: 1, ..summary(2, 0) };
+    assert_eq!(TrayIcon::for_summary(&working), TrayIcon::Working);
+
+    let failing = TraySummary { failing: 1, ..working.clone() };
+    assert_eq!(TrayIcon::for_summary(&failing), TrayIcon::Error);
+
+    let waiting = TraySummary { waiting: 1, ..failing };
+    assert_eq!(TrayIcon::for_summary(&waiting), TrayIcon::Attention);
+}
+
+#[test]
+fn test_tray_settings_defaults() {
+    let settings: TraySettings = serde_json::from_value(serde_json::json!({"title": "{waiting}"})).unwrap();
+    assert_eq!(settings.title, "{waiting}");
+    assert!(settings.status_icon);
+    assert_eq!(TraySettings::default().title, DEFAULT_TITLE);
+}
@@ -0,0 +1,181 @@
+use crate::api::{read_request, Request, Response};
+use crate::session::{SessionEvent, SessionStatus};
+use crate::jsonl_log;
+use crate::webhooks::{deliver, dispatch, DeliveryAttempt, Webhook, WebhookFormat, WebhookSettings};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use tempfile::tempdir;
+
+use super::cli_tests::session;
+
+/// A local HTTP stand-in answering with `statuses` in turn, passing each
+/// request it receives back to the test
+fn stand_in(statuses: Vec<u16>) -> (String, Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for (stream, status) in listener.incoming().zip(statuses) {
+            let stream = stream.unwrap();
+            let request = read_request(&stream).unwrap();
+            let _ = tx.send(request);
+            let _ = Response::json(status, "{}".to_string()).write_to(&stream);
+        }
+    });
+    (url, rx)
+}
+
+fn read_log(path: &std::path::Path) -> Vec<DeliveryAttempt> {
+    jsonl_log::read(path)
+}
+
+fn hook(url: &str, format: &str) -> Webhook {
+    serde_json::from_value(serde_json::json!({ "name": "test", "url": url, "format": format })).unwrap()
+}
+
+fn fast_retries(max_attempts: u32) -> WebhookSettings {
+    WebhookSettings {
+        hooks: Vec::new(),
+        max_attempts,
+        initial_backoff_ms: 10,
+    }
+}
+
+#[test]
+fn test_payload_formats() {
+    let mut waiting = session("s1", 1, "waiting");
+    waiting.last_message = Some("Allow `rm -rf target`?".to_string());
+    let from = SessionStatus::Processing;
+
+    let slack: serde_json::Value = serde_json::from_str(&hook("http://x", "slack").payload(&from, &waiting)).unwrap();
+    assert_eq!(slack, serde_json::json!({ "text": "my-project needs your input: Allow `rm -rf target`?" }));
+
+    let discord: serde_json::Value = serde_json::from_str(&hook("http://x", "discord").payload(&from, &waiting)).unwrap();
+    assert_eq!(discord["content"], "my-project needs your input: Allow `rm -rf target`?");
+
+    let json: serde_json::Value = serde_json::from_str(&hook("http://x", "json").payload(&from, &waiting)).unwrap();
+    assert_eq!(json["event"], "changed");
+    assert_eq!(json["from"], "processing");
+    assert_eq!(json["to"], "waiting");
+    assert_eq!(json["session"]["id"], "s1");
+    assert!(json["timestamp"].is_string());
+
+    let rule: serde_json::Value = serde_json::from_str(&hook("http://x", "json").rule_payload("blocked 10m", &waiting)).unwrap();
+    assert_eq!(rule["event"], "rule");
+    assert_eq!(rule["rule"], "blocked 10m");
+    assert_eq!(rule["status"], "waiting");
+    assert_eq!(rule["text"], "blocked 10m: my-project needs your input");
+
+    let mut templated = hook("http://x", "slack");
+    templated.template = Some("{project}@{branch}: {from} -> {status} ({session_id})".to_string());
+    let text: serde_json::Value = serde_json::from_str(&templated.payload(&from, &waiting)).unwrap();
+    assert_eq!(text["text"], "my-project@main: processing -> waiting (s1)");
+}
+
+#[test]
+fn test_hook_matching() {
+    let mut hook = hook("https://hooks.example.com/T000/secret", "json");
+    assert_eq!(hook.format, WebhookFormat::Json);
+    assert_eq!(hook.label(), "test");
+    hook.name = None;
+    assert_eq!(hook.label(), "hooks.example.com");
+
+    // Default triggers: any -> waiting, active -> idle
+    assert!(hook.matches(&SessionStatus::Thinking, &session("s", 1, "waiting")));
+    assert!(hook.matches(&SessionStatus::Processing, &session("s", 1, "idle")));
+    assert!(!hook.matches(&SessionStatus::Waiting, &session("s", 1, "idle")));
+    assert!(!hook.matches(&SessionStatus::Waiting, &session("s", 1, "thinking")));
+
+    hook.projects = vec!["other-project".to_string()];
+    assert!(!hook.matches(&SessionStatus::Thinking, &session("s", 1, "waiting")));
+    hook.projects.push("/tmp/my-project".to_string());
+    assert!(hook.matches(&SessionStatus::Thinking, &session("s", 1, "waiting")));
+}
+
+#[test]
+fn test_deliver_retries_server_errors_and_logs_attempts() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("webhooks.log");
+    let (url, requests) = stand_in(vec![503, 500, 200]);
+    let mut hook = hook(&url, "slack");
+    hook.headers.insert("X-Token".to_string(), "abc".to_string());
+
+    let status = deliver(&hook, r#"{"text":"hi"}"#, "s1", "thinking -> waiting", &fast_retries(4), Some(&log));
+    assert_eq!(status, Ok(200));
+
+    let received: Vec<Request> = requests.try_iter().collect();
+    assert_eq!(received.len(), 3);
+    assert_eq!(received[0].method, "POST");
+    assert_eq!(received[0].path, "/hook");
+    assert_eq!(received[0].body, r#"{"text":"hi"}"#);
+    assert_eq!(received[0].header("x-token"), Some("abc"));
+    assert_eq!(received[0].header("content-type"), Some("application/json"));
+
+    let attempts = read_log(&log);
+    assert_eq!(attempts.len(), 3);
+    assert_eq!(attempts[0].status, Some(503));
+    assert!(!attempts[0].delivered);
+    assert_eq!(attempts[2].attempt, 3);
+    assert_eq!(attempts[2].status, Some(200));
+    assert!(attempts[2].delivered);
+    assert_eq!(attempts[2].hook, "test");
+    assert_eq!(attempts[2].transition, "thinking -> waiting");
+}
+
+#[test]
+fn test_deliver_gives_up() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("webhooks.log");
+
+    // Client errors aren't retried
+    let (url, _requests) = stand_in(vec![404, 200]);
+    assert_eq!(deliver(&hook(&url, "json"), "{}", "s1", "", &fast_retries(4), Some(&log)), Err("HTTP 404".to_string()));
+    assert_eq!(read_log(&log).len(), 1);
+
+    // Out of attempts
+    let (url, _requests) = stand_in(vec![500, 500, 200]);
+    assert!(deliver(&hook(&url, "json"), "{}", "s1", "", &fast_retries(2), Some(&log)).is_err());
+    assert_eq!(read_log(&log).len(), 3);
+
+    // Nothing listening
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let result = deliver(&hook(&format!("http://{}/", closed), "json"), "{}", "s1", "", &fast_retries(2), Some(&log));
+    assert!(result.is_err());
+    let attempts = read_log(&log);
+    assert_eq!(attempts.len(), 5);
+    assert_eq!(attempts[4].status, None);
+    assert!(attempts[4].error.is_some());
+}
+
+#[test]
+fn test_dispatch_posts_matching_hooks() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("webhooks.log");
+    let (slack_url, slack_requests) = stand_in(vec![200]);
+    let (discord_url, discord_requests) = stand_in(vec![200]);
+
+    let mut settings = fast_retries(1);
+    settings.hooks = vec![hook(&slack_url, "slack"), hook(&discord_url, "discord")];
+    settings.hooks[1].projects = vec!["other-project".to_string()];
+
+    let event = SessionEvent::Changed {
+        from: SessionStatus::Thinking,
+        session: Box::new(session("s1", 1, "idle")),
+    };
+    for handle in dispatch(&event, &settings, Some(log.clone())) {
+        handle.join().unwrap();
+    }
+
+    let slack: serde_json::Value = serde_json::from_str(&slack_requests.recv().unwrap().body).unwrap();
+    assert_eq!(slack["text"], "my-project finished");
+    assert!(discord_requests.try_recv().is_err());
+    assert_eq!(read_log(&log).len(), 1);
+
+    // Only status changes are posted
+    let added = SessionEvent::Added {
+        session: Box::new(session("s2", 2, "waiting")),
+    };
+    assert!(dispatch(&added, &settings, Some(log)).is_empty());
+}
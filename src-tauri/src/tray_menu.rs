@@ -0,0 +1,140 @@
+//! Contents of the tray menu: one submenu per session with quick actions,
+//! and an entry jumping between waiting sessions. Only describes the menu;
+//! `commands::tray` builds it with Tauri and handles clicks.
+
+use chrono::DateTime;
+use std::time::{Duration, Instant};
+
+use crate::session::{Session, SessionStatus, StatusDwell};
+
+/// Menu id of "Jump to next waiting session"
+pub const JUMP_TO_WAITING_ID: &str = "jump-waiting";
+
+/// Prefix of per-session menu ids: `session:<action>:<session id>`
+const SESSION_ID_PREFIX: &str = "session:";
+
+/// Longest project name shown in a menu item
+const MAX_PROJECT_CHARS: usize = 28;
+
+/// A click on a tray menu item that acts on sessions
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrayAction {
+    JumpToWaiting,
+    Focus(String),
+    Kill(String),
+    OpenPr(String),
+    OpenGithub(String),
+}
+
+impl TrayAction {
+    /// The menu item id carrying this action
+    pub fn menu_id(&self) -> String {
+        let (action, id) = match self {
+            TrayAction::JumpToWaiting => return JUMP_TO_WAITING_ID.to_string(),
+            TrayAction::Focus(id) => ("focus", id),
+            TrayAction::Kill(id) => ("kill", id),
+            TrayAction::OpenPr(id) => ("open-pr", id),
+            TrayAction::OpenGithub(id) => ("open-github", id),
+        };
+        format!("{}{}:{}", SESSION_ID_PREFIX, action, id)
+    }
+
+    /// The action behind a menu item id, if it is one of ours
+    pub fn from_menu_id(menu_id: &str) -> Option<Self> {
+        if menu_id == JUMP_TO_WAITING_ID {
+            return Some(TrayAction::JumpToWaiting);
+        }
+        let (action, id) = menu_id.strip_prefix(SESSION_ID_PREFIX)?.split_once(':')?;
+        let id = id.to_string();
+        match action {
+            "focus" => Some(TrayAction::Focus(id)),
+            "kill" => Some(TrayAction::Kill(id)),
+            "open-pr" => Some(TrayAction::OpenPr(id)),
+            "open-github" => Some(TrayAction::OpenGithub(id)),
+            _ => None,
+        }
+    }
+}
+
+/// One session's submenu
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraySessionItem {
+    pub session_id: String,
+    /// e.g. "● my-app (main) · 12m"
+    pub label: String,
+    pub pr_url: Option<String>,
+    pub github_url: Option<String>,
+}
+
+/// Everything the session part of the tray menu shows. Compared between
+/// scans so the menu is only rebuilt when it would look different.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrayMenuModel {
+    pub waiting_count: usize,
+    pub sessions: Vec<TraySessionItem>,
+}
+
+/// The menu for `sessions`, in the order the app lists them
+pub fn menu_model(sessions: &[Session], dwell: &StatusDwell, now: Instant) -> TrayMenuModel {
+    TrayMenuModel {
+        waiting_count: sessions.iter().filter(|s| s.status == SessionStatus::Waiting).count(),
+        sessions: sessions
+            .iter()
+            .map(|session| TraySessionItem {
+                session_id: session.id.clone(),
+                label: session_label(session, dwell.in_status(&session.id, now)),
+                pr_url: session.pr_info.as_ref().map(|pr| pr.url.clone()),
+                github_url: session.github_url.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Status glyph, project, branch and time in status
+pub fn session_label(session: &Session, in_status: Duration) -> String {
+    let project = if session.project_name.chars().count() > MAX_PROJECT_CHARS {
+        session.project_name.chars().take(MAX_PROJECT_CHARS - 1).collect::<String>() + "…"
+    } else {
+        session.project_name.clone()
+    };
+    let branch = session
+        .git_branch
+        .as_ref()
+        .map(|b| format!(" ({})", b))
+        .unwrap_or_default();
+
+    format!("{} {}{} · {}", status_glyph(&session.status), project, branch, format_dwell(in_status))
+}
+
+pub fn status_glyph(status: &SessionStatus) -> &'static str {
+    match status {
+        SessionStatus::Waiting => "●",
+        SessionStatus::Thinking | SessionStatus::Processing => "◐",
+        SessionStatus::Compacting => "◒",
+        SessionStatus::Idle => "○",
+        SessionStatus::Paused => "‖",
+    }
+}
+
+/// Minute resolution, so labels change at most once a minute
+pub fn format_dwell(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    match minutes {
+        0 => "<1m".to_string(),
+        m if m < 60 => format!("{}m", m),
+        m => format!("{}h{:02}m", m / 60, m % 60),
+    }
+}
+
+/// The waiting session after `after`, longest-waiting first and wrapping
+/// around; the first one when `after` is unset or no longer waiting
+pub fn next_waiting<'a>(sessions: &'a [Session], after: Option<&str>) -> Option<&'a Session> {
+    let mut waiting: Vec<&Session> = sessions.iter().filter(|s| s.status == SessionStatus::Waiting).collect();
+    // A waiting session's last activity is when it started waiting
+    waiting.sort_by_key(|s| DateTime::parse_from_rfc3339(&s.last_activity_at).ok());
+    let next = after
+        .and_then(|id| waiting.iter().position(|s| s.id == id))
+        .map(|i| (i + 1) % waiting.len())
+        .unwrap_or(0);
+    waiting.get(next).copied()
+}
@@ -0,0 +1,206 @@
+//! What the tray shows at a glance: a title rendered from a template and an
+//! icon variant for the overall state. `commands::tray` applies both after
+//! every scan.
+//!
+//! Configured under `tray` in the global config:
+//!
+//! ```json
+//! "tray": {"title": "{waiting}/{total}[ · {longest_wait}]", "status_icon": true}
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::session::{CiStatus, Session, SessionStatus};
+use crate::tray_menu::format_dwell;
+
+/// "3 (1 waiting)", "3" or nothing, as the title has always read
+pub const DEFAULT_TITLE: &str = "[{total}][ ({waiting} waiting)]";
+
+/// The `tray` section of the global config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraySettings {
+    /// Title template; see `render_title`
+    pub title: String,
+    /// Switch the icon with the overall state instead of always showing the
+    /// plain one
+    pub status_icon: bool,
+}
+
+impl Default for TraySettings {
+    fn default() -> Self {
+        TraySettings {
+            title: DEFAULT_TITLE.to_string(),
+            status_icon: true,
+        }
+    }
+}
+
+/// Counts and extremes over all sessions, filling the title placeholders
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TraySummary {
+    pub total: usize,
+    pub waiting: usize,
+    pub thinking: usize,
+    pub processing: usize,
+    pub compacting: usize,
+    pub idle: usize,
+    pub paused: usize,
+    /// Sessions whose pull request checks are failing
+    pub failing: usize,
+    /// Time the longest-waiting session has been waiting, counted from its
+    /// last activity so it reads the same in the tray and the CLI
+    pub longest_wait: Option<Duration>,
+    /// Least context window left across sessions, in percent
+    pub lowest_context: Option<f32>,
+}
+
+impl TraySummary {
+    pub fn new(sessions: &[Session], now: DateTime<Utc>) -> Self {
+        let count = |status: SessionStatus| sessions.iter().filter(|s| s.status == status).count();
+        TraySummary {
+            total: sessions.len(),
+            waiting: count(SessionStatus::Waiting),
+            thinking: count(SessionStatus::Thinking),
+            processing: count(SessionStatus::Processing),
+            compacting: count(SessionStatus::Compacting),
+            idle: count(SessionStatus::Idle),
+            paused: count(SessionStatus::Paused),
+            failing: sessions
+                .iter()
+                .filter(|s| {
+                    s.pr_info
+                        .as_ref()
+                        .is_some_and(|pr| pr.ci_status == Some(CiStatus::Failure))
+                })
+                .count(),
+            longest_wait: sessions
+                .iter()
+                .filter(|s| s.status == SessionStatus::Waiting)
+                .filter_map(|s| DateTime::parse_from_rfc3339(&s.last_activity_at).ok())
+                .filter_map(|at| (now - at.with_timezone(&Utc)).to_std().ok())
+                .max(),
+            lowest_context: sessions
+                .iter()
+                .filter_map(|s| s.context_window_percent)
+                .min_by(|a, b| a.total_cmp(b)),
+        }
+    }
+
+    /// Sessions thinking, running a tool or compacting
+    pub fn working(&self) -> usize {
+        self.thinking + self.processing + self.compacting
+    }
+
+    /// Value of a title placeholder; `None` for names that aren't one
+    pub fn placeholder(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "total" => self.total.to_string(),
+            "waiting" => self.waiting.to_string(),
+            "thinking" => self.thinking.to_string(),
+            "processing" => self.processing.to_string(),
+            "compacting" => self.compacting.to_string(),
+            "working" => self.working().to_string(),
+            "idle" => self.idle.to_string(),
+            "paused" => self.paused.to_string(),
+            "failing" => self.failing.to_string(),
+            "longest_wait" => self.longest_wait.map(format_dwell).unwrap_or_default(),
+            "lowest_context" => self.lowest_context.map(|p| format!("{:.0}%", p)).unwrap_or_default(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Fill the placeholders in `template`: `{total}`, `{waiting}`,
+/// `{thinking}`, `{processing}`, `{compacting}`, `{working}`, `{idle}`,
+/// `{paused}`, `{failing}`, `{longest_wait}` (e.g. "12m") and
+/// `{lowest_context}` (e.g. "18%"). A part in square brackets is left out
+/// when any placeholder in it is 0 or empty, so `[ ({waiting} waiting)]`
+/// only shows while something waits. Unknown placeholders are kept as written.
+pub fn render_title(template: &str, summary: &TraySummary) -> String {
+    let mut title = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('[') {
+        title.push_str(&render_part(&rest[..start], summary).0);
+        let after = &rest[start + 1..];
+        match after.find(']') {
+            Some(end) => {
+                let (part, all_set) = render_part(&after[..end], summary);
+                if all_set {
+                    title.push_str(&part);
+                }
+                rest = &after[end + 1..];
+            }
+            // Unclosed: the bracket is just text
+            None => {
+                title.push('[');
+                rest = after;
+            }
+        }
+    }
+    title.push_str(&render_part(rest, summary).0);
+    title
+}
+
+/// Render `text`, and whether every placeholder in it had a value
+fn render_part(text: &str, summary: &TraySummary) -> (String, bool) {
+    let mut out = String::new();
+    let mut all_set = true;
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let value = after
+            .find('}')
+            .and_then(|end| summary.placeholder(&after[1..end]).map(|v| (v, end)));
+        match value {
+            Some((value, end)) => {
+                if value.is_empty() || value == "0" {
+                    all_set = false;
+                }
+                out.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &after[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    (out, all_set)
+}
+
+/// Bundled tray icon variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayIcon {
+    /// Nothing running; the plain monochrome icon
+    Idle,
+    /// Some session thinking, running a tool or compacting
+    Working,
+    /// Some session waiting for input
+    Attention,
+    /// Some session's pull request checks are failing
+    Error,
+}
+
+impl TrayIcon {
+    /// The variant for the overall state. A waiting session outranks failing
+    /// checks, since it is blocked on the user.
+    pub fn for_summary(summary: &TraySummary) -> Self {
+        if summary.waiting > 0 {
+            TrayIcon::Attention
+        } else if summary.failing > 0 {
+            TrayIcon::Error
+        } else if summary.working() > 0 {
+            TrayIcon::Working
+        } else {
+            TrayIcon::Idle
+        }
+    }
+}
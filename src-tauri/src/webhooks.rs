@@ -0,0 +1,297 @@
+//! Outgoing webhooks for session status transitions, for phones and chat
+//! rooms when nobody is at the desk.
+//!
+//! Configured under `webhooks` in the global config. Each hook picks the
+//! transitions it cares about with the same triggers as notifications and
+//! posts one of three payloads:
+//!
+//! - `slack` - `{"text": ...}`
+//! - `discord` - `{"content": ...}`
+//! - `json` (default) - `{"event": "changed", "from", "to", "text", "timestamp", "session"}`
+//!   where `session` is the full session object as served by `/sessions`.
+//!   Automation rules post `{"event": "rule", "rule", "status", ...}` instead.
+//!
+//! `template` sets the text (see `render_template` for the placeholders).
+//! Failed deliveries (connection errors, 429 and 5xx) are retried with
+//! exponential backoff, and every attempt is appended to
+//! `~/.agent-sessions/webhooks.log`.
+
+use chrono::Utc;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::notifications::{Notification, NotificationSettings, NotificationTrigger};
+use crate::jsonl_log;
+use crate::session::{enum_name, Session, SessionEvent, SessionStatus};
+
+/// Per-request timeout, connecting included
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The `webhooks` section of the global config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub hooks: Vec<Webhook>,
+    /// Attempts per delivery, the first included
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for each one after
+    pub initial_backoff_ms: u64,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        WebhookSettings {
+            hooks: Vec::new(),
+            max_attempts: 4,
+            initial_backoff_ms: 2000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    /// Shown in the delivery log instead of the URL, which is often a secret
+    #[serde(default)]
+    pub name: Option<String>,
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Transitions that are posted; defaults to the notification defaults
+    #[serde(default = "default_triggers")]
+    pub on: Vec<NotificationTrigger>,
+    /// Project names or paths to post about; empty means all
+    #[serde(default)]
+    pub projects: Vec<String>,
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Extra request headers, e.g. for authentication
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+fn default_triggers() -> Vec<NotificationTrigger> {
+    NotificationSettings::default().on
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    #[default]
+    Json,
+    Slack,
+    Discord,
+}
+
+impl Webhook {
+    /// Name used in logs: `name`, or the URL's host
+    pub fn label(&self) -> String {
+        if let Some(name) = self.name.as_ref().filter(|n| !n.is_empty()) {
+            return name.clone();
+        }
+        let rest = self.url.split_once("://").map(|(_, r)| r).unwrap_or(&self.url);
+        rest.split(['/', '?']).next().unwrap_or(rest).to_string()
+    }
+
+    /// Whether a change of `session` from `from` should be posted
+    pub fn matches(&self, from: &SessionStatus, session: &Session) -> bool {
+        let project_ok = self.projects.is_empty()
+            || self
+                .projects
+                .iter()
+                .any(|p| p == &session.project_name || p == &session.project_path);
+        project_ok && self.on.iter().any(|t| t.matches(from, &session.status))
+    }
+
+    /// The request body for a change of `session` from `from`
+    pub fn payload(&self, from: &SessionStatus, session: &Session) -> String {
+        let text = render_template(self.template_or("{title}: {message}", session), session, Some(from), None);
+        self.body(
+            text,
+            serde_json::json!({ "event": "changed", "from": from, "to": session.status }),
+            session,
+        )
+    }
+
+    /// The request body for automation rule `rule` firing on `session`
+    pub fn rule_payload(&self, rule: &str, session: &Session) -> String {
+        let text = render_template(self.template_or("{rule}: {title}", session), session, None, Some(rule));
+        self.body(
+            text,
+            serde_json::json!({ "event": "rule", "rule": rule, "status": session.status }),
+            session,
+        )
+    }
+
+    /// `template`, or `default` with an empty `: {message}` dropped
+    fn template_or<'a>(&'a self, default: &'a str, session: &Session) -> &'a str {
+        match &self.template {
+            Some(t) => t,
+            None if session.last_message.as_deref().unwrap_or("").trim().is_empty() => {
+                default.strip_suffix(": {message}").unwrap_or(default)
+            }
+            None => default,
+        }
+    }
+
+    fn body(&self, text: String, event: serde_json::Value, session: &Session) -> String {
+        let payload = match self.format {
+            WebhookFormat::Slack => serde_json::json!({ "text": text }),
+            // Discord rejects content over 2000 characters
+            WebhookFormat::Discord => serde_json::json!({ "content": text.chars().take(2000).collect::<String>() }),
+            WebhookFormat::Json => {
+                let mut payload = event;
+                payload["text"] = text.into();
+                payload["timestamp"] = Utc::now().to_rfc3339().into();
+                payload["session"] = serde_json::to_value(session).unwrap_or_default();
+                payload
+            }
+        };
+        payload.to_string()
+    }
+}
+
+/// Fill the `{project}`, `{branch}`, `{status}`, `{message}`, `{session_id}`
+/// and `{title}` placeholders in `template`, plus `{from}` and `{rule}` when given
+pub fn render_template(template: &str, session: &Session, from: Option<&SessionStatus>, rule: Option<&str>) -> String {
+    let title = Notification::for_session(session).title;
+    let message = session.last_message.as_deref().map(str::trim).unwrap_or("");
+
+    template
+        .replace("{from}", &from.map(enum_name).unwrap_or_default())
+        .replace("{rule}", rule.unwrap_or(""))
+        .replace("{title}", &title)
+        .replace("{project}", &session.project_name)
+        .replace("{branch}", session.git_branch.as_deref().unwrap_or(""))
+        .replace("{status}", &enum_name(&session.status))
+        .replace("{session_id}", &session.id)
+        .replace("{message}", message)
+}
+
+/// One delivery attempt, as written to the delivery log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryAttempt {
+    /// RFC 3339 timestamp
+    pub at: String,
+    pub hook: String,
+    pub session_id: String,
+    /// e.g. "thinking -> waiting"
+    pub transition: String,
+    pub attempt: u32,
+    /// HTTP status, when a response arrived
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub delivered: bool,
+}
+
+/// Post `body` to `hook`, retrying as configured and logging every attempt
+/// to `log_path`. Returns the final HTTP status.
+pub fn deliver(
+    hook: &Webhook,
+    body: &str,
+    session_id: &str,
+    transition: &str,
+    settings: &WebhookSettings,
+    log_path: Option<&Path>,
+) -> Result<u16, String> {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+    let attempts = settings.max_attempts.max(1);
+    let mut backoff = Duration::from_millis(settings.initial_backoff_ms);
+    let mut last_error = String::new();
+
+    for attempt in 1..=attempts {
+        let mut request = agent.post(&hook.url).set("Content-Type", "application/json");
+        for (name, value) in &hook.headers {
+            request = request.set(name, value);
+        }
+
+        let (status, error) = match request.send_string(body) {
+            Ok(response) => (Some(response.status()), None),
+            Err(ureq::Error::Status(code, _)) => (Some(code), Some(format!("HTTP {}", code))),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let delivered = error.is_none();
+
+        if let Some(path) = log_path {
+            jsonl_log::append(
+                path,
+                &DeliveryAttempt {
+                    at: Utc::now().to_rfc3339(),
+                    hook: hook.label(),
+                    session_id: session_id.to_string(),
+                    transition: transition.to_string(),
+                    attempt,
+                    status,
+                    error: error.clone(),
+                    delivered,
+                },
+            );
+        }
+
+        if delivered {
+            return Ok(status.unwrap_or(200));
+        }
+        last_error = error.unwrap_or_default();
+
+        // Other client errors won't succeed on a retry
+        let retryable = status.map(|s| s == 429 || s >= 500).unwrap_or(true);
+        if !retryable || attempt == attempts {
+            break;
+        }
+        debug!("Webhook {} attempt {} failed ({}), retrying in {:?}", hook.label(), attempt, last_error, backoff);
+        thread::sleep(backoff);
+        backoff *= 2;
+    }
+
+    Err(last_error)
+}
+
+/// Path of the delivery log (`~/.agent-sessions/webhooks.log`)
+pub fn log_path() -> Option<PathBuf> {
+    crate::settings::app_dir().map(|d| d.join("webhooks.log"))
+}
+
+/// Post every matching hook for a session event, each on its own thread
+pub fn dispatch(event: &SessionEvent, settings: &WebhookSettings, log_path: Option<PathBuf>) -> Vec<thread::JoinHandle<()>> {
+    let (from, session) = match event {
+        SessionEvent::Changed { from, session } => (from, session),
+        _ => return Vec::new(),
+    };
+    let transition = format!("{} -> {}", enum_name(from), enum_name(&session.status));
+
+    settings
+        .hooks
+        .iter()
+        .filter(|hook| hook.matches(from, session))
+        .map(|hook| {
+            let body = hook.payload(from, session);
+            let hook = hook.clone();
+            let settings = settings.clone();
+            let session_id = session.id.clone();
+            let transition = transition.clone();
+            let log_path = log_path.clone();
+            thread::spawn(move || {
+                if let Err(e) = deliver(&hook, &body, &session_id, &transition, &settings, log_path.as_deref()) {
+                    warn!("Webhook {} failed for {}: {}", hook.label(), session_id, e);
+                }
+            })
+        })
+        .collect()
+}
+
+/// Post session events to the configured webhooks in the background
+pub fn start() {
+    let events = crate::api::subscribe();
+    thread::spawn(move || {
+        for event in events {
+            let settings = crate::settings::get().webhooks;
+            if !settings.hooks.is_empty() {
+                dispatch(&event, &settings, log_path());
+            }
+        }
+    });
+}
@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { Session, TerminalApp, ProjectLink } from '../types/session';
+import { Session, TerminalApp, ProjectLink, KillReport } from '../types/session';
 import { Card, CardContent } from '@/components/ui/card';
 import { Badge } from '@/components/ui/badge';
 import { Button } from '@/components/ui/button';
@@ -18,7 +18,7 @@ import {
   DialogFooter,
 } from '@/components/ui/dialog';
 import { Input } from '@/components/ui/input';
-import { formatTimeAgo, truncatePath, statusConfig } from '@/lib/formatters';
+import { formatTimeAgo, formatDuration, formatBytes, truncatePath, statusConfig } from '@/lib/formatters';
 import { openUrl } from '@tauri-apps/plugin-opener';
 import { invoke } from '@tauri-apps/api/core';
 
@@ -78,6 +78,22 @@ const terminalAppConfig: Record<TerminalApp, { label: string; icon: React.ReactN
       </svg>
     ),
   },
+  zed: {
+    label: 'Zed',
+    icon: (
+      <svg viewBox="0 0 24 24" className="w-4 h-4" fill="none" stroke="currentColor" strokeWidth="2">
+        <path d="M5 5h14L5 19h14" strokeLinecap="round" strokeLinejoin="round"/>
+      </svg>
+    ),
+  },
+  'vscode-extension': {
+    label: 'VS Code ext',
+    icon: (
+      <svg viewBox="0 0 24 24" className="w-4 h-4" fill="currentColor">
+        <path d="M17.583 2.243L12.34 7.576 7.5 3.896 3 5.849v12.353l4.5 1.953 4.84-3.68 5.243 5.333L21 20.23V3.821l-3.417-1.578zM7.5 15.572V8.479l4.84 3.546-4.84 3.547zM17.583 17.25L13.5 12.05l4.083-5.2v10.4z"/>
+      </svg>
+    ),
+  },
   unknown: null,
 };
 
@@ -226,7 +242,7 @@ function ProjectHeader({ session, children }: { session: Session; children?: Rea
                 invoke('launch_chrome', { projectName: session.projectName, projectPath: session.projectPath, url: link.url })
                   .catch(() => openUrl(link.url));
               }}
-              title={link.label}
+              title={link.detected ? `${link.label} (dev server)` : link.label}
             >
               <Favicon url={link.url} className="w-3 h-3" />
             </button>
@@ -256,15 +272,24 @@ function SessionMenu({ session, onRename, onSetUrl, onProjectLinks, onSessionLin
 
   const handleKillSession = async () => {
     try {
-      await invoke('kill_session', { pid: session.pid });
+      await invoke<KillReport>('kill_session', { pid: session.pid });
     } catch (error) {
       console.error('Failed to kill session:', error);
     }
   };
 
+  const handleTogglePause = async () => {
+    const command = session.status === 'paused' ? 'resume_session_process' : 'pause_session';
+    try {
+      await invoke<number[]>(command, { pid: session.pid });
+    } catch (error) {
+      console.error(`Failed to ${command === 'pause_session' ? 'pause' : 'resume'} session:`, error);
+    }
+  };
+
   const handleKillSessionAndCompanions = async () => {
     try {
-      await invoke('kill_session_and_companions', { pid: session.pid, projectPath: session.projectPath });
+      await invoke<KillReport>('kill_session_and_companions', { pid: session.pid, projectPath: session.projectPath });
     } catch (error) {
       console.error('Failed to kill session and companions:', error);
     }
@@ -352,6 +377,16 @@ function SessionMenu({ session, onRename, onSetUrl, onProjectLinks, onSessionLin
           </DropdownMenuItem>
         )}
         <DropdownMenuSeparator />
+        <DropdownMenuItem onClick={handleTogglePause}>
+          <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+            {session.status === 'paused' ? (
+              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M8 5v14l11-7L8 5z" />
+            ) : (
+              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M10 5v14M14 5v14" />
+            )}
+          </svg>
+          {session.status === 'paused' ? 'Resume Session' : 'Pause Session'}
+        </DropdownMenuItem>
         <DropdownMenuItem onClick={handleKillSession}>
           <svg className="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
             <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M6 18L18 6M6 6l12 12" />
@@ -683,6 +718,11 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
                     worktree
                   </span>
                 )}
+                {session.profile && session.profile !== 'default' && (
+                  <span className="shrink-0 text-[10px] px-1 py-0 rounded bg-purple-500/20 text-purple-300 border border-purple-500/30" title="Claude profile">
+                    {session.profile}
+                  </span>
+                )}
               </p>
             </div>
             <div className="flex items-center gap-1.5 shrink-0">
@@ -742,6 +782,15 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
                 {session.lastMessage}
               </div>
             )}
+            {session.childProcesses && session.childProcesses.length > 0 && (
+              <div
+                className="text-xs font-mono text-muted-foreground truncate mt-1.5"
+                title={`${session.childProcesses.map((c) => c.command).join('\n')}\n${Math.round(session.treeCpuUsage)}% CPU · ${formatBytes(session.memoryBytes)}`}
+              >
+                running: {session.childProcesses[0].command} ({formatDuration(session.childProcesses[0].runtimeSecs)})
+                {session.childProcesses.length > 1 && ` +${session.childProcesses.length - 1}`}
+              </div>
+            )}
           </div>
 
           {/* Footer: Status Badge + Terminal Icon + Time */}
@@ -814,7 +863,7 @@ export function SessionCard({ session, onClick }: SessionCardProps) {
         isOpen={isSessionLinksOpen}
         onClose={() => setIsSessionLinksOpen(false)}
         title="Session Links"
-        links={session.sessionLinks}
+        links={session.sessionLinks.filter((l) => !l.detected)}
         onSave={handleSaveSessionLinks}
       />
     </>
@@ -961,7 +1010,7 @@ function SessionSubCard({ session, onClick }: { session: Session; onClick: () =>
         isOpen={isSessionLinksOpen}
         onClose={() => setIsSessionLinksOpen(false)}
         title="Session Links"
-        links={session.sessionLinks}
+        links={session.sessionLinks.filter((l) => !l.detected)}
         onSave={handleSaveSessionLinks}
       />
     </>
@@ -14,30 +14,88 @@ interface SettingsProps {
   onClose: () => void;
 }
 
-const STORAGE_KEY = 'claude-sessions-hotkey';
-const DEFAULT_HOTKEY = 'Control+Space';
+// Single toggle hotkey saved by older versions
+const LEGACY_STORAGE_KEY = 'claude-sessions-hotkey';
+const STORAGE_KEY = 'claude-sessions-hotkeys';
+
+/** Mirrors `HotkeyBindings` in src-tauri/src/hotkeys.rs */
+interface HotkeyBindings {
+  toggleWindow: string | null;
+  nextWaiting: string | null;
+  /** Modifiers only; the backend binds them with 1-9 */
+  focusSession: string | null;
+  killFocused: string | null;
+}
+
+/** A binding the backend could not register */
+interface HotkeyConflict {
+  binding: keyof HotkeyBindings;
+  shortcut: string;
+  reason: string;
+}
+
+const DEFAULT_BINDINGS: HotkeyBindings = {
+  toggleWindow: 'Control+Space',
+  nextWaiting: null,
+  focusSession: null,
+  killFocused: null,
+};
+
+const HOTKEY_ROWS: { key: keyof HotkeyBindings; label: string; hint?: string }[] = [
+  { key: 'toggleWindow', label: 'Show / Hide Window' },
+  { key: 'nextWaiting', label: 'Next Waiting Session', hint: 'Longest-waiting first' },
+  { key: 'focusSession', label: 'Focus Session 1–9', hint: 'Record the modifiers with any key' },
+  { key: 'killFocused', label: 'Kill Focused Session', hint: 'Press twice to confirm' },
+];
+
+const MODIFIER_KEYS = ['Command', 'Control', 'Option', 'Shift'];
+
+function loadBindings(): HotkeyBindings {
+  const saved = localStorage.getItem(STORAGE_KEY);
+  if (saved) {
+    try {
+      return { ...DEFAULT_BINDINGS, ...JSON.parse(saved) };
+    } catch {
+      // Fall through to the defaults
+    }
+  }
+  const legacy = localStorage.getItem(LEGACY_STORAGE_KEY);
+  return legacy ? { ...DEFAULT_BINDINGS, toggleWindow: legacy } : DEFAULT_BINDINGS;
+}
+
+function displayBinding(key: keyof HotkeyBindings, shortcut: string | null): string | null {
+  if (!shortcut) return null;
+  return key === 'focusSession' ? `${shortcut}+1…9` : shortcut;
+}
 
 export function Settings({ isOpen, onClose }: SettingsProps) {
-  const [hotkey, setHotkey] = useState(DEFAULT_HOTKEY);
-  const [isRecording, setIsRecording] = useState(false);
+  const [bindings, setBindings] = useState<HotkeyBindings>(DEFAULT_BINDINGS);
+  const [recording, setRecording] = useState<keyof HotkeyBindings | null>(null);
   const [recordedKeys, setRecordedKeys] = useState<string[]>([]);
+  const [conflicts, setConflicts] = useState<HotkeyConflict[]>([]);
   const [error, setError] = useState<string | null>(null);
   const [saved, setSaved] = useState(false);
 
-  // Load saved hotkey on mount
+  // Load saved hotkeys on mount
   useEffect(() => {
-    const saved = localStorage.getItem(STORAGE_KEY);
-    if (saved) {
-      setHotkey(saved);
-    }
+    setBindings(loadBindings());
   }, []);
 
-  // Register hotkey with backend
-  const registerHotkey = useCallback(async (shortcut: string) => {
+  // Show bindings the startup registration couldn't take
+  useEffect(() => {
+    if (!isOpen) return;
+    invoke<HotkeyConflict[]>('get_hotkey_conflicts')
+      .then(setConflicts)
+      .catch(console.error);
+  }, [isOpen]);
+
+  // Register hotkeys with backend
+  const registerHotkeys = useCallback(async (next: HotkeyBindings) => {
     try {
-      await invoke('register_shortcut', { shortcut });
+      const found = await invoke<HotkeyConflict[]>('register_shortcuts', { bindings: next });
+      setConflicts(found);
       setError(null);
-      return true;
+      return found.length === 0;
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
       return false;
@@ -46,7 +104,8 @@ export function Settings({ isOpen, onClose }: SettingsProps) {
 
   // Handle key recording
   useEffect(() => {
-    if (!isRecording) return;
+    if (!recording) return;
+    const target = recording;
 
     const handleKeyDown = (e: KeyboardEvent) => {
       e.preventDefault();
@@ -79,10 +138,14 @@ export function Settings({ isOpen, onClose }: SettingsProps) {
       e.preventDefault();
 
       if (recordedKeys.length > 0 && !['Meta', 'Control', 'Alt', 'Shift'].includes(e.key)) {
-        // We have a complete shortcut
-        const shortcut = recordedKeys.join('+');
-        setHotkey(shortcut);
-        setIsRecording(false);
+        // We have a complete shortcut; focus-by-position keeps only its modifiers
+        const keys = target === 'focusSession'
+          ? recordedKeys.filter((k) => MODIFIER_KEYS.includes(k))
+          : recordedKeys;
+        if (keys.length === 0) return;
+
+        setBindings((current) => ({ ...current, [target]: keys.join('+') }));
+        setRecording(null);
         setRecordedKeys([]);
       }
     };
@@ -94,12 +157,13 @@ export function Settings({ isOpen, onClose }: SettingsProps) {
       window.removeEventListener('keydown', handleKeyDown);
       window.removeEventListener('keyup', handleKeyUp);
     };
-  }, [isRecording, recordedKeys]);
+  }, [recording, recordedKeys]);
 
   const handleSave = async () => {
-    const success = await registerHotkey(hotkey);
-    if (success) {
-      localStorage.setItem(STORAGE_KEY, hotkey);
+    // Saved even with conflicts, so they can be fixed one at a time
+    localStorage.setItem(STORAGE_KEY, JSON.stringify(bindings));
+    localStorage.removeItem(LEGACY_STORAGE_KEY);
+    if (await registerHotkeys(bindings)) {
       setSaved(true);
       setTimeout(() => setSaved(false), 2000);
     }
@@ -108,8 +172,11 @@ export function Settings({ isOpen, onClose }: SettingsProps) {
   const handleClear = async () => {
     try {
       await invoke('unregister_shortcut');
-      setHotkey('');
-      localStorage.removeItem(STORAGE_KEY);
+      const cleared: HotkeyBindings = { toggleWindow: null, nextWaiting: null, focusSession: null, killFocused: null };
+      setBindings(cleared);
+      setConflicts([]);
+      localStorage.setItem(STORAGE_KEY, JSON.stringify(cleared));
+      localStorage.removeItem(LEGACY_STORAGE_KEY);
       setError(null);
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
@@ -118,33 +185,69 @@ export function Settings({ isOpen, onClose }: SettingsProps) {
 
   return (
     <Dialog open={isOpen} onOpenChange={(open) => !open && onClose()}>
-      <DialogContent className="sm:max-w-[320px] gap-6">
+      <DialogContent className="sm:max-w-[360px] gap-6">
         <DialogHeader>
           <DialogTitle>Settings</DialogTitle>
         </DialogHeader>
 
-        <div className="space-y-3">
+        <div className="space-y-4">
           <label className="text-sm font-medium text-foreground">
-            Global Hotkey
+            Global Hotkeys
           </label>
-          <div
-            className={`flex items-center justify-center h-11 rounded-lg border cursor-pointer transition-colors ${
-              isRecording
-                ? 'border-foreground/50 bg-foreground/5'
-                : 'border-border bg-muted/50 hover:border-muted-foreground/50'
-            }`}
-            onClick={() => setIsRecording(true)}
-          >
-            <span className="text-sm text-foreground">
-              {isRecording ? (
-                recordedKeys.length > 0 ? recordedKeys.join(' + ') : 'Press keys...'
-              ) : (
-                hotkey || 'Click to set hotkey'
-              )}
-            </span>
-          </div>
+
+          {HOTKEY_ROWS.map(({ key, label, hint }) => {
+            const isRecording = recording === key;
+            const rowConflicts = conflicts.filter((c) => c.binding === key);
+            return (
+              <div key={key} className="space-y-1.5">
+                <div className="flex items-baseline justify-between">
+                  <span className="text-xs text-foreground">{label}</span>
+                  {hint && <span className="text-xs text-muted-foreground">{hint}</span>}
+                </div>
+                <div className="flex items-center gap-2">
+                  <div
+                    className={`flex flex-1 items-center justify-center h-9 rounded-lg border cursor-pointer transition-colors ${
+                      isRecording
+                        ? 'border-foreground/50 bg-foreground/5'
+                        : rowConflicts.length > 0
+                          ? 'border-destructive/50 bg-destructive/5'
+                          : 'border-border bg-muted/50 hover:border-muted-foreground/50'
+                    }`}
+                    onClick={() => {
+                      setRecordedKeys([]);
+                      setRecording(key);
+                    }}
+                  >
+                    <span className="text-sm text-foreground">
+                      {isRecording ? (
+                        recordedKeys.length > 0 ? recordedKeys.join(' + ') : 'Press keys...'
+                      ) : (
+                        displayBinding(key, bindings[key]) || 'Click to set hotkey'
+                      )}
+                    </span>
+                  </div>
+                  {bindings[key] && (
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      title="Remove hotkey"
+                      onClick={() => setBindings((current) => ({ ...current, [key]: null }))}
+                    >
+                      ×
+                    </Button>
+                  )}
+                </div>
+                {rowConflicts.map((conflict) => (
+                  <p key={conflict.shortcut} className="text-xs text-destructive">
+                    {conflict.shortcut}: {conflict.reason}
+                  </p>
+                ))}
+              </div>
+            );
+          })}
+
           <p className="text-xs text-muted-foreground">
-            Click and press your desired key combination
+            Click a field and press your desired key combination
           </p>
 
           {error && (
@@ -155,7 +258,7 @@ export function Settings({ isOpen, onClose }: SettingsProps) {
 
           {saved && (
             <div className="p-3 rounded-lg bg-emerald-400/10 border border-emerald-400/20 text-emerald-400 text-sm">
-              Hotkey saved
+              Hotkeys saved
             </div>
           )}
         </div>
@@ -175,9 +278,8 @@ export function Settings({ isOpen, onClose }: SettingsProps) {
 
 export function useHotkeyInit() {
   useEffect(() => {
-    const savedHotkey = localStorage.getItem(STORAGE_KEY);
-    if (savedHotkey) {
-      invoke('register_shortcut', { shortcut: savedHotkey }).catch(console.error);
-    }
+    if (!localStorage.getItem(STORAGE_KEY) && !localStorage.getItem(LEGACY_STORAGE_KEY)) return;
+    // Conflicts are kept by the backend and shown when Settings opens
+    invoke<HotkeyConflict[]>('register_shortcuts', { bindings: loadBindings() }).catch(console.error);
   }, []);
 }
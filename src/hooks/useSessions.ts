@@ -14,6 +14,7 @@ function getOrderingPriority(status: string): number {
     case 'waiting':
       return 0; // All active/attention states - same ordering priority
     case 'idle':
+    case 'paused':
       return 1; // Only idle causes reordering
     default:
       return 2;
@@ -84,33 +85,26 @@ export function useSessions() {
   const [error, setError] = useState<string | null>(null);
   const sessionsRef = useRef<Session[]>([]);
 
-  const updateTrayTitle = useCallback(async (total: number, waiting: number) => {
-    try {
-      await invoke('update_tray_title', { total, waiting });
-    } catch (err) {
-      console.error('Failed to update tray title:', err);
-    }
-  }, []);
-
   const fetchSessions = useCallback(async () => {
     try {
       const response = await invoke<SessionsResponse>('get_all_sessions');
       // Merge with stable ordering to prevent unnecessary reordering
       const stableSessions = mergeWithStableOrder(sessionsRef.current, response.sessions);
+      // Hotkeys and the tray number sessions the way this list shows them
+      if (stableSessions.map(s => s.id).join() !== sessionsRef.current.map(s => s.id).join()) {
+        invoke('set_session_order', { ids: stableSessions.map(s => s.id) }).catch(console.error);
+      }
       sessionsRef.current = stableSessions;
       setSessions([...stableSessions]);
       setTotalCount(response.totalCount);
       setWaitingCount(response.waitingCount);
       setError(null);
-
-      // Update tray icon title with counts
-      await updateTrayTitle(response.totalCount, response.waitingCount);
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to fetch sessions');
     } finally {
       setIsLoading(false);
     }
-  }, [updateTrayTitle]);
+  }, []);
 
   const focusSession = useCallback(async (session: Session) => {
     try {
@@ -14,6 +14,21 @@ export function formatTimeAgo(timestamp: string): string {
   return `${diffDays}d ago`;
 }
 
+export function formatDuration(totalSecs: number): string {
+  const hours = Math.floor(totalSecs / 3600);
+  const mins = Math.floor((totalSecs % 3600) / 60);
+  const secs = totalSecs % 60;
+
+  if (hours > 0) return `${hours}h${String(mins).padStart(2, '0')}m`;
+  if (mins > 0) return `${mins}m${String(secs).padStart(2, '0')}s`;
+  return `${secs}s`;
+}
+
+export function formatBytes(bytes: number): string {
+  if (bytes >= 1024 ** 3) return `${(bytes / 1024 ** 3).toFixed(1)} GB`;
+  return `${Math.round(bytes / 1024 ** 2)} MB`;
+}
+
 export function truncatePath(path: string): string {
   return path.replace(/^\/Users\/[^/]+/, '~');
 }
@@ -59,4 +74,12 @@ export const statusConfig = {
     badgeClassName: 'border-white/20 text-white/50 bg-white/5',
     label: 'Idle',
   },
+  paused: {
+    color: 'bg-sky-400',
+    fillColor: 'fill-sky-400',
+    cardBg: 'bg-sky-400/10',
+    cardBorder: 'border-sky-400/30',
+    badgeClassName: 'border-sky-400/40 text-sky-300 bg-sky-400/20',
+    label: 'Paused',
+  },
 } as const;
@@ -1,8 +1,8 @@
-export type SessionStatus = 'waiting' | 'processing' | 'thinking' | 'compacting' | 'idle';
+export type SessionStatus = 'waiting' | 'processing' | 'thinking' | 'compacting' | 'idle' | 'paused';
 
 export type AgentType = 'claude' | 'opencode';
 
-export type TerminalApp = 'iterm2' | 'warp' | 'cursor' | 'vscode' | 'terminal' | 'tmux' | 'unknown';
+export type TerminalApp = 'iterm2' | 'warp' | 'cursor' | 'vscode' | 'terminal' | 'tmux' | 'zed' | 'vscode-extension' | 'unknown';
 
 export type CiStatus = 'success' | 'failure' | 'pending' | 'unknown';
 
@@ -17,6 +17,8 @@ export interface ProjectLink {
   label: string;
   url: string;
   icon?: string | null;
+  /** Found from a dev server's listening port rather than saved by the user */
+  detected?: boolean;
 }
 
 export interface Session {
@@ -42,6 +44,20 @@ export interface Session {
   contextWindowPercent: number | null;
   projectLinks: ProjectLink[];
   sessionLinks: ProjectLink[];
+  /** Claude config profile ("default" for ~/.claude) */
+  profile?: string | null;
+  /** CPU of the agent plus all descendant processes */
+  treeCpuUsage: number;
+  /** Resident memory of the agent plus all descendant processes, in bytes */
+  memoryBytes: number;
+  /** Commands running under the agent, most recently started first */
+  childProcesses: ChildProcess[];
+}
+
+export interface ChildProcess {
+  pid: number;
+  command: string;
+  runtimeSecs: number;
 }
 
 export interface SessionsResponse {
@@ -49,3 +65,37 @@ export interface SessionsResponse {
   totalCount: number;
   waitingCount: number;
 }
+
+export interface SiblingOverride {
+  file: string;
+  status: SessionStatus;
+}
+
+export interface StatusExplanation {
+  sessionId: string;
+  jsonlPath: string;
+  lastMsgType: string | null;
+  lastRole: string | null;
+  hasToolUse: boolean;
+  hasToolResult: boolean;
+  isLocalCommand: boolean;
+  isInterrupted: boolean;
+  isCompacting: boolean;
+  fileAgeSecs: number | null;
+  cpuUsage: number;
+  contentStatus: SessionStatus;
+  activeSubagentCount: number;
+  subagentOverride: boolean;
+  siblingOverride: SiblingOverride | null;
+  rawStatus: SessionStatus;
+  finalStatus: SessionStatus;
+  decisionPath: string[];
+}
+
+/** PIDs taken down by `kill_session` */
+export interface KillReport {
+  /** Exited within the grace period */
+  terminated: number[];
+  /** Sent SIGKILL after the grace period */
+  forceKilled: number[];
+}